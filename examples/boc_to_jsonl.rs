@@ -0,0 +1,263 @@
+/*
+ * Copyright (C) 2019-2023 EverX. All Rights Reserved.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.  You may obtain a copy of the
+ * License at:
+ *
+ * https://www.ever.dev/licenses
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific EVERX DEV software governing permissions and limitations
+ * under the License.
+ */
+
+//! Converts block BOC files of the directory into JSON Lines files per entity kind:
+//! `blocks.jsonl`, `transactions.jsonl`, `messages.jsonl`, `account_transactions.jsonl`
+//! and `account_creations.jsonl`.
+//!
+//! ```text
+//! cargo run --features examples --example boc_to_jsonl -- <input dir> <output dir>
+//!     [--watch] [--kinds blocks,transactions,messages] [--sharding <depth>]
+//!     [--fields <kind>=<reducer config file>]...
+//! ```
+//!
+//! With `--watch` the input directory is polled for new files, otherwise the files existing
+//! at start are converted. Files failed to convert are retried on the next poll.
+//! Output files are rewritten on every run. `--fields` sets `JsonFieldsReducer` config
+//! for the entity kind.
+//!
+//! The example is tested with `cargo test --features examples --example boc_to_jsonl`.
+
+use ever_block::{
+    fail, read_single_root_boc, Block, BlockIdExt, Deserializable, Result, UInt256,
+};
+use ever_block_json::{
+    BlockParser, BlockParserConfig, EntryConfig, JsonFieldsReducer, NoTrace, ParsedEntry,
+    ParsingBlock,
+};
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+const KINDS: [&str; 5] = [
+    "blocks", "transactions", "messages", "account_transactions", "account_creations"
+];
+
+struct Args {
+    input: PathBuf,
+    output: PathBuf,
+    watch: bool,
+    kinds: Vec<String>,
+    sharding_depth: Option<u32>,
+    fields: HashMap<String, String>,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut args = std::env::args().skip(1);
+    let mut positional = Vec::new();
+    let mut watch = false;
+    let mut kinds = vec!["blocks".to_string(), "transactions".to_string(), "messages".to_string()];
+    let mut sharding_depth = None;
+    let mut fields = HashMap::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--watch" => watch = true,
+            "--kinds" => {
+                let value = args.next().ok_or_else(|| ever_block::error!("--kinds needs a value"))?;
+                kinds = value.split(',').map(|kind| kind.trim().to_string()).collect();
+            }
+            "--sharding" => {
+                let value = args.next().ok_or_else(|| ever_block::error!("--sharding needs a value"))?;
+                sharding_depth = Some(value.parse()?);
+            }
+            "--fields" => {
+                let value = args.next().ok_or_else(|| ever_block::error!("--fields needs a value"))?;
+                let Some((kind, path)) = value.split_once('=') else {
+                    fail!("--fields value must be <kind>=<reducer config file>")
+                };
+                fields.insert(kind.to_string(), std::fs::read_to_string(path)?);
+            }
+            _ => positional.push(arg),
+        }
+    }
+    for kind in &kinds {
+        if !KINDS.contains(&kind.as_str()) {
+            fail!("unknown entity kind {}, expected one of {:?}", kind, KINDS)
+        }
+    }
+    let [input, output]: [String; 2] = positional.try_into()
+        .map_err(|_| ever_block::error!("usage: boc_to_jsonl <input dir> <output dir> [options]"))?;
+    Ok(Args { input: input.into(), output: output.into(), watch, kinds, sharding_depth, fields })
+}
+
+fn entry_config(args: &Args, kind: &str) -> Result<Option<EntryConfig<JsonFieldsReducer>>> {
+    if !args.kinds.iter().any(|k| k == kind) {
+        return Ok(None)
+    }
+    let reducer = match args.fields.get(kind) {
+        Some(config) => Some(JsonFieldsReducer::with_config(config)?),
+        None => None,
+    };
+    Ok(Some(EntryConfig { sharding_depth: args.sharding_depth, reducer }))
+}
+
+struct Output {
+    files: HashMap<&'static str, BufWriter<File>>,
+}
+
+impl Output {
+    fn new(dir: &Path, kinds: &[String]) -> Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let mut files = HashMap::new();
+        for kind in KINDS.iter().filter(|kind| kinds.iter().any(|k| k == *kind)) {
+            let file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(dir.join(format!("{}.jsonl", kind)))?;
+            files.insert(*kind, BufWriter::new(file));
+        }
+        Ok(Self { files })
+    }
+
+    fn write<'a>(&mut self, kind: &str, entries: impl IntoIterator<Item = &'a ParsedEntry>) -> Result<()> {
+        if let Some(file) = self.files.get_mut(kind) {
+            for entry in entries {
+                serde_json::to_writer(&mut *file, &entry.body)?;
+                file.write_all(b"\n")?;
+            }
+            file.flush()?;
+        }
+        Ok(())
+    }
+}
+
+fn convert(
+    parser: &BlockParser<NoTrace, JsonFieldsReducer>,
+    path: &Path,
+    output: &mut Output,
+) -> Result<()> {
+    let boc = std::fs::read(path)?;
+    let root = read_single_root_boc(&boc)?;
+    let block = Block::construct_from_cell(root.clone())?;
+    let info = block.read_info()?;
+    let id = BlockIdExt::with_params(
+        info.shard().clone(),
+        info.seq_no(),
+        root.repr_hash(),
+        UInt256::calc_file_hash(&boc),
+    );
+    let parsed = parser.parse(ParsingBlock::new(&id, &block, &root, &boc), false)?;
+    output.write("blocks", parsed.block.iter())?;
+    output.write("transactions", parsed.transactions.iter())?;
+    output.write("messages", parsed.messages.iter())?;
+    output.write("account_transactions", parsed.account_transactions.iter())?;
+    output.write("account_creations", parsed.account_creations.iter())?;
+    Ok(())
+}
+
+/// Converts files of the input directory not converted yet, failed files are left
+/// for the next call
+fn convert_new(
+    parser: &BlockParser<NoTrace, JsonFieldsReducer>,
+    input: &Path,
+    output: &mut Output,
+    converted: &mut HashSet<PathBuf>,
+) -> Result<()> {
+    let mut paths = std::fs::read_dir(input)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    paths.retain(|path| path.extension().map_or(false, |ext| ext == "boc"));
+    paths.sort();
+    for path in paths {
+        if converted.contains(&path) {
+            continue
+        }
+        match convert(parser, &path, output) {
+            Ok(()) => {
+                converted.insert(path);
+            }
+            Err(err) => eprintln!("{}: {}", path.display(), err),
+        }
+    }
+    Ok(())
+}
+
+fn create_parser(args: &Args) -> Result<BlockParser<NoTrace, JsonFieldsReducer>> {
+    let mut config = BlockParserConfig::default();
+    config.blocks = entry_config(args, "blocks")?;
+    config.transactions = entry_config(args, "transactions")?;
+    config.messages = entry_config(args, "messages")?;
+    config.account_transactions = entry_config(args, "account_transactions")?;
+    config.account_creations = entry_config(args, "account_creations")?;
+    Ok(BlockParser::<NoTrace, JsonFieldsReducer>::new(config, None))
+}
+
+fn main() -> Result<()> {
+    let args = parse_args()?;
+    let parser = create_parser(&args)?;
+    let mut output = Output::new(&args.output, &args.kinds)?;
+    let mut converted = HashSet::new();
+    loop {
+        convert_new(&parser, &args.input, &mut output, &mut converted)?;
+        if !args.watch {
+            return Ok(())
+        }
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_lines(path: &Path) -> usize {
+        std::fs::read_to_string(path).unwrap().lines().count()
+    }
+
+    #[test]
+    fn test_convert_new() {
+        let dir = std::env::temp_dir().join(format!("boc_to_jsonl_{}", std::process::id()));
+        let (input, output_dir) = (dir.join("input"), dir.join("output"));
+        std::fs::create_dir_all(&input).unwrap();
+        let block = "3FAFAAB7B5D17E439CD9DDEF7EEC3C3BC9D28E7C181BA374218E13599F8F6657.boc";
+        std::fs::copy(Path::new("src/tests/data").join(block), input.join(block)).unwrap();
+        std::fs::write(input.join("broken.boc"), b"not a boc").unwrap();
+
+        let args = Args {
+            input: input.clone(),
+            output: output_dir.clone(),
+            watch: false,
+            kinds: vec!["blocks".to_string(), "transactions".to_string()],
+            sharding_depth: None,
+            fields: HashMap::new(),
+        };
+        let parser = create_parser(&args).unwrap();
+        let mut converted = HashSet::new();
+        let mut output = Output::new(&output_dir, &args.kinds).unwrap();
+        convert_new(&parser, &input, &mut output, &mut converted).unwrap();
+        assert_eq!(converted, HashSet::from([input.join(block)]));
+        assert_eq!(count_lines(&output_dir.join("blocks.jsonl")), 1);
+        let transactions = count_lines(&output_dir.join("transactions.jsonl"));
+        assert!(transactions > 0);
+        assert!(!output_dir.join("messages.jsonl").exists());
+
+        // the broken file is retried, converted files are not
+        std::fs::copy(input.join(block), input.join("broken.boc")).unwrap();
+        convert_new(&parser, &input, &mut output, &mut converted).unwrap();
+        assert_eq!(converted.len(), 2);
+        assert_eq!(count_lines(&output_dir.join("blocks.jsonl")), 2);
+
+        // rerun rewrites the output
+        let mut output = Output::new(&output_dir, &args.kinds).unwrap();
+        convert_new(&parser, &input, &mut output, &mut HashSet::new()).unwrap();
+        assert_eq!(count_lines(&output_dir.join("blocks.jsonl")), 2);
+        assert_eq!(count_lines(&output_dir.join("transactions.jsonl")), transactions * 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,20 @@
+#![no_main]
+
+use ever_block::{Account, Deserializable};
+use ever_block_json::{db_serialize_account, parse_account, AccountSerializationSet};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(account) = Account::construct_from_bytes(data) else { return };
+    let address = account.get_addr().map(|addr| addr.to_string());
+    let set = AccountSerializationSet {
+        account,
+        prev_code_hash: None,
+        boc: data.to_vec(),
+        boc1: None,
+        proof: None,
+    };
+    let Ok(map) = db_serialize_account("id", &set) else { return };
+    assert_eq!(map.get("id").and_then(|id| id.as_str()), address.as_deref());
+    assert_eq!(parse_account(&map).unwrap(), set.account);
+});
@@ -0,0 +1,24 @@
+#![no_main]
+
+use ever_block::{base64_encode, read_single_root_boc, Block, BlockProcessingStatus, Deserializable};
+use ever_block_json::{db_serialize_block, parse_block_prev_refs, BlockSerializationSetFH};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(cell) = read_single_root_boc(data) else { return };
+    let Ok(block) = Block::construct_from_cell(cell.clone()) else { return };
+    let id = cell.repr_hash();
+    let set = BlockSerializationSetFH {
+        block: &block,
+        id: &id,
+        status: BlockProcessingStatus::Finalized,
+        boc: data,
+        file_hash: None,
+    };
+    let Ok(map) = db_serialize_block("id", set) else { return };
+    let info = block.read_info().unwrap();
+    assert_eq!(map["boc"], base64_encode(data));
+    assert_eq!(map["seq_no"], info.seq_no());
+    assert_eq!(map["workchain_id"], info.shard().workchain_id());
+    assert_eq!(parse_block_prev_refs(&map).unwrap().0, info.read_prev_ref().unwrap());
+});
@@ -0,0 +1,23 @@
+#![no_main]
+
+use ever_block::{read_single_root_boc, Deserializable, Transaction, TransactionProcessingStatus};
+use ever_block_json::{db_serialize_transaction, parse_transaction, TransactionSerializationSetEx};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(cell) = read_single_root_boc(data) else { return };
+    let Ok(transaction) = Transaction::construct_from_cell(cell.clone()) else { return };
+    let id = cell.repr_hash();
+    let set = TransactionSerializationSetEx {
+        transaction: &transaction,
+        id: &id,
+        status: TransactionProcessingStatus::Finalized,
+        block_id: None,
+        workchain_id: None,
+        boc: data,
+        proof: None,
+    };
+    let Ok(map) = db_serialize_transaction("id", set) else { return };
+    assert_eq!(map["outmsg_cnt"], transaction.msg_count());
+    assert_eq!(parse_transaction(&map).unwrap(), transaction);
+});
@@ -5,7 +5,7 @@ use crate::{
     ParsingBlock,
 };
 use std::collections::{HashMap, HashSet};
-use ever_block::{Account, Deserializable, Serializable, ShardAccounts, Transaction};
+use ever_block::{Account, AccountStatus, Deserializable, Serializable, ShardAccounts, Transaction};
 use ever_block::{fail, AccountId, Cell, ExceptionCode, SliceData, UInt256};
 use ever_block::{write_boc, BuilderData, Result};
 
@@ -85,6 +85,7 @@ impl<'a, R: JsonReducer> ParserAccounts<'a, R> {
             ))?
         };
         let shard_accounts = shard_state.read_accounts()?;
+        let time_anchor = crate::LtTimeAnchor::from_block_info(&self.parsing.block.read_info()?);
         for account_id in self.changed.iter() {
             let acc = shard_accounts.account(account_id)?.ok_or_else(|| {
                 BlockParsingError::InvalidData(
@@ -99,7 +100,9 @@ impl<'a, R: JsonReducer> ParserAccounts<'a, R> {
             result.accounts.push(Self::prepare_account_entry(
                 acc,
                 self.get_code_hash_from(UpdateSide::Old, account_id)?,
+                self.was_uninit(account_id)?,
                 last_trans_chain_order,
+                Some(&time_anchor),
                 self.max_account_bytes_size,
                 self.accounts_sharding_depth,
                 self.accounts_config,
@@ -174,6 +177,27 @@ impl<'a, R: JsonReducer> ParserAccounts<'a, R> {
         )
     }
 
+    /// True if the old state of the block is available and the account did not exist or was
+    /// not initialized in it, so the account has no previous code
+    fn was_uninit(&self, id: &AccountId) -> Result<bool> {
+        let Some((old_accounts, _)) = &self.update else {
+            return Ok(false);
+        };
+        let acc = old_accounts.account(id);
+        if let Err(err) = &acc {
+            if let Some(ExceptionCode::PrunedCellAccess) = err.downcast_ref::<ExceptionCode>() {
+                return Ok(false);
+            }
+        }
+        Ok(match acc? {
+            Some(acc) => {
+                let account = acc.read_account()?;
+                account.is_none() || account.status() == AccountStatus::AccStateUninit
+            }
+            None => true,
+        })
+    }
+
     fn get_code_hash_from(&self, source: UpdateSide, id: &AccountId) -> Result<Option<UInt256>> {
         let acc = if let Some(updates) = &self.update {
             let accounts = match source {
@@ -201,7 +225,9 @@ impl<'a, R: JsonReducer> ParserAccounts<'a, R> {
     pub(crate) fn prepare_account_entry(
         account: Account,
         prev_code_hash: Option<UInt256>,
+        prev_uninit: bool,
         last_trans_chain_order: Option<String>,
+        time_anchor: Option<&crate::LtTimeAnchor>,
         max_account_bytes_size: Option<usize>,
         accounts_sharding_depth: u32,
         accounts_config: &Option<EntryConfig<R>>,
@@ -240,6 +266,12 @@ impl<'a, R: JsonReducer> ParserAccounts<'a, R> {
             Some(id) => id,
             None => fail!("Account without id in external db processor"),
         };
+        // known only if the previous state of the account is available
+        let code_changed = match &prev_code_hash {
+            Some(prev_code_hash) => Some(account.get_code_hash().as_ref() != Some(prev_code_hash)),
+            None if prev_uninit => Some(account.get_code_hash().is_some()),
+            None => None,
+        };
         let set = crate::AccountSerializationSet {
             account,
             prev_code_hash,
@@ -250,7 +282,18 @@ impl<'a, R: JsonReducer> ParserAccounts<'a, R> {
         };
 
         let partition = get_partition(accounts_sharding_depth, account_id.clone())?;
-        let mut doc = crate::db_serialize_account("id", &set)?;
+        let mut doc = match time_anchor {
+            Some(anchor) => crate::db_serialize_account_with_time_anchor(
+                "id",
+                &set,
+                anchor,
+                crate::SerializationMode::Standart,
+            )?,
+            None => crate::db_serialize_account("id", &set)?,
+        };
+        if let Some(code_changed) = code_changed {
+            doc.insert("code_changed".to_owned(), code_changed.into());
+        }
         if let Some(last_trans_chain_order) = last_trans_chain_order {
             doc.insert(
                 "last_trans_chain_order".to_owned(),
@@ -1,13 +1,29 @@
+use crate::block_parser::chunks::{ChunkLimits, ParsedChunks, ParsedEntryKind};
 use crate::block_parser::entry::ParsedEntry;
 use ever_block::{Block, BlockIdExt, BlockProof, ShardStateUnsplit};
 use ever_block::Cell;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
+#[derive(Serialize, Deserialize)]
 pub struct ParsedBlock {
     pub block: Option<ParsedEntry>,
     pub proof: Option<ParsedEntry>,
     pub accounts: Vec<ParsedEntry>,
     pub transactions: Vec<ParsedEntry>,
     pub messages: Vec<ParsedEntry>,
+    pub account_transactions: Vec<ParsedEntry>,
+    pub account_creations: Vec<ParsedEntry>,
+    pub economics: Option<ParsedEntry>,
+    /// Counts of entries before sampling, set only if the parser is configured with sampling.
+    pub counts: Option<ParsedCounts>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParsedCounts {
+    pub accounts: usize,
+    pub transactions: usize,
+    pub messages: usize,
 }
 
 impl ParsedBlock {
@@ -18,10 +34,93 @@ impl ParsedBlock {
             accounts: Vec::new(),
             transactions: Vec::new(),
             messages: Vec::new(),
+            account_transactions: Vec::new(),
+            account_creations: Vec::new(),
+            economics: None,
+            counts: None,
+        }
+    }
+
+    /// Splits the entries into chunks bounded by `limits`: the block, the proof, accounts,
+    /// transactions, messages, account transactions, account creations and economics
+    /// in this order.
+    pub fn into_chunks(mut self, limits: ChunkLimits) -> ParsedChunks<'static> {
+        ParsedChunks::new(limits, move |kind| Ok(self.take_entries(kind)))
+    }
+
+    /// Moves out the entries of the kind
+    pub(crate) fn take_entries(&mut self, kind: ParsedEntryKind) -> Vec<ParsedEntry> {
+        match kind {
+            ParsedEntryKind::Block => self.block.take().into_iter().collect(),
+            ParsedEntryKind::Proof => self.proof.take().into_iter().collect(),
+            ParsedEntryKind::Account => std::mem::take(&mut self.accounts),
+            ParsedEntryKind::Transaction => std::mem::take(&mut self.transactions),
+            ParsedEntryKind::Message => std::mem::take(&mut self.messages),
+            ParsedEntryKind::AccountTransactions => std::mem::take(&mut self.account_transactions),
+            ParsedEntryKind::AccountCreation => std::mem::take(&mut self.account_creations),
+            ParsedEntryKind::Economics => self.economics.take().into_iter().collect(),
+        }
+    }
+
+    /// Leaves only accounts, transactions and messages with `sample_key(id) % rate == 0`
+    /// and remembers the counts of all entries.
+    pub(crate) fn sample(&mut self, rate: u64) {
+        self.counts = Some(ParsedCounts {
+            accounts: self.accounts.len(),
+            transactions: self.transactions.len(),
+            messages: self.messages.len(),
+        });
+        if rate > 1 {
+            for entries in [&mut self.accounts, &mut self.transactions, &mut self.messages] {
+                entries.retain(|entry| sample_key(&entry.id) % rate == 0);
+            }
         }
     }
+
+    /// Adds the `ingestion` object with `metadata` into every entry
+    pub(crate) fn insert_metadata(&mut self, metadata: &Map<String, Value>) {
+        let entries = self.block.iter_mut()
+            .chain(self.proof.iter_mut())
+            .chain(self.accounts.iter_mut())
+            .chain(self.transactions.iter_mut())
+            .chain(self.messages.iter_mut())
+            .chain(self.account_transactions.iter_mut())
+            .chain(self.account_creations.iter_mut())
+            .chain(self.economics.iter_mut());
+        for entry in entries {
+            entry.body.insert("ingestion".to_string(), metadata.clone().into());
+        }
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl ParsedBlock {
+    /// Packs the parsed block into CBOR to pass it to another process
+    pub fn to_cbor(&self) -> ever_block::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        ciborium::ser::into_writer(self, &mut data)?;
+        Ok(data)
+    }
+
+    pub fn from_cbor(data: &[u8]) -> ever_block::Result<Self> {
+        Ok(ciborium::de::from_reader(data)?)
+    }
 }
 
+/// Sampling key of the entry: ids of entries are hashes or addresses, so the last 64 bits
+/// of the hex id are used. Other ids are hashed with FNV-1a.
+pub(crate) fn sample_key(id: &str) -> u64 {
+    let tail = id.get(id.len().saturating_sub(16)..).unwrap_or_default();
+    u64::from_str_radix(tail, 16).unwrap_or_else(|_| {
+        id.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+    })
+}
+
+/// Block to parse with the optional data around it. Created with `ParsingBlock::new`,
+/// the optional data is set with the `with_*` methods.
+#[non_exhaustive]
 pub struct ParsingBlock<'a> {
     pub id: &'a BlockIdExt,
     pub block: &'a Block,
@@ -29,6 +128,53 @@ pub struct ParsingBlock<'a> {
     pub data: &'a [u8],
 
     pub mc_seq_no: Option<u32>,
+    /// Masterchain block which commits the shard block, if known. Referenced in `master`
+    /// section of shard block documents together with `mc_seq_no`.
+    pub mc_block_id: Option<&'a BlockIdExt>,
     pub proof: Option<&'a BlockProof>,
     pub shard_state: Option<&'a ShardStateUnsplit>,
+    /// Shard state before the block, if known. Its outbound queue is used to resolve
+    /// messages dequeued by the block in `out_msg_descr` of the block document.
+    pub prev_shard_state: Option<&'a ShardStateUnsplit>,
+}
+
+impl<'a> ParsingBlock<'a> {
+    pub fn new(id: &'a BlockIdExt, block: &'a Block, root: &'a Cell, data: &'a [u8]) -> Self {
+        Self {
+            id,
+            block,
+            root,
+            data,
+            mc_seq_no: None,
+            mc_block_id: None,
+            proof: None,
+            shard_state: None,
+            prev_shard_state: None,
+        }
+    }
+
+    pub fn with_mc_seq_no(mut self, mc_seq_no: Option<u32>) -> Self {
+        self.mc_seq_no = mc_seq_no;
+        self
+    }
+
+    pub fn with_mc_block_id(mut self, mc_block_id: Option<&'a BlockIdExt>) -> Self {
+        self.mc_block_id = mc_block_id;
+        self
+    }
+
+    pub fn with_proof(mut self, proof: Option<&'a BlockProof>) -> Self {
+        self.proof = proof;
+        self
+    }
+
+    pub fn with_shard_state(mut self, shard_state: Option<&'a ShardStateUnsplit>) -> Self {
+        self.shard_state = shard_state;
+        self
+    }
+
+    pub fn with_prev_shard_state(mut self, prev_shard_state: Option<&'a ShardStateUnsplit>) -> Self {
+        self.prev_shard_state = prev_shard_state;
+        self
+    }
 }
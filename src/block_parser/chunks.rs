@@ -0,0 +1,125 @@
+use crate::block_parser::entry::ParsedEntry;
+use ever_block::Result;
+use std::collections::VecDeque;
+
+/// Collection of the parsed entry
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParsedEntryKind {
+    Block,
+    Proof,
+    Account,
+    Transaction,
+    Message,
+    AccountTransactions,
+    AccountCreation,
+    Economics,
+}
+
+/// Bounds of a chunk, a chunk always has at least one entry even if the entry is bigger
+/// than `max_bytes`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChunkLimits {
+    pub max_entries: usize,
+    /// Sum of serialized sizes of the entries
+    pub max_bytes: usize,
+}
+
+impl Default for ChunkLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: 1000,
+            max_bytes: 8 * 1024 * 1024,
+        }
+    }
+}
+
+/// Entries of the same kind taken from the parsed block
+pub struct ParsedChunk {
+    pub kind: ParsedEntryKind,
+    pub entries: Vec<ParsedEntry>,
+    /// Serialized size of the entries
+    pub bytes: usize,
+}
+
+type EntrySource<'a> = Box<dyn FnMut(ParsedEntryKind) -> Result<Vec<ParsedEntry>> + 'a>;
+
+/// Entries of the block split into chunks, see `BlockParser::parse_chunked` and
+/// `ParsedBlock::into_chunks`. Entries are taken by kinds in the order of `ParsedEntryKind`:
+/// the entries of a kind are generated when the previous kind is fully returned, and every
+/// `next_chunk` call moves them out, so only one kind of entries is held at a time.
+pub struct ParsedChunks<'a> {
+    limits: ChunkLimits,
+    kinds: VecDeque<ParsedEntryKind>,
+    current: Option<(ParsedEntryKind, VecDeque<ParsedEntry>)>,
+    source: EntrySource<'a>,
+}
+
+/// Kinds of the entries in the order they are returned by `ParsedChunks`
+pub(crate) const ENTRY_KINDS: [ParsedEntryKind; 8] = [
+    ParsedEntryKind::Block,
+    ParsedEntryKind::Proof,
+    ParsedEntryKind::Account,
+    ParsedEntryKind::Transaction,
+    ParsedEntryKind::Message,
+    ParsedEntryKind::AccountTransactions,
+    ParsedEntryKind::AccountCreation,
+    ParsedEntryKind::Economics,
+];
+
+impl<'a> ParsedChunks<'a> {
+    /// `source` generates all the entries of the kind, it is called once for every kind
+    pub(crate) fn new(
+        limits: ChunkLimits,
+        source: impl FnMut(ParsedEntryKind) -> Result<Vec<ParsedEntry>> + 'a,
+    ) -> Self {
+        Self {
+            limits,
+            kinds: ENTRY_KINDS.into_iter().collect(),
+            current: None,
+            source: Box::new(source),
+        }
+    }
+
+    /// Next chunk or `None` if all the entries are returned
+    pub fn next_chunk(&mut self) -> Result<Option<ParsedChunk>> {
+        while self.current.as_ref().map_or(true, |(_, queue)| queue.is_empty()) {
+            // the returned kind is released before the next one is generated
+            self.current = None;
+            let Some(kind) = self.kinds.pop_front() else {
+                return Ok(None);
+            };
+            self.current = Some((kind, (self.source)(kind)?.into()));
+        }
+        let Some((kind, queue)) = self.current.as_mut() else {
+            return Ok(None);
+        };
+        let mut chunk = ParsedChunk {
+            kind: *kind,
+            entries: Vec::new(),
+            bytes: 0,
+        };
+        while let Some(entry) = queue.front() {
+            if chunk.entries.len() >= self.limits.max_entries.max(1) {
+                break;
+            }
+            let size = serde_json::to_vec(&entry.body)?.len();
+            if !chunk.entries.is_empty() && chunk.bytes + size > self.limits.max_bytes {
+                break;
+            }
+            chunk.bytes += size;
+            chunk.entries.extend(queue.pop_front());
+        }
+        if queue.is_empty() {
+            self.current = None;
+        }
+        Ok(Some(chunk))
+    }
+}
+
+impl Iterator for ParsedChunks<'_> {
+    type Item = Result<ParsedChunk>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_chunk().transpose()
+    }
+}
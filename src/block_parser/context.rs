@@ -0,0 +1,60 @@
+use serde_json::{Map, Value};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of the `parsed_at` time of the ingestion metadata
+pub trait ParserClock: Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+pub struct SystemClock();
+
+impl ParserClock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Always returns the same time, so documents stay deterministic in tests
+pub struct FixedClock(pub SystemTime);
+
+impl ParserClock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
+
+/// Ingestion metadata added to every parsed entry as the `ingestion` object:
+/// `parsed_at` (unix time in milliseconds), `parser_version` and optional `host`.
+pub struct ParserContext {
+    pub clock: Box<dyn ParserClock>,
+    pub host: Option<String>,
+}
+
+impl ParserContext {
+    pub fn new(host: Option<String>) -> Self {
+        Self {
+            clock: Box::new(SystemClock()),
+            host,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: impl ParserClock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    pub fn metadata(&self) -> Map<String, Value> {
+        let parsed_at = self
+            .clock
+            .now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_millis() as u64);
+        let mut map = Map::new();
+        map.insert("parsed_at".to_string(), parsed_at.into());
+        map.insert("parser_version".to_string(), env!("CARGO_PKG_VERSION").into());
+        if let Some(host) = &self.host {
+            map.insert("host".to_string(), host.as_str().into());
+        }
+        map
+    }
+}
@@ -2,8 +2,9 @@ use crate::block_parser::{BlockParsingError, JsonReducer};
 use crate::EntryConfig;
 use serde_json::{Map, Value};
 use ever_block::Result;
+use serde_derive::{Deserialize, Serialize};
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ParsedEntry {
     pub id: String,
     pub body: Map<String, Value>,
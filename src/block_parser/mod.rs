@@ -1,5 +1,7 @@
 mod accounts;
 mod block;
+mod chunks;
+mod context;
 mod entry;
 mod parser;
 mod transactions;
@@ -9,10 +11,12 @@ use serde_json::{Map, Value};
 use std::time::{Duration, SystemTime};
 use ever_block::{error, MsgAddrStd, MsgAddressInt, Result, SliceData, UInt256};
 
-pub use block::{ParsedBlock, ParsingBlock};
+pub use block::{ParsedBlock, ParsedCounts, ParsingBlock};
+pub use chunks::{ChunkLimits, ParsedChunk, ParsedChunks, ParsedEntryKind};
+pub use context::{FixedClock, ParserClock, ParserContext, SystemClock};
 pub use entry::ParsedEntry;
 pub use parser::{BlockParser, BlockParserConfig, EntryConfig};
-pub use reducers::JsonFieldsReducer;
+pub use reducers::{CellPoolReducer, JsonFieldsReducer, JsonPointerReducer, JsonSizeReducer};
 
 #[derive(Debug, thiserror::Error)]
 pub enum BlockParsingError {
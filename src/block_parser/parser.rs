@@ -1,16 +1,20 @@
 use crate::block_parser::accounts::{AccountTransition, ParserAccounts};
 use crate::block_parser::block::{ParsedBlock, ParsingBlock};
+use crate::block_parser::chunks::{ChunkLimits, ParsedChunks, ParsedEntryKind, ENTRY_KINDS};
+use crate::block_parser::context::ParserContext;
 use crate::block_parser::entry::{get_sharding_depth, ParsedEntry};
 use crate::block_parser::transactions::ParserTransactions;
 use crate::block_parser::{
-    is_account_none, unix_time_to_system_time, ParserTraceEvent, ParserTracer,
+    get_partition, is_account_none, unix_time_to_system_time, ParserTraceEvent, ParserTracer,
 };
 use crate::JsonReducer;
-use serde_json::Value;
-use std::collections::BTreeMap;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
 use ever_block::{
     Account, AccountBlock, AccountStatus, BlockIdExt, BlockProcessingStatus, BlockProof,
-    Deserializable, HashmapAugType, Transaction,
+    Deserializable, EnqueuedMsg, HashmapAugType, MsgAddressInt, OutMsg, OutMsgQueueKey,
+    Serializable, Transaction,
 };
 use ever_block::{fail, HashmapType, Result, SliceData, UInt256};
 
@@ -19,15 +23,49 @@ pub struct EntryConfig<R: JsonReducer> {
     pub reducer: Option<R>,
 }
 
+/// Entries to produce and their options. New options may be added in minor versions,
+/// so the config is created with `Default::default()` and the needed fields are assigned.
+#[non_exhaustive]
 pub struct BlockParserConfig<R: JsonReducer> {
     pub blocks: Option<EntryConfig<R>>,
     pub proofs: Option<EntryConfig<R>>,
     pub accounts: Option<EntryConfig<R>>,
     pub transactions: Option<EntryConfig<R>>,
     pub messages: Option<EntryConfig<R>>,
+    /// Per-account groups of the block transactions, see `db_serialize_account_transactions`
+    pub account_transactions: Option<EntryConfig<R>>,
+    /// Accounts created in the block with their funding messages,
+    /// see `db_serialize_account_creation`
+    pub account_creations: Option<EntryConfig<R>>,
+    /// Economics of masterchain blocks parsed with the shard state,
+    /// see `db_serialize_block_economics`
+    pub economics: Option<EntryConfig<R>>,
 
     pub max_account_bytes_size: Option<usize>,
     pub is_node_se: bool,
+    /// Keep only every N-th (by id) account, transaction and message, see `ParsedBlock::counts`
+    pub sampling: Option<u64>,
+    /// Ingestion metadata added to all the entries, see `ParserContext`
+    pub context: Option<ParserContext>,
+}
+
+impl<R: JsonReducer> Default for BlockParserConfig<R> {
+    fn default() -> Self {
+        Self {
+            blocks: None,
+            proofs: None,
+            accounts: None,
+            transactions: None,
+            messages: None,
+            account_transactions: None,
+            account_creations: None,
+            economics: None,
+            max_account_bytes_size: None,
+            is_node_se: false,
+            sampling: None,
+            context: None,
+        }
+    }
 }
 
 pub struct BlockParser<T: ParserTracer, R: JsonReducer> {
@@ -46,10 +84,49 @@ impl<T: ParserTracer, R: JsonReducer> BlockParser<T, R> {
         }
     }
 
+    /// Same as `parse` but the entries are returned by chunks bounded by `limits`.
+    /// The entries are generated lazily by kinds in the order of `ParsedEntryKind`: every kind
+    /// is parsed from the block cells when the previous one is fully returned, so only one kind
+    /// of entries is held at a time. Kinds depending on transactions iterate them anew.
+    pub fn parse_chunked<'a>(
+        &'a self,
+        block: ParsingBlock<'a>,
+        with_proofs: bool,
+        limits: ChunkLimits,
+    ) -> Result<ParsedChunks<'a>> {
+        self.start_parsing(&block)?;
+        Ok(ParsedChunks::new(limits, move |kind| {
+            Ok(self.parse_kinds(&block, with_proofs, &[kind])?.take_entries(kind))
+        }))
+    }
+
     pub fn parse(&self, block: ParsingBlock, with_proofs: bool) -> Result<ParsedBlock> {
+        self.start_parsing(&block)?;
+        self.parse_kinds(&block, with_proofs, &ENTRY_KINDS)
+    }
+
+    fn start_parsing(&self, block: &ParsingBlock) -> Result<()> {
         if self.config.accounts.is_some() && block.shard_state.is_none() {
             fail!("Shard state should be specified because the block parser was configured with account parsing.");
         }
+        if let Some(tracer) = &self.tracer {
+            tracer.trace(
+                block.id.root_hash(),
+                None,
+                unix_time_to_system_time(block.block.read_info()?.gen_utime().as_u32() as u64)?,
+                ParserTraceEvent::BlockCollated,
+            );
+        }
+        Ok(())
+    }
+
+    /// Parses entries of the `kinds` only
+    fn parse_kinds(
+        &self,
+        block: &ParsingBlock,
+        with_proofs: bool,
+        kinds: &[ParsedEntryKind],
+    ) -> Result<ParsedBlock> {
         let now = std::time::Instant::now();
 
         let block_id_str = block.id.root_hash().as_hex_string();
@@ -62,15 +139,6 @@ impl<T: ParserTracer, R: JsonReducer> BlockParser<T, R> {
         log::trace!("block order for {}: {:#?}", block_id_str, block_order);
 
         let block_info = block.block.read_info()?;
-        let ut = block_info.gen_utime();
-        if let Some(tracer) = &self.tracer {
-            tracer.trace(
-                block.id.root_hash(),
-                None,
-                unix_time_to_system_time(ut.as_u32() as u64)?,
-                ParserTraceEvent::BlockCollated,
-            );
-        }
         log::debug!(
             "TIME: block deserialize {}ms;   {}",
             now.elapsed().as_millis(),
@@ -79,11 +147,16 @@ impl<T: ParserTracer, R: JsonReducer> BlockParser<T, R> {
 
         let mut result = ParsedBlock::new();
 
-        let include_accounts = self.config.accounts.is_some();
-        let include_transactions = self.config.transactions.is_some();
-        let include_messages = self.config.messages.is_some();
+        let include = |kind: ParsedEntryKind, config: &Option<EntryConfig<R>>| {
+            config.is_some() && kinds.contains(&kind)
+        };
+        let include_accounts = include(ParsedEntryKind::Account, &self.config.accounts);
+        let include_transactions = include(ParsedEntryKind::Transaction, &self.config.transactions);
+        let include_messages = include(ParsedEntryKind::Message, &self.config.messages);
+        let include_account_creations =
+            include(ParsedEntryKind::AccountCreation, &self.config.account_creations);
 
-        if include_accounts || include_transactions || include_messages {
+        if include_accounts || include_transactions || include_messages || include_account_creations {
             // Transactions and messages
 
             let now = std::time::Instant::now();
@@ -91,9 +164,9 @@ impl<T: ParserTracer, R: JsonReducer> BlockParser<T, R> {
 
             let mut block_transactions = BTreeMap::new();
 
-            let mut accounts = ParserAccounts::new(&self.config, &block)?;
+            let mut accounts = ParserAccounts::new(&self.config, block)?;
             let transactions =
-                ParserTransactions::new(&self.config, &self.tracer, &block, with_proofs);
+                ParserTransactions::new(&self.config, &self.tracer, block, with_proofs);
             let mut tr_count = 0;
 
             let block_extra = block.block.read_extra()?;
@@ -168,7 +241,22 @@ impl<T: ParserTracer, R: JsonReducer> BlockParser<T, R> {
                     )?;
                 }
 
+                if include_account_creations {
+                    result.account_creations.extend(self.prepare_account_creation_entry(
+                        block,
+                        workchain_id,
+                        &cell.repr_hash(),
+                        &transaction,
+                    )?);
+                }
+
                 if include_transactions {
+                    let now_ms = interpolate_now_ms(
+                        block_info.gen_utime_ms(),
+                        block_info.start_lt(),
+                        block_info.end_lt(),
+                        transaction.logical_time(),
+                    );
                     result
                         .transactions
                         .push(transactions.prepare_transaction_entry(
@@ -177,6 +265,7 @@ impl<T: ParserTracer, R: JsonReducer> BlockParser<T, R> {
                             workchain_id,
                             transaction_order,
                             &code_hash,
+                            now_ms,
                         )?);
                 }
                 index += 1;
@@ -187,6 +276,7 @@ impl<T: ParserTracer, R: JsonReducer> BlockParser<T, R> {
             );
 
             if include_messages {
+                transactions.add_messages_routing(&mut prepared_messages)?;
                 result.messages = transactions.finish_prepared_messages(prepared_messages)?;
             }
 
@@ -199,8 +289,8 @@ impl<T: ParserTracer, R: JsonReducer> BlockParser<T, R> {
 
         // Block
 
-        if self.config.blocks.is_some() {
-            result.block = Some(self.prepare_block_entry(&block, &block_order)?);
+        if include(ParsedEntryKind::Block, &self.config.blocks) {
+            result.block = Some(self.prepare_block_entry(block, &block_order)?);
         }
 
         log::debug!(
@@ -209,10 +299,18 @@ impl<T: ParserTracer, R: JsonReducer> BlockParser<T, R> {
             block_id_str
         );
 
-        if self.config.proofs.is_some() {
+        if include(ParsedEntryKind::Economics, &self.config.economics) {
+            result.economics = self.prepare_economics_entry(block)?;
+        }
+
+        if include(ParsedEntryKind::AccountTransactions, &self.config.account_transactions) {
+            result.account_transactions = self.prepare_account_transactions_entries(block)?;
+        }
+
+        if include(ParsedEntryKind::Proof, &self.config.proofs) {
             if let Some(proof) = block.proof {
                 let now = std::time::Instant::now();
-                result.proof = Some(self.prepare_block_proof_entry(&block, proof, &block_order)?);
+                result.proof = Some(self.prepare_block_proof_entry(block, proof, &block_order)?);
                 log::trace!(
                     "TIME: block proof {}ms;   {}",
                     now.elapsed().as_millis(),
@@ -221,6 +319,14 @@ impl<T: ParserTracer, R: JsonReducer> BlockParser<T, R> {
             }
         }
 
+        if let Some(rate) = self.config.sampling {
+            result.sample(rate);
+        }
+
+        if let Some(context) = &self.config.context {
+            result.insert_metadata(&context.metadata());
+        }
+
         Ok(result)
     }
 
@@ -238,6 +344,15 @@ impl<T: ParserTracer, R: JsonReducer> BlockParser<T, R> {
         };
 
         let mut doc = crate::db_serialize_block("id", set)?;
+        crate::serialize_special_msg_values(&mut doc, block.block, crate::SerializationMode::Standart)?;
+        crate::serialize_master_anchor(
+            &mut doc,
+            block.block,
+            block.mc_seq_no,
+            block.mc_block_id,
+            crate::SerializationMode::Standart,
+        )?;
+        resolve_dequeued_messages(&mut doc, block, crate::SerializationMode::Standart.into())?;
         if let Some(block_order) = block_order {
             doc.insert("chain_order".to_owned(), Value::String(block_order.clone()));
         }
@@ -245,6 +360,61 @@ impl<T: ParserTracer, R: JsonReducer> BlockParser<T, R> {
         ParsedEntry::reduced(doc.into(), partition, &self.config.blocks)
     }
 
+    fn prepare_economics_entry(&self, block: &ParsingBlock) -> Result<Option<ParsedEntry>> {
+        let Some(shard_state) = block.shard_state else {
+            return Ok(None);
+        };
+        let Some(doc) = crate::db_serialize_block_economics(
+            block.id.root_hash(),
+            block.block,
+            shard_state,
+            crate::SerializationMode::Standart,
+        )? else {
+            return Ok(None);
+        };
+        let partition = get_block_partition(get_sharding_depth(&self.config.economics), &block.id);
+        Ok(Some(ParsedEntry::reduced(doc, partition, &self.config.economics)?))
+    }
+
+    fn prepare_account_transactions_entries(&self, block: &ParsingBlock) -> Result<Vec<ParsedEntry>> {
+        let sharding_depth = get_sharding_depth(&self.config.account_transactions);
+        let docs = crate::db_serialize_account_transactions(
+            block.id.root_hash(),
+            block.block,
+            crate::SerializationMode::Standart,
+        )?;
+        let mut entries = Vec::with_capacity(docs.len());
+        for doc in docs {
+            let account_addr = doc["account_addr"].as_str().unwrap_or_default();
+            let partition = get_partition(sharding_depth, MsgAddressInt::from_str(account_addr)?.address())?;
+            entries.push(ParsedEntry::reduced(doc, partition, &self.config.account_transactions)?);
+        }
+        Ok(entries)
+    }
+
+    fn prepare_account_creation_entry(
+        &self,
+        block: &ParsingBlock,
+        workchain_id: i32,
+        transaction_id: &UInt256,
+        transaction: &Transaction,
+    ) -> Result<Option<ParsedEntry>> {
+        let Some(doc) = crate::db_serialize_account_creation(
+            block.id.root_hash(),
+            workchain_id,
+            transaction_id,
+            transaction,
+            crate::SerializationMode::Standart,
+        )? else {
+            return Ok(None);
+        };
+        let partition = get_partition(
+            get_sharding_depth(&self.config.account_creations),
+            transaction.account_id().clone(),
+        )?;
+        Ok(Some(ParsedEntry::reduced(doc, partition, &self.config.account_creations)?))
+    }
+
     fn prepare_block_proof_entry(
         &self,
         block: &ParsingBlock,
@@ -268,7 +438,9 @@ impl<T: ParserTracer, R: JsonReducer> BlockParser<T, R> {
         ParserAccounts::prepare_account_entry(
             account,
             prev_code_hash,
+            false,
             last_trans_chain_order,
+            None,
             self.config.max_account_bytes_size,
             get_sharding_depth(&self.config.accounts),
             &self.config.accounts,
@@ -276,6 +448,76 @@ impl<T: ParserTracer, R: JsonReducer> BlockParser<T, R> {
     }
 }
 
+/// Links `Dequeue` and `DequeueShort` out descriptors of the block document to the messages
+/// enqueued in the provided shard state: adds `dequeued_msg_id` and `enqueued_lt` fields.
+/// Queue entries are matched by the envelope hash, document items are matched by their own
+/// ids (`out_msg.msg_id` of `Dequeue` and `msg_env_hash` of `DequeueShort`), so reducers
+/// may filter or reorder `out_msg_descr` items.
+fn resolve_dequeued_messages(
+    doc: &mut Map<String, Value>,
+    block: &ParsingBlock,
+    mode: crate::SerializationOptions,
+) -> Result<()> {
+    // dequeued messages are in the queue before the block, the state after it has them removed
+    let Some(prev_shard_state) = block.prev_shard_state else {
+        return Ok(());
+    };
+    let Some(descr) = doc.get_mut("out_msg_descr").and_then(|v| v.as_array_mut()) else {
+        return Ok(());
+    };
+
+    // envelope hash -> (message hash, enqueued lt)
+    let mut enqueued = HashMap::new();
+    prev_shard_state.read_out_msg_queue_info()?.out_queue().iterate_with_keys(
+        &mut |key: OutMsgQueueKey, value: EnqueuedMsg| -> Result<bool> {
+            enqueued.insert(value.out_msg_cell().repr_hash(), (key.hash, value.enqueued_lt()));
+            Ok(true)
+        },
+    )?;
+    if enqueued.is_empty() {
+        return Ok(());
+    }
+
+    // id of the document item -> (message hash, enqueued lt)
+    let mut dequeued = HashMap::new();
+    block.block.read_extra()?.read_out_msg_descr()?.iterate_objects(|msg: OutMsg| {
+        let (item_id, env_hash) = match &msg {
+            OutMsg::Dequeue(dequeue) => {
+                let env = dequeue.read_out_message()?;
+                (env.message_cell().repr_hash(), env.serialize()?.repr_hash())
+            }
+            OutMsg::DequeueShort(short) => (short.msg_env_hash.clone(), short.msg_env_hash.clone()),
+            _ => return Ok(true),
+        };
+        if let Some(resolved) = enqueued.get(&env_hash) {
+            dequeued.insert(item_id, resolved.clone());
+        }
+        Ok(true)
+    })?;
+
+    for entry in descr.iter_mut().filter_map(Value::as_object_mut) {
+        let item_id = entry.get("msg_env_hash")
+            .or_else(|| entry.get("out_msg").and_then(|out_msg| out_msg.get("msg_id")))
+            .and_then(Value::as_str)
+            .and_then(|id| crate::deserialize::parse_uint256(id).ok());
+        if let Some((msg_id, enqueued_lt)) = item_id.and_then(|id| dequeued.get(&id)) {
+            entry.insert("dequeued_msg_id".to_owned(), crate::serialize::format_uint256(msg_id, mode).into());
+            crate::serialize::serialize_lt(entry, "enqueued_lt", enqueued_lt, mode);
+        }
+    }
+    Ok(())
+}
+
+/// Approximates transaction time in milliseconds: transactions are spread over the rest of
+/// the block generation second proportionally to their logical time, so ordering by `now_ms`
+/// follows ordering by `lt` and `now_ms / 1000` stays equal to `now`.
+fn interpolate_now_ms(gen_utime_ms: u64, start_lt: u64, end_lt: u64, lt: u64) -> u64 {
+    let ms_span = 999 - gen_utime_ms % 1000;
+    let lt_span = end_lt.saturating_sub(start_lt).max(1);
+    let lt_offset = lt.saturating_sub(start_lt).min(lt_span);
+    gen_utime_ms + (lt_offset as u128 * ms_span as u128 / lt_span as u128) as u64
+}
+
 fn get_block_partition(sharding_depth: u32, block_id: &BlockIdExt) -> Option<u32> {
     if sharding_depth > 0 {
         let partitioning_info =
@@ -131,6 +131,229 @@ impl JsonFieldsReducer {
     }
 }
 
+/// Reduces entry with JSON Pointer (RFC 6901) expressions: if `keep` pointers are set only
+/// the values they point to are left (intermediate containers are recreated as objects),
+/// then values pointed by `remove` pointers are dropped. Pointers are validated on construction.
+pub struct JsonPointerReducer {
+    keep: Vec<Vec<String>>,
+    remove: Vec<Vec<String>>,
+}
+
+impl JsonReducer for JsonPointerReducer {
+    fn reduce(&self, json: Map<String, Value>) -> Result<Map<String, Value>> {
+        self.reduce(json)
+    }
+}
+
+impl JsonPointerReducer {
+    pub fn with_pointers(keep: &[&str], remove: &[&str]) -> Result<Self> {
+        let keep = keep.iter().map(|p| Self::parse_pointer(p)).collect::<Result<Vec<_>>>()?;
+        let remove = remove.iter().map(|p| Self::parse_pointer(p)).collect::<Result<Vec<_>>>()?;
+        if remove.iter().any(|tokens| tokens.is_empty()) {
+            return Err(BlockParsingError::InvalidData(
+                "the whole document can't be removed".to_string()
+            ).into());
+        }
+        Ok(Self { keep, remove })
+    }
+
+    fn parse_pointer(pointer: &str) -> Result<Vec<String>> {
+        if pointer.is_empty() {
+            return Ok(vec![]);
+        }
+        let Some(pointer) = pointer.strip_prefix('/') else {
+            return Err(BlockParsingError::InvalidData(format!(
+                "JSON pointer must start with `/`: {}",
+                pointer
+            ))
+            .into());
+        };
+        pointer
+            .split('/')
+            .map(|token| {
+                let mut result = String::new();
+                let mut chars = token.chars();
+                while let Some(char) = chars.next() {
+                    if char != '~' {
+                        result.push(char);
+                        continue;
+                    }
+                    match chars.next() {
+                        Some('0') => result.push('~'),
+                        Some('1') => result.push('/'),
+                        _ => {
+                            return Err(BlockParsingError::InvalidData(format!(
+                                "invalid escape in JSON pointer token {}",
+                                token
+                            ))
+                            .into())
+                        }
+                    }
+                }
+                Ok(result)
+            })
+            .collect()
+    }
+
+    pub fn reduce(&self, json: Map<String, Value>) -> Result<Map<String, Value>> {
+        let mut json = Value::Object(json);
+        if !self.keep.is_empty() {
+            let mut result = Value::Object(Map::new());
+            for tokens in &self.keep {
+                if let Some(value) = Self::get(&json, tokens) {
+                    Self::insert(&mut result, tokens, value.clone())?;
+                }
+            }
+            json = result;
+        }
+        for tokens in &self.remove {
+            let (last, parent) = tokens.split_last().expect("checked on construction");
+            match Self::get_mut(&mut json, parent) {
+                Some(Value::Object(map)) => {
+                    map.remove(last);
+                }
+                Some(Value::Array(array)) => {
+                    if let Ok(index) = last.parse::<usize>() {
+                        if index < array.len() {
+                            array.remove(index);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        match json {
+            Value::Object(map) => Ok(map),
+            _ => Err(BlockParsingError::InvalidData("JSON object expected".to_string()).into()),
+        }
+    }
+
+    fn get<'v>(value: &'v Value, tokens: &[String]) -> Option<&'v Value> {
+        tokens.iter().try_fold(value, |value, token| match value {
+            Value::Object(map) => map.get(token),
+            Value::Array(array) => array.get(token.parse::<usize>().ok()?),
+            _ => None,
+        })
+    }
+
+    fn get_mut<'v>(value: &'v mut Value, tokens: &[String]) -> Option<&'v mut Value> {
+        tokens.iter().try_fold(value, |value, token| match value {
+            Value::Object(map) => map.get_mut(token),
+            Value::Array(array) => array.get_mut(token.parse::<usize>().ok()?),
+            _ => None,
+        })
+    }
+
+    fn insert(result: &mut Value, tokens: &[String], value: Value) -> Result<()> {
+        let Some((last, parent)) = tokens.split_last() else {
+            *result = value;
+            return Ok(());
+        };
+        let mut current = result;
+        for token in parent {
+            current = match current {
+                Value::Object(map) => map
+                    .entry(token.clone())
+                    .or_insert_with(|| Value::Object(Map::new())),
+                _ => {
+                    return Err(BlockParsingError::InvalidData(format!(
+                        "kept JSON pointers conflict at `{}`",
+                        token
+                    ))
+                    .into())
+                }
+            };
+        }
+        match current {
+            Value::Object(map) => {
+                map.insert(last.clone(), value);
+                Ok(())
+            }
+            _ => Err(BlockParsingError::InvalidData(format!(
+                "kept JSON pointers conflict at `{}`",
+                last
+            ))
+            .into()),
+        }
+    }
+}
+
+/// Moves repeated BOCs of an entry into its `cell_pool`, see `pool_repeated_cells`.
+pub struct CellPoolReducer;
+
+impl JsonReducer for CellPoolReducer {
+    fn reduce(&self, json: Map<String, Value>) -> Result<Map<String, Value>> {
+        crate::pool_repeated_cells(json)
+    }
+}
+
+/// Keeps the serialized size of an entry within `max_size` bytes by dropping low-priority
+/// fields in the configured order. Names of the dropped fields are listed in `_truncated`,
+/// entries still bigger than `max_size` after all the drops are marked with `_over_size`.
+pub struct JsonSizeReducer {
+    max_size: usize,
+    drop_order: Vec<String>,
+}
+
+impl JsonReducer for JsonSizeReducer {
+    fn reduce(&self, json: Map<String, Value>) -> Result<Map<String, Value>> {
+        self.reduce(json)
+    }
+}
+
+impl JsonSizeReducer {
+    pub const DEFAULT_DROP_ORDER: [&'static str; 6] = [
+        "boc",
+        "code",
+        "data",
+        "in_msg_descr",
+        "out_msg_descr",
+        "account_blocks",
+    ];
+
+    pub fn new(max_size: usize) -> Self {
+        Self::with_drop_order(max_size, &Self::DEFAULT_DROP_ORDER)
+    }
+
+    pub fn with_drop_order(max_size: usize, drop_order: &[&str]) -> Self {
+        Self {
+            max_size,
+            drop_order: drop_order.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    pub fn reduce(&self, json: Map<String, Value>) -> Result<Map<String, Value>> {
+        let mut map = json;
+        let mut truncated = vec![];
+        for name in &self.drop_order {
+            if Self::serialized_size(&map, &truncated)? <= self.max_size {
+                break;
+            }
+            if map.remove(name).is_some() {
+                truncated.push(Value::String(name.clone()));
+            }
+        }
+        // nothing else to drop, the consumer decides what to do with the entry
+        let over_size = Self::serialized_size(&map, &truncated)? > self.max_size;
+        if !truncated.is_empty() {
+            map.insert("_truncated".to_owned(), Value::Array(truncated));
+        }
+        if over_size {
+            map.insert("_over_size".to_owned(), Value::Bool(true));
+        }
+        Ok(map)
+    }
+
+    fn serialized_size(map: &Map<String, Value>, truncated: &[Value]) -> Result<usize> {
+        let mut size = serde_json::to_vec(map)?.len();
+        if !truncated.is_empty() {
+            // `,"_truncated":[...]` is appended to the document
+            size += serde_json::to_vec(truncated)?.len() + r#","_truncated":"#.len();
+        }
+        Ok(size)
+    }
+}
+
 #[cfg(test)]
 #[path = "../tests/test_reducers.rs"]
 mod tests;
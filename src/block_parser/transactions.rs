@@ -2,14 +2,14 @@ use crate::block_parser::entry::get_sharding_depth;
 use crate::block_parser::{get_partition, is_minter_address};
 use crate::{
     BlockParserConfig, BlockParsingError, EntryConfig, JsonReducer, ParsedEntry, ParserTraceEvent,
-    ParserTracer, ParsingBlock,
+    ParserTracer, ParsingBlock, SerializationMode,
 };
 use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::time::SystemTime;
 use ever_block::{
-    CommonMsgInfo, Deserializable, Message, MessageProcessingStatus, MsgAddressExt, Transaction,
-    TransactionProcessingStatus,
+    CommonMsgInfo, Deserializable, HashmapAugType, InMsg, Message, MessageProcessingStatus,
+    MsgAddressExt, Transaction, TransactionProcessingStatus,
 };
 use ever_block::{write_boc, Cell, Result, SliceData, UInt256};
 
@@ -176,6 +176,24 @@ impl<'a, T: ParserTracer, R: JsonReducer> ParserTransactions<'a, T, R> {
         Ok(())
     }
 
+    /// Adds routing of the messages delivered in the block, see `serialize_msg_routing`
+    pub(crate) fn add_messages_routing(
+        &self,
+        prepared_messages: &mut HashMap<UInt256, PreparedMessage>,
+    ) -> Result<()> {
+        if prepared_messages.is_empty() {
+            return Ok(());
+        }
+        let in_msg_descr = self.parsing.block.read_extra()?.read_in_msg_descr()?;
+        in_msg_descr.iterate_with_keys(|message_id: UInt256, in_msg: InMsg| {
+            if let Some(prepared_message) = prepared_messages.get_mut(&message_id) {
+                crate::serialize_msg_routing(&mut prepared_message.doc, &in_msg)?;
+            }
+            Ok(true)
+        })?;
+        Ok(())
+    }
+
     pub fn finish_prepared_messages(
         &self,
         prepared_messages: HashMap<UInt256, PreparedMessage>,
@@ -249,6 +267,7 @@ impl<'a, T: ParserTracer, R: JsonReducer> ParserTransactions<'a, T, R> {
         workchain_id: i32,
         transaction_order: Option<String>,
         code_hash: &Option<String>,
+        now_ms: u64,
     ) -> Result<ParsedEntry> {
         let boc = write_boc(&cell).unwrap();
         let proof = if self.with_proofs {
@@ -257,6 +276,10 @@ impl<'a, T: ParserTracer, R: JsonReducer> ParserTransactions<'a, T, R> {
             None
         };
         let address = transaction.account_id().clone();
+        let mut context_fields = Map::new();
+        crate::serialize_in_msg_values(&mut context_fields, &transaction, SerializationMode::Standart)?;
+        crate::serialize_bounce_msg_id(&mut context_fields, &transaction, SerializationMode::Standart)?;
+        crate::serialize_phase_flags(&mut context_fields, &transaction)?;
         let set = crate::TransactionSerializationSet {
             transaction,
             id: cell.repr_hash(),
@@ -267,6 +290,7 @@ impl<'a, T: ParserTracer, R: JsonReducer> ParserTransactions<'a, T, R> {
             proof,
         };
         let mut doc = crate::db_serialize_transaction("id", &set)?;
+        doc.extend(context_fields);
         let partition = get_partition(self.transactions_sharding_depth, address)?;
         if let Some(transaction_order) = transaction_order {
             doc.insert("chain_order".to_owned(), transaction_order.into());
@@ -274,6 +298,7 @@ impl<'a, T: ParserTracer, R: JsonReducer> ParserTransactions<'a, T, R> {
         if let Some(code_hash) = code_hash {
             doc.insert("code_hash".to_owned(), code_hash.clone().into());
         }
+        doc.insert("now_ms".to_owned(), now_ms.into());
 
         ParsedEntry::reduced(doc.into(), partition, self.transactions_config)
     }
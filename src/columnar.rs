@@ -0,0 +1,201 @@
+/*
+ * Copyright (C) 2019-2023 EverX. All Rights Reserved.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.  You may obtain a copy of the
+ * License at:
+ *
+ * https://www.ever.dev/licenses
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific EVERX DEV software governing permissions and limitations
+ * under the License.
+ */
+
+//! Conversion of batches of serialized documents (transactions, messages, ...) into columns
+//! with one vector per field, ready to be copied into Arrow arrays or Parquet column chunks.
+//! Nested objects are flattened to columns named by the path joined with `.`,
+//! arrays are kept as JSON text.
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Values of one column, `None` stands for missing or `null` field of the document
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnValues {
+    Bool(Vec<Option<bool>>),
+    Int(Vec<Option<i64>>),
+    UInt(Vec<Option<u64>>),
+    Float(Vec<Option<f64>>),
+    /// Strings and values of mixed types or arrays as JSON text
+    String(Vec<Option<String>>),
+}
+
+impl ColumnValues {
+    pub fn len(&self) -> usize {
+        match self {
+            ColumnValues::Bool(values) => values.len(),
+            ColumnValues::Int(values) => values.len(),
+            ColumnValues::UInt(values) => values.len(),
+            ColumnValues::Float(values) => values.len(),
+            ColumnValues::String(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    pub name: String,
+    /// True if the field is absent or `null` in some documents of the batch
+    pub nullable: bool,
+    pub values: ColumnValues,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColumnBatch {
+    pub rows: usize,
+    /// Columns in order of the first appearance of the field in the documents
+    pub columns: Vec<Column>,
+}
+
+impl ColumnBatch {
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ColumnType {
+    Bool,
+    Int,
+    UInt,
+    /// Unsigned integers above `i64::MAX`, can't be widened to `Int` or `Float` without loss
+    LargeUInt,
+    Float,
+    String,
+}
+
+impl ColumnType {
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::Bool(_) => ColumnType::Bool,
+            Value::Number(number) if number.is_u64() => match number.as_u64() > Some(i64::MAX as u64) {
+                true => ColumnType::LargeUInt,
+                false => ColumnType::UInt,
+            },
+            Value::Number(number) if number.is_i64() => ColumnType::Int,
+            Value::Number(_) => ColumnType::Float,
+            _ => ColumnType::String,
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (ColumnType::UInt, ColumnType::LargeUInt) | (ColumnType::LargeUInt, ColumnType::UInt) => ColumnType::LargeUInt,
+            (ColumnType::LargeUInt, _) | (_, ColumnType::LargeUInt) => ColumnType::String,
+            (ColumnType::UInt, ColumnType::Int) | (ColumnType::Int, ColumnType::UInt) => ColumnType::Int,
+            (ColumnType::UInt | ColumnType::Int | ColumnType::Float,
+             ColumnType::UInt | ColumnType::Int | ColumnType::Float) => ColumnType::Float,
+            _ => ColumnType::String,
+        }
+    }
+}
+
+fn flatten<'a>(prefix: &str, map: &'a Map<String, Value>, row: &mut Vec<(String, &'a Value)>) {
+    for (name, value) in map {
+        let path = match prefix.is_empty() {
+            true => name.clone(),
+            false => format!("{}.{}", prefix, name),
+        };
+        match value {
+            Value::Object(map) => flatten(&path, map, row),
+            value => row.push((path, value)),
+        }
+    }
+}
+
+fn to_string(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Converts the batch of documents into columns. Type of the column is the common type
+/// of its values: integers are widened to `Int` or `Float` if needed, any other mix
+/// of types gives `String` column with JSON text of non string values. Unsigned integers
+/// above `i64::MAX` don't fit `Int` and `Float`, so mixing them with negative or float
+/// values gives `String` column with decimal text as well.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_transaction_set()?;
+/// let doc = db_serialize_transaction("id", &set)?;
+/// let batch = documents_to_columns(&[doc.clone(), doc]);
+/// assert_eq!(batch.rows, 2);
+/// assert_eq!(batch.column("lt").unwrap().values.len(), 2);
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn documents_to_columns(docs: &[Map<String, Value>]) -> ColumnBatch {
+    let mut names = Vec::<String>::new();
+    let mut types = HashMap::<String, ColumnType>::new();
+    let mut rows = Vec::with_capacity(docs.len());
+    for doc in docs {
+        let mut row = Vec::new();
+        flatten("", doc, &mut row);
+        for (name, value) in &row {
+            if value.is_null() {
+                continue
+            }
+            let value_type = ColumnType::of(value);
+            match types.get_mut(name) {
+                Some(column_type) => *column_type = column_type.merge(value_type),
+                None => {
+                    names.push(name.clone());
+                    types.insert(name.clone(), value_type);
+                }
+            }
+        }
+        rows.push(row.into_iter().collect::<HashMap<_, _>>());
+    }
+
+    let columns = names.into_iter().map(|name| {
+        let cells = rows.iter()
+            .map(|row| row.get(&name).copied().filter(|value| !value.is_null()))
+            .collect::<Vec<_>>();
+        let nullable = cells.iter().any(Option::is_none);
+        let values = match types[&name] {
+            ColumnType::Bool => ColumnValues::Bool(
+                cells.iter().map(|cell| cell.and_then(Value::as_bool)).collect()
+            ),
+            ColumnType::Int => ColumnValues::Int(
+                cells.iter().map(|cell| cell.and_then(Value::as_i64)).collect()
+            ),
+            ColumnType::UInt | ColumnType::LargeUInt => ColumnValues::UInt(
+                cells.iter().map(|cell| cell.and_then(Value::as_u64)).collect()
+            ),
+            ColumnType::Float => ColumnValues::Float(
+                cells.iter().map(|cell| cell.and_then(Value::as_f64)).collect()
+            ),
+            ColumnType::String => ColumnValues::String(
+                cells.iter().map(|cell| cell.map(to_string)).collect()
+            ),
+        };
+        Column { name, nullable, values }
+    }).collect();
+
+    ColumnBatch { rows: docs.len(), columns }
+}
+
+#[cfg(test)]
+#[path = "tests/test_columnar.rs"]
+mod tests;
@@ -15,12 +15,50 @@
  */
 
 use serde_json::{Map, Value};
-use std::{str::FromStr, convert::TryInto};
+use std::{cell::RefCell, collections::{HashMap, HashSet}, str::FromStr, convert::TryInto};
 use ton_api::{
     ton::ton_node::{rempmessagestatus, RempMessageLevel, RempMessageStatus, RempReceipt},
     IntoBoxed
 };
 use ever_block::*;
+use rayon::prelude::*;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeserializationError {
+    #[error("{path} must have the field `{field}`")]
+    MissingField { path: String, field: String },
+    #[error("{path} must be {expected}")]
+    WrongType { path: String, expected: &'static str },
+    #[error("{path} must be {expected}: {reason}")]
+    InvalidValue { path: String, expected: &'static str, reason: String },
+    #[error("{path}: sum of validator weights overflows u64")]
+    ValidatorsWeightOverflow { path: String },
+    #[error("{path}: total_weight {expected} doesn't match sum of validator weights {actual}")]
+    TotalWeightMismatch { path: String, expected: u64, actual: u64 },
+    #[error("{path}: {kind} can't be restored without `boc`")]
+    BocRequired { path: String, kind: &'static str },
+}
+
+impl DeserializationError {
+    /// Stable code of the error class, doesn't change with the wording of the message
+    pub fn code(&self) -> &'static str {
+        match self {
+            DeserializationError::MissingField { .. } => "E001",
+            DeserializationError::WrongType { .. } => "E002",
+            DeserializationError::InvalidValue { .. } => "E003",
+            DeserializationError::ValidatorsWeightOverflow { .. } => "E004",
+            DeserializationError::TotalWeightMismatch { .. } => "E005",
+            DeserializationError::BocRequired { .. } => "E006",
+        }
+    }
+
+    /// Code of the deserialization error if the error is caused by it
+    pub fn code_of(err: &anyhow::Error) -> Option<&'static str> {
+        err.chain()
+            .find_map(|cause| cause.downcast_ref::<DeserializationError>())
+            .map(DeserializationError::code)
+    }
+}
 
 #[allow(dead_code)]
 trait ParseJson {
@@ -32,9 +70,15 @@ trait ParseJson {
     fn as_ulong(&self) -> Result<u64>;
 }
 
+/// Parses hash in any of `HashFormat` formats
+pub(crate) fn parse_uint256(string: &str) -> Result<UInt256> {
+    let string = string.strip_prefix("0x").or_else(|| string.strip_prefix("0X")).unwrap_or(string);
+    UInt256::from_str(&string.to_ascii_lowercase())
+}
+
 impl ParseJson for Value {
     fn as_uint256(&self) -> Result<UInt256> {
-        self.as_str().ok_or_else(|| error!("field is not str"))?.parse()
+        parse_uint256(self.as_str().ok_or_else(|| error!("field is not str"))?)
     }
     fn as_base64(&self) -> Result<Vec<u8>> {
         Ok(base64_decode(self.as_str().ok_or_else(|| error!("field is not str"))?)?)
@@ -77,64 +121,205 @@ impl ParseJson for Value {
     }
 }
 
+/// Remembers which fields of JSON objects were read by the parser
+/// to report the ones that were ignored.
+#[derive(Debug, Default)]
+struct FieldsTracker {
+    visited: RefCell<HashMap<*const Map<String, Value>, HashSet<String>>>,
+}
+
+impl FieldsTracker {
+    fn visit(&self, map: &Map<String, Value>) {
+        self.visited.borrow_mut().entry(map as *const _).or_default();
+    }
+    fn use_field(&self, map: &Map<String, Value>, name: &str) {
+        self.visited.borrow_mut().entry(map as *const _).or_default().insert(name.to_string());
+    }
+    fn ignored_fields(&self, map: &Map<String, Value>) -> Vec<String> {
+        let mut result = Vec::new();
+        self.collect_ignored(map, "root", &mut result);
+        result
+    }
+    fn collect_ignored(&self, map: &Map<String, Value>, path: &str, result: &mut Vec<String>) {
+        let used = match self.visited.borrow().get(&(map as *const _)) {
+            Some(used) => used.clone(),
+            None => return
+        };
+        for (name, value) in map {
+            let path = format!("{}/{}", path, name);
+            if !used.contains(name) {
+                result.push(path);
+            } else {
+                self.collect_ignored_value(value, &path, result);
+            }
+        }
+    }
+    fn collect_ignored_value(&self, value: &Value, path: &str, result: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => self.collect_ignored(map, path, result),
+            Value::Array(array) => for (i, value) in array.iter().enumerate() {
+                self.collect_ignored_value(value, &format!("{}/{}", path, i), result)
+            }
+            _ => ()
+        }
+    }
+}
+
+/// How the parser treats numbers encoded as strings. Only `parse_config_with_strictness`
+/// and `parse_state_with_strictness` take it into account.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParserStrictness {
+    /// Numeric strings are accepted in any field
+    #[default]
+    Lenient,
+    /// Numeric strings are expected only in big-int fields: logical times and the 64-bit
+    /// fields the serializer writes as strings, other integer fields produce warnings.
+    /// Grams and other currencies are always strings and are not checked.
+    Strict,
+}
+
+/// Returns true if the serializer writes the field as the string
+fn is_big_int_field(name: &str) -> bool {
+    name == "lt" || name.ends_with("_lt") || crate::serialize::U64_STRING_FIELDS.contains(&name)
+}
+
+/// Collects warnings about numbers passed as strings in non big-int fields
+#[derive(Debug, Default)]
+struct CoercionsTracker {
+    warnings: RefCell<Vec<String>>,
+}
+
+impl CoercionsTracker {
+    fn check(&self, path: &[&str], name: &str) {
+        if !is_big_int_field(name) {
+            let warning = format!("{}/{} is the number passed as the string", path.join("/"), name);
+            log::warn!("{}", warning);
+            self.warnings.borrow_mut().push(warning);
+        }
+    }
+    fn into_warnings(self) -> Vec<String> {
+        self.warnings.into_inner()
+    }
+}
+
+const INTEGER: &str = "the integer or a string with the integer";
+const CURRENCY_ID: &str = "the currency id fitting 32-bit dictionary key";
+
+/// Currency id from the decimal or `0x` hex string
+fn parse_currency_id(id: &str) -> Option<u64> {
+    match id.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => u64::from_str(id).ok(),
+    }
+}
+
 #[derive(Debug)]
 struct PathMap<'m, 'a> {
     map: &'m Map<String, Value>,
-    path: Vec<&'a str>
+    path: Vec<&'a str>,
+    tracker: Option<&'m FieldsTracker>,
+    coercions: Option<&'m CoercionsTracker>,
 }
 
 impl<'m, 'a> PathMap<'m, 'a> {
     fn new(map: &'m Map<String, Value>) -> Self {
         Self {
             map,
-            path: vec!["root"]
+            path: vec!["root"],
+            tracker: None,
+            coercions: None,
+        }
+    }
+    fn with_tracker(map: &'m Map<String, Value>, tracker: &'m FieldsTracker) -> Self {
+        tracker.visit(map);
+        Self {
+            map,
+            path: vec!["root"],
+            tracker: Some(tracker),
+            coercions: None,
         }
     }
+    fn with_coercions(map: &'m Map<String, Value>, coercions: &'m CoercionsTracker) -> Self {
+        Self {
+            map,
+            path: vec!["root"],
+            tracker: None,
+            coercions: Some(coercions),
+        }
+    }
+    fn wrong_type(&self, name: &str, expected: &'static str) -> anyhow::Error {
+        DeserializationError::WrongType {
+            path: format!("{}/{}", self.path.join("/"), name),
+            expected,
+        }.into()
+    }
+    fn invalid_value(&self, name: &str, expected: &'static str, reason: impl std::fmt::Display) -> anyhow::Error {
+        DeserializationError::InvalidValue {
+            path: format!("{}/{}", self.path.join("/"), name),
+            expected,
+            reason: reason.to_string(),
+        }.into()
+    }
     fn cont(prev: &Self, name: &'a str, value: &'m Value) -> Result<Self> {
         let map = value
             .as_object()
-            .ok_or_else(|| error!("{}/{} must be the vector of objects", prev.path.join("/"), name))?;
+            .ok_or_else(|| prev.wrong_type(name, "the vector of objects"))?;
         let mut path = prev.path.clone();
         path.push(name);
+        if let Some(tracker) = prev.tracker {
+            tracker.visit(map);
+        }
         Ok(Self {
             map,
-            path
+            path,
+            tracker: prev.tracker,
+            coercions: prev.coercions,
         })
     }
+    /// `null` values are reported as missing fields, so documents produced with
+    /// `AbsentFieldsPolicy::NullAbsent` are read the same way as the ones without the fields
     fn get_item(&self, name: &'a str) -> Result<&'m Value> {
-        let item = self.map.get(name).ok_or_else(|| error!("{} must have the field `{}`", self.path.join("/"), name))?;
-        Ok(item)
+        if let Some(tracker) = self.tracker {
+            tracker.use_field(self.map, name);
+        }
+        self.map.get(name).filter(|item| !item.is_null()).ok_or_else(|| DeserializationError::MissingField {
+            path: self.path.join("/"),
+            field: name.to_string(),
+        }.into())
     }
     fn get_obj(&self, name: &'a str) -> Result<Self> {
         let map = self.get_item(name)?
             .as_object()
-            .ok_or_else(|| error!("{}/{} must be the object", self.path.join("/"), name))?;
+            .ok_or_else(|| self.wrong_type(name, "the object"))?;
         let mut path = self.path.clone();
         path.push(name);
+        if let Some(tracker) = self.tracker {
+            tracker.visit(map);
+        }
         Ok(Self {
             map,
-            path
+            path,
+            tracker: self.tracker,
+            coercions: self.coercions,
         })
     }
     fn get_vec(&self, name: &'a str) -> Result<&'m Vec<Value>> {
         self.get_item(name)?
             .as_array()
-            .ok_or_else(|| error!("{}/{} must be the vector", self.path.join("/"), name))
+            .ok_or_else(|| self.wrong_type(name, "the vector"))
     }
     fn get_str(&self, name: &'a str) -> Result<&'m str> {
         self.get_item(name)?
             .as_str()
-            .ok_or_else(|| error!("{}/{} must be the string", self.path.join("/"), name))
+            .ok_or_else(|| self.wrong_type(name, "the string"))
     }
     fn get_uint256(&self, name: &'a str) -> Result<UInt256> {
-        self.get_str(name)?.parse()
-            .map_err(|err| error!("{}/{} must be the uint256 in hex format : {}",
-                self.path.join("/"), name, err))
+        parse_uint256(self.get_str(name)?)
+            .map_err(|err| self.invalid_value(name, "the uint256 in hex format", err))
     }
     fn get_base64(&self, name: &'a str) -> Result<Vec<u8>> {
         base64_decode(self.get_str(name)?)
-            .map_err(|err| error!("{}/{} must be the base64 : {}",
-                self.path.join("/"), name, err))
+            .map_err(|err| self.invalid_value(name, "the base64", err))
     }
 
     fn get_num(&self, name: &'a str) -> Result<i64> {
@@ -145,37 +330,22 @@ impl<'m, 'a> PathMap<'m, 'a> {
         }
         if let Ok(value) = self.get_item(&(name.to_string() + "_dec")) {
             if let Some(v) = value.as_str() {
-                return i64::from_str(v).map_err(|err| {
-                    error!(
-                        "{}/{} must be the integer or a string with the integer {}: {}",
-                        self.path.join("/"), name, v, err
-                    )
-                });
+                return i64::from_str(v).map_err(|err| self.invalid_value(name, INTEGER, format!("{}: {}", v, err)));
             }
         }
         if let Ok(value) = self.get_item(name) {
             if let Some(v) = value.as_str() {
+                if let Some(coercions) = self.coercions {
+                    coercions.check(&self.path, name);
+                }
                 if let Some(v) = v.strip_prefix("0x") {
-                    return i64::from_str_radix(v, 16).map_err(|err| {
-                        error!(
-                            "{}/{} must be the integer or a string with the integer {}: {}",
-                            self.path.join("/"), name, v, err
-                        )
-                    });
+                    return i64::from_str_radix(v, 16).map_err(|err| self.invalid_value(name, INTEGER, format!("{}: {}", v, err)));
                 } else {
-                    return i64::from_str(v).map_err(|err| {
-                        error!(
-                            "{}/{} must be the integer or a string with the integer {}: {}",
-                            self.path.join("/"), name, v, err
-                        )
-                    });
+                    return i64::from_str(v).map_err(|err| self.invalid_value(name, INTEGER, format!("{}: {}", v, err)));
                 }
             }
         }
-        fail!(
-            "{}/{} must be the integer or a string with the integer",
-            self.path.join("/"), name
-        )
+        Err(self.wrong_type(name, INTEGER))
     }
 
     fn get_grams(&self, name: &'a str) -> Result<Grams> {
@@ -186,28 +356,15 @@ impl<'m, 'a> PathMap<'m, 'a> {
         }
         if let Ok(value) = self.get_item(&(name.to_string() + "_dec")) {
             if let Some(v) = value.as_str() {
-                return Grams::from_str(v).map_err(|err| {
-                    error!(
-                        "{}/{} must be the integer or a string with the integer {}: {}",
-                        self.path.join("/"), name, v, err
-                    )
-                });
+                return Grams::from_str(v).map_err(|err| self.invalid_value(name, INTEGER, format!("{}: {}", v, err)));
             }
         }
         if let Ok(value) = self.get_item(name) {
             if let Some(v) = value.as_str() {
-                return Grams::from_str(v).map_err(|err| {
-                    error!(
-                        "{}/{} must be the integer or a string with the integer {}: {}",
-                        self.path.join("/"), name, v, err
-                    )
-                });
+                return Grams::from_str(v).map_err(|err| self.invalid_value(name, INTEGER, format!("{}: {}", v, err)));
             }
         }
-        fail!(
-            "{}/{} must be the integer or a string with the integer",
-            self.path.join("/"), name
-        )
+        Err(self.wrong_type(name, INTEGER))
     }
 
     #[allow(dead_code)]
@@ -216,13 +373,100 @@ impl<'m, 'a> PathMap<'m, 'a> {
             *value = new_value as u32;
         }
     }
+    /// Integer fitting the type, negative and too large values are rejected
+    fn get_int<T: TryFrom<i64>>(&self, name: &'a str) -> Result<T> {
+        let value = self.get_num(name)?;
+        T::try_from(value).map_err(|_| self.invalid_value(
+            name, INTEGER, format!("{} is out of range of {}", value, std::any::type_name::<T>())
+        ))
+    }
+    /// Unsigned 64-bit integer in any of `get_num` formats, values above `i64::MAX`
+    /// are accepted as well, negative values are rejected
+    fn get_u64(&self, name: &'a str) -> Result<u64> {
+        if let Ok(value) = self.get_item(name) {
+            if let Some(v) = value.as_u64() {
+                return Ok(v);
+            }
+            if let Some(v) = value.as_i64() {
+                return Err(self.invalid_value(name, INTEGER, format!("{} is negative", v)));
+            }
+        }
+        if let Ok(value) = self.get_item(&(name.to_string() + "_dec")) {
+            if let Some(v) = value.as_str() {
+                return u64::from_str(v).map_err(|err| self.invalid_value(name, INTEGER, format!("{}: {}", v, err)));
+            }
+        }
+        if let Ok(value) = self.get_item(name) {
+            if let Some(v) = value.as_str() {
+                if let Some(coercions) = self.coercions {
+                    coercions.check(&self.path, name);
+                }
+                if let Some(v) = v.strip_prefix("0x") {
+                    return u64::from_str_radix(v, 16).map_err(|err| self.invalid_value(name, INTEGER, format!("{}: {}", v, err)));
+                } else {
+                    return u64::from_str(v).map_err(|err| self.invalid_value(name, INTEGER, format!("{}: {}", v, err)));
+                }
+            }
+        }
+        Err(self.wrong_type(name, INTEGER))
+    }
+    /// Value of the optional field: `None` if the field is absent, errors of the present
+    /// field are propagated
+    fn get_opt<T>(&self, name: &'a str, get: impl FnOnce(&Self, &'a str) -> Result<T>) -> Result<Option<T>> {
+        match has_field(self, name) || has_field(self, &format!("{}_dec", name)) {
+            true => get(self, name).map(Some),
+            false => Ok(None)
+        }
+    }
+    /// Currency id as the number or the string with decimal or `0x` hex number
+    fn get_currency_id(&self, name: &'a str) -> Result<u32> {
+        let value = self.get_item(name)?;
+        let id = match (value.as_u64(), value.as_str()) {
+            (Some(id), _) => Some(id),
+            (None, Some(id)) => parse_currency_id(id),
+            _ => None,
+        };
+        id.and_then(|id| id.try_into().ok()).ok_or_else(|| self.invalid_value(
+            name, CURRENCY_ID, value
+        ))
+    }
+    /// Grams from `<name>` and extra currencies from optional `<name>_other` either
+    /// in array or in object layout
+    fn get_cc(&self, name: &'a str) -> Result<CurrencyCollection> {
+        let mut cc = CurrencyCollection::from_grams(self.get_grams(name)?);
+        let other_name = format!("{}_other", name);
+        if let Some(Value::Object(other)) = self.map.get(&other_name) {
+            for (currency, value) in other {
+                let id = parse_currency_id(currency)
+                    .and_then(|id| u32::try_from(id).ok())
+                    .ok_or_else(|| self.invalid_value(&other_name, CURRENCY_ID, currency))?;
+                let value = match (value.as_u64(), value.as_str()) {
+                    (Some(value), _) => value.to_string().parse()?,
+                    (None, Some(value)) => value.parse()?,
+                    _ => return Err(self.invalid_value(&other_name, INTEGER, value))
+                };
+                cc.other.set(&id, &value)?;
+            }
+        } else if let Ok(other) = self.get_vec(&other_name) {
+            other.iter().try_for_each::<_, Result<()>>(|currency| {
+                let currency = PathMap::cont(self, &other_name, currency)?;
+                let value = if let Ok(value) = currency.get_str("value_dec") {
+                    value.parse()?
+                } else {
+                    currency.get_str("value")?.parse()?
+                };
+                cc.other.set(&currency.get_currency_id("currency")?, &value)
+            })?;
+        }
+        Ok(cc)
+    }
     fn get_num16(&self, name: &'a str) -> Result<u16> {
         Ok(self.get_num(name)? as u16)
     }
     fn get_bool(&self, name: &'a str) -> Result<bool> {
         self.get_item(name)?
             .as_bool()
-            .ok_or_else(|| error!("{}/{} must be boolean", self.path.join("/"), name))
+            .ok_or_else(|| self.wrong_type(name, "boolean"))
     }
 }
 
@@ -393,9 +637,14 @@ impl StateParser {
     fn parse_storage_prices(&mut self, config: &PathMap) -> Result<()> {
         self.parse_array(config, 18, |p18| {
             let mut map = ConfigParam18Map::default();
-            let mut index = 0u32;
+            let mut next_index = Some(0u32);
             p18.iter().try_for_each::<_, Result<_>>(|value| {
                 let p = PathMap::cont(config, "p18", value)?;
+                // documents without indices get them numbered in order
+                let index = match p.get_opt("index", PathMap::get_int)? {
+                    Some(index) => index,
+                    None => next_index.ok_or_else(|| p.invalid_value("index", INTEGER, "index after u32::MAX"))?,
+                };
                 let p = StoragePrices {
                     utime_since:      p.get_num("utime_since")? as u32,
                     bit_price_ps:     p.get_num("bit_price_ps")? as u64,
@@ -404,7 +653,7 @@ impl StateParser {
                     mc_cell_price_ps: p.get_num("mc_cell_price_ps")? as u64,
                 };
                 map.set(&index, &p)?;
-                index += 1;
+                next_index = index.checked_add(1);
                 Ok(())
             })?;
             Ok(ConfigParamEnum::ConfigParam18(ConfigParam18 { map }))
@@ -479,28 +728,35 @@ impl StateParser {
             p12.iter().try_for_each(|wc_info| {
                 let wc_info = PathMap::cont(config, "p12", wc_info)?;
                 let mut descr = WorkchainDescr::default();
-                let workchain_id = wc_info.get_num("workchain_id")? as u32;
-                descr.enabled_since = wc_info.get_num("enabled_since")? as u32;
-                descr.set_min_split(wc_info.get_num("min_split")? as u8)?;
-                descr.set_max_split(wc_info.get_num("max_split")? as u8)?;
-                descr.flags = wc_info.get_num("flags")? as u16;
+                // documents of older versions have the dictionary key, e.g. 4294967295 for -1
+                let workchain_id = match wc_info.get_int::<i32>("workchain_id") {
+                    Ok(workchain_id) => workchain_id as u32,
+                    Err(_) => wc_info.get_int::<u32>("workchain_id")?,
+                };
+                descr.enabled_since = wc_info.get_int("enabled_since")?;
+                let min_split = wc_info.get_int("min_split")?;
+                descr.set_min_split(min_split)?;
+                descr.set_max_split(wc_info.get_int("max_split")?)?;
+                descr.set_actual_min_split(
+                    wc_info.get_opt("actual_min_split", PathMap::get_int)?.unwrap_or(min_split)
+                )?;
+                descr.flags = wc_info.get_opt("flags", PathMap::get_int)?.unwrap_or_default();
                 descr.active = wc_info.get_bool("active")?;
                 descr.accept_msgs = wc_info.get_bool("accept_msgs")?;
                 descr.zerostate_root_hash = wc_info.get_uint256("zerostate_root_hash")?;
                 descr.zerostate_file_hash = wc_info.get_uint256("zerostate_file_hash")?;
-                descr.version = wc_info.get_num("version")? as u32;
-                // TODO: check here
-                descr.format = match wc_info.get_bool("basic")? {
+                descr.version = wc_info.get_opt("version", PathMap::get_int)?.unwrap_or_default();
+                descr.format = match wc_info.get_opt("basic", PathMap::get_bool)?.unwrap_or(true) {
                     true => {
-                        let vm_version = wc_info.get_num("vm_version")? as i32;
-                        let vm_mode    = wc_info.get_num("vm_mode"   )? as u64;
+                        let vm_version = wc_info.get_int("vm_version")?;
+                        let vm_mode    = wc_info.get_int("vm_mode"   )?;
                         WorkchainFormat::Basic(WorkchainFormat1::with_params(vm_version, vm_mode))
                     }
                     false => {
-                        let min_addr_len      = wc_info.get_num("min_addr_len")? as u16;
-                        let max_addr_len      = wc_info.get_num("max_addr_len")? as u16;
-                        let addr_len_step     = wc_info.get_num("addr_len_step")? as u16;
-                        let workchain_type_id = wc_info.get_num("workchain_type_id")? as u32;
+                        let min_addr_len      = wc_info.get_int("min_addr_len")?;
+                        let max_addr_len      = wc_info.get_int("max_addr_len")?;
+                        let addr_len_step     = wc_info.get_int("addr_len_step")?;
+                        let workchain_type_id = wc_info.get_int("workchain_type_id")?;
                         WorkchainFormat::Extended(
                             WorkchainFormat0::with_params(
                                 min_addr_len, max_addr_len, addr_len_step, workchain_type_id
@@ -552,14 +808,13 @@ impl StateParser {
         let utime_until = config.get_num("utime_until")? as u32;
         //let total = config.get_num("total")? as u16;
         let main = config.get_num("main")? as u16;
-        //let total_weight = config.get_num("total_weight")? as u64;
 
         let mut list = Vec::default();
         config.get_vec("list").and_then(|p| {
             p.iter().try_for_each::<_, Result<_>>(|p| {
                 let p = PathMap::cont(config, "p", p)?;
                 let public_key = hex::decode(p.get_str("public_key")?)?;
-                let weight = p.get_num("weight")? as u64;
+                let weight = p.get_u64("weight")?;
                 let adnl_addr = if let Ok(adnl_addr) = p.get_uint256("adnl_addr") {
                     Some(adnl_addr)
                 } else {
@@ -585,10 +840,30 @@ impl StateParser {
             Ok(())
         })?;
 
+        Self::check_validators_weight(config, &list)?;
         let validator_set = ValidatorSet::new(utime_since, utime_until, main, list)?;
         Ok(validator_set)
     }
 
+    fn check_validators_weight(config: &PathMap, list: &[ValidatorDescr]) -> Result<()> {
+        let mut total_weight = 0u64;
+        for descr in list {
+            total_weight = total_weight.checked_add(descr.weight).ok_or_else(|| {
+                DeserializationError::ValidatorsWeightOverflow { path: config.path.join("/") }
+            })?;
+        }
+        if let Some(expected) = config.get_opt("total_weight", PathMap::get_u64)? {
+            if expected != total_weight {
+                Err(DeserializationError::TotalWeightMismatch {
+                    path: config.path.join("/"),
+                    expected,
+                    actual: total_weight,
+                })?
+            }
+        }
+        Ok(())
+    }
+
     pub fn parse_config(&mut self, config: &PathMap) -> Result<()> {
         self.parse_uint256(config, 0, |config_addr | Ok(ConfigParamEnum::ConfigParam0(ConfigParam0 {config_addr} )))?;
         self.parse_uint256(config, 1, |elector_addr| Ok(ConfigParamEnum::ConfigParam1(ConfigParam1 {elector_addr} )))?;
@@ -614,7 +889,7 @@ impl StateParser {
                     currency.get_str("value")?.parse()?
                 };
                 to_mint.set(
-                    &(currency.get_num("currency")? as u32),
+                    &currency.get_currency_id("currency")?,
                     &value
                 )
             })?;
@@ -703,12 +978,13 @@ impl StateParser {
 
                 list.push(ValidatorDescr::with_params(
                     p.get_str("public_key")?.parse()?,
-                    p.get_num("weight")? as u64,
+                    p.get_u64("weight")?,
                     None,
                     bls_public_key,
                 ));
                 Ok(())
             }))?;
+            Self::check_validators_weight(p34, &list)?;
             let cur_validators = ValidatorSet::new(
                 p34.get_num("utime_since")? as u32,
                 p34.get_num("utime_until")? as u32,
@@ -803,8 +1079,7 @@ impl StateParser {
         Ok(())
     }
 
-    fn parse_state_unchecked(mut self, map: &Map<String, Value>) -> Result<ShardStateUnsplit> {
-        let map_path = PathMap::new(map);
+    fn parse_state_unchecked(mut self, map_path: &PathMap) -> Result<ShardStateUnsplit> {
 
         self.state.set_min_ref_mc_seqno(std::u32::MAX);
 
@@ -825,8 +1100,8 @@ impl StateParser {
             }
         }
 
-        match map_path.get_grams("total_balance") {
-            Ok(balance) => self.state.set_total_balance(CurrencyCollection::from_grams(balance)),
+        match map_path.get_cc("total_balance") {
+            Ok(balance) => self.state.set_total_balance(balance),
             Err(err) => {
                 if self.mandatory_params != 0 {
                     return Err(err)
@@ -838,6 +1113,12 @@ impl StateParser {
             Ok(master) => {
                 let config = master.get_obj("config")?;
                 self.parse_config(&config)?;
+                if let Ok(unknown_config) = master.get_vec("unknown_config") {
+                    unknown_config.iter().try_for_each::<_, Result<()>>(|param| {
+                        let param = PathMap::cont(&master, "unknown_config", param)?;
+                        set_raw_config_param(&mut self.extra.config, &param)
+                    })?;
+                }
                 match master.get_uint256("config_addr") {
                     Ok(addr) => self.extra.config.config_addr = addr,
                     Err(err) => {
@@ -870,8 +1151,8 @@ impl StateParser {
                         }
                     }
                 }
-                match master.get_grams("global_balance") {
-                    Ok(balance) => self.extra.global_balance.grams = balance,
+                match master.get_cc("global_balance") {
+                    Ok(balance) => self.extra.global_balance = balance,
                     Err(err) => {
                         if self.mandatory_params != 0 {
                             return Err(err)
@@ -892,15 +1173,8 @@ impl StateParser {
             let mut shard_accounts = self.state.read_accounts()?;
             accounts.iter().try_for_each::<_, Result<()>>(|account| {
                 let account = PathMap::cont(&map_path, "accounts", account)?;
-                let account = Account::construct_from_bytes(&account.get_base64("boc")?)?;
-                if let Some(account_id) = account.get_id() {
-                    let aug = account.aug()?;
-                    let account = ShardAccount::with_params(&account, UInt256::ZERO, 0)?;
-                    shard_accounts.set_builder_serialized(
-                        account_id,
-                        &account.write_to_new_cell()?,
-                        &aug
-                    )?;
+                if let Some((account_id, account, aug)) = parse_shard_account(&account)? {
+                    shard_accounts.set_builder_serialized(account_id, &account, &aug)?;
                 }
                 Ok(())
             })?;
@@ -927,8 +1201,7 @@ impl StateParser {
     }
 }
 
-pub fn parse_config_with_mandatory_params(config: &Map<String, Value>, mandatories: &[u32]) -> Result<ConfigParams> {
-    let config = PathMap::new(config);
+fn parse_config_path(config: &PathMap, mandatories: &[u32]) -> Result<ConfigParams> {
     let mut parser = StateParser::new();
     if !mandatories.is_empty() {
         parser.mandatory_params = 0;
@@ -936,20 +1209,574 @@ pub fn parse_config_with_mandatory_params(config: &Map<String, Value>, mandatori
             parser.mandatory_params |= 1u64 << mandatory;
         }
     }
-    parser.parse_config(&config)?;
+    parser.parse_config(config)?;
     Ok(parser.extra.config)
 }
 
+fn parse_shard_account(account: &PathMap) -> Result<Option<(AccountId, BuilderData, DepthBalanceInfo)>> {
+    let account = Account::construct_from_bytes(&account.get_base64("boc")?)?;
+    match account.get_id() {
+        Some(account_id) => {
+            let aug = account.aug()?;
+            let account = ShardAccount::with_params(&account, UInt256::ZERO, 0)?;
+            Ok(Some((account_id, account.write_to_new_cell()?, aug)))
+        }
+        None => Ok(None)
+    }
+}
+
+/// Builds accounts dictionary from many account documents, each one is identified by name
+/// (e.g. file name) used in error messages. Documents are parsed in parallel, all errors
+/// are collected and reported together.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// let accounts = map["accounts"].as_array().unwrap().iter().enumerate()
+///     .map(|(i, account)| (format!("account {}", i), account.as_object().unwrap().clone()))
+///     .collect::<Vec<_>>();
+/// let accounts = parse_shard_accounts(&accounts)?;
+/// assert_eq!(accounts.len()?, parse_state(&map)?.read_accounts()?.len()?);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_shard_accounts(accounts: &[(String, Map<String, Value>)]) -> Result<ShardAccounts> {
+    let parsed = accounts
+        .par_iter()
+        .map(|(name, account)| {
+            parse_shard_account(&PathMap::new(account)).map_err(|err| format!("{}: {}", name, err))
+        })
+        .collect::<Vec<_>>();
+    let mut errors = Vec::new();
+    let mut shard_accounts = ShardAccounts::default();
+    for account in parsed {
+        match account {
+            Ok(Some((account_id, account, aug))) => {
+                shard_accounts.set_builder_serialized(account_id, &account, &aug)?;
+            }
+            Ok(None) => (),
+            Err(err) => errors.push(err)
+        }
+    }
+    if !errors.is_empty() {
+        fail!("{} account documents can't be parsed:\n{}", errors.len(), errors.join("\n"))
+    }
+    Ok(shard_accounts)
+}
+
+/// Reads all `*.json` account documents from the directory and builds accounts dictionary
+/// with `parse_shard_accounts`.
+///
+/// ```no_run
+/// use ever_block_json::*;
+/// let accounts = parse_accounts_dir("accounts")?;
+/// println!("{} accounts", accounts.len()?);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_accounts_dir(path: impl AsRef<std::path::Path>) -> Result<ShardAccounts> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.extension().map_or(false, |ext| ext == "json") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    let accounts = files
+        .par_iter()
+        .map(|path| -> Result<(String, Map<String, Value>)> {
+            let name = path.display().to_string();
+            let json = std::fs::read_to_string(path)
+                .map_err(|err| error!("{}: {}", name, err))?;
+            let map = serde_json::from_str(&json)
+                .map_err(|err| error!("{}: {}", name, err))?;
+            Ok((name, map))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    parse_shard_accounts(&accounts)
+}
+
+/// Restores the document produced by `pool_repeated_cells`: pooled references are replaced
+/// with BOCs from `cell_pool` and the pool is removed.
+pub fn inline_pooled_cells(mut map: Map<String, Value>) -> Result<Map<String, Value>> {
+    fn inline(value: &mut Value, pool: &Map<String, Value>) -> Result<()> {
+        match value {
+            Value::Object(map) => for (name, value) in map.iter_mut() {
+                let hash = match value {
+                    Value::Object(pooled) if crate::POOLED_CELL_FIELDS.contains(&name.as_str()) => {
+                        pooled.get("pooled").and_then(|hash| hash.as_str()).map(str::to_string)
+                    }
+                    _ => None
+                };
+                match hash {
+                    Some(hash) => {
+                        *value = pool.get(&hash)
+                            .ok_or_else(|| error!("cell {} is not found in cell_pool", hash))?
+                            .clone();
+                    }
+                    None => inline(value, pool)?
+                }
+            }
+            Value::Array(array) => for value in array.iter_mut() {
+                inline(value, pool)?
+            }
+            _ => ()
+        }
+        Ok(())
+    }
+
+    let pool = match map.remove("cell_pool") {
+        Some(Value::Object(pool)) => pool,
+        Some(_) => fail!("root/cell_pool must be the object"),
+        None => return Ok(map)
+    };
+    let mut value = Value::Object(map);
+    inline(&mut value, &pool)?;
+    match value {
+        Value::Object(map) => Ok(map),
+        _ => unreachable!()
+    }
+}
+
+/// Parses config like `parse_config` and checks only the given params are present.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// let config = map["master"]["config"].as_object().unwrap();
+/// assert!(parse_config_with_mandatory_params(config, &[0, 1, 34])?.config(34)?.is_some());
+/// assert!(parse_config_with_mandatory_params(config, &[99]).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_config_with_mandatory_params(config: &Map<String, Value>, mandatories: &[u32]) -> Result<ConfigParams> {
+    parse_config_path(&PathMap::new(config), mandatories)
+}
+
+/// Parses `config` section of the key block or masterchain state document.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// let config = parse_config(map["master"]["config"].as_object().unwrap())?;
+/// assert_eq!(config, parse_state(&map)?.read_custom()?.unwrap().config);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 pub fn parse_config(config: &Map<String, Value>) -> Result<ConfigParams> {
     parse_config_with_mandatory_params(config, &[])
 }
 
+/// Parser of network-specific config params, the counterpart of `ConfigParamSerializer`.
+pub trait ConfigParamParser {
+    /// Returns the cell of the param or `None` if the param with this number is not handled
+    fn parse(&self, number: u32, param: &Value) -> Result<Option<Cell>>;
+}
+
+impl ConfigParamParser for crate::BridgeConfigParams {
+    fn parse(&self, number: u32, param: &Value) -> Result<Option<Cell>> {
+        if !Self::NUMBERS.contains(&number) {
+            return Ok(None)
+        }
+        let name = format!("p{}", number);
+        let root = Map::new();
+        let param = PathMap::cont(&PathMap::new(&root), &name, param)?;
+        let mut oracles = HashmapE::with_bit_len(256);
+        for oracle in param.get_vec("oracles")? {
+            let oracle = PathMap::cont(&param, "oracles", oracle)?;
+            let key = SliceData::load_builder(oracle.get_uint256("address")?.write_to_new_cell()?)?;
+            oracles.set_builder(key, &oracle.get_uint256("public_key")?.write_to_new_cell()?)?;
+        }
+        let mut builder = param.get_uint256("bridge_address")?.write_to_new_cell()?;
+        param.get_uint256("oracle_multisig_address")?.write_to(&mut builder)?;
+        oracles.write_to(&mut builder)?;
+        param.get_uint256("external_chain_address")?.write_to(&mut builder)?;
+        Ok(Some(builder.into_cell()?))
+    }
+}
+
+/// Parses config like `parse_config`, `pN` fields handled by extensions are parsed with them.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// let config = map["master"]["config"].as_object().unwrap();
+/// assert_eq!(parse_config_with(config, &[&BridgeConfigParams])?, parse_config(config)?);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_config_with(config: &Map<String, Value>, extensions: &[&dyn ConfigParamParser]) -> Result<ConfigParams> {
+    let mut config_params = parse_config(config)?;
+    for (name, param) in config {
+        let Some(number) = name.strip_prefix('p').and_then(|n| n.parse::<u32>().ok()) else {
+            continue
+        };
+        for extension in extensions {
+            if let Some(cell) = extension.parse(number, param)? {
+                let key = SliceData::load_builder(number.write_to_new_cell()?)?;
+                config_params.config_params.setref(key, &cell)?;
+                break
+            }
+        }
+    }
+    Ok(config_params)
+}
+
+/// Parses config like `parse_config` and returns paths of the fields which were ignored
+/// by the parser, e.g. misspelled or not supported ones.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// let (_, ignored) = parse_config_with_ignored_fields(map["master"]["config"].as_object().unwrap())?;
+/// assert!(ignored.is_empty());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_config_with_ignored_fields(config: &Map<String, Value>) -> Result<(ConfigParams, Vec<String>)> {
+    let tracker = FieldsTracker::default();
+    let config_params = parse_config_path(&PathMap::with_tracker(config, &tracker), &[])?;
+    Ok((config_params, tracker.ignored_fields(config)))
+}
+
+/// Parses config like `parse_config` and returns warnings about numbers passed as strings
+/// in the fields not documented as big-int ones if `strictness` is `Strict`.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// let config = map["master"]["config"].as_object().unwrap();
+/// let (_, warnings) = parse_config_with_strictness(config, ParserStrictness::Strict)?;
+/// assert!(warnings.is_empty());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_config_with_strictness(
+    config: &Map<String, Value>,
+    strictness: ParserStrictness
+) -> Result<(ConfigParams, Vec<String>)> {
+    match strictness {
+        ParserStrictness::Lenient => Ok((parse_config(config)?, Vec::new())),
+        ParserStrictness::Strict => {
+            let coercions = CoercionsTracker::default();
+            let config_params = parse_config_path(&PathMap::with_coercions(config, &coercions), &[])?;
+            Ok((config_params, coercions.into_warnings()))
+        }
+    }
+}
+
+/// Parses zerostate document, all mandatory config params must be present.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// let state = parse_state(&map)?;
+/// assert_eq!(state.shard().to_string(), map["id"]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 pub fn parse_state(map: &Map<String, Value>) -> Result<ShardStateUnsplit> {
-    StateParser::for_zero_state().parse_state_unchecked(map)
+    StateParser::for_zero_state().parse_state_unchecked(&PathMap::new(map))
 }
 
+/// Parses state like `parse_state` and returns paths of the fields which were ignored
+/// by the parser, e.g. misspelled or not supported ones.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// let (state, ignored) = parse_state_with_ignored_fields(&map)?;
+/// assert_eq!(state, parse_state(&map)?);
+/// println!("ignored: {:?}", ignored);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_state_with_ignored_fields(map: &Map<String, Value>) -> Result<(ShardStateUnsplit, Vec<String>)> {
+    let tracker = FieldsTracker::default();
+    let state = StateParser::for_zero_state()
+        .parse_state_unchecked(&PathMap::with_tracker(map, &tracker))?;
+    Ok((state, tracker.ignored_fields(map)))
+}
+
+/// Parses state like `parse_state` and returns warnings about numbers passed as strings
+/// in the fields not documented as big-int ones if `strictness` is `Strict`.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// let (state, _) = parse_state_with_strictness(&map, ParserStrictness::Strict)?;
+/// assert_eq!(state, parse_state(&map)?);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_state_with_strictness(
+    map: &Map<String, Value>,
+    strictness: ParserStrictness
+) -> Result<(ShardStateUnsplit, Vec<String>)> {
+    match strictness {
+        ParserStrictness::Lenient => Ok((parse_state(map)?, Vec::new())),
+        ParserStrictness::Strict => {
+            let coercions = CoercionsTracker::default();
+            let state = StateParser::for_zero_state()
+                .parse_state_unchecked(&PathMap::with_coercions(map, &coercions))?;
+            Ok((state, coercions.into_warnings()))
+        }
+    }
+}
+
+/// Parses state document like `parse_state` but without checks of mandatory config params.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// assert_eq!(parse_state_unchecked(&map)?, parse_state(&map)?);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 pub fn parse_state_unchecked(map: &Map<String, Value>) -> Result<ShardStateUnsplit> {
-    StateParser::new().parse_state_unchecked(map)
+    StateParser::new().parse_state_unchecked(&PathMap::new(map))
+}
+
+/// Config param differing in two states, `None` stands for the absent param
+#[derive(Debug, PartialEq, serde_derive::Serialize)]
+pub struct ConfigParamDiff {
+    pub number: u32,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+/// Change of the account balance in nanotokens and extra currencies
+#[derive(Debug, PartialEq, serde_derive::Serialize)]
+pub struct BalanceDelta {
+    pub account: String,
+    pub old: u128,
+    pub new: u128,
+    pub delta: i128,
+    /// Changed extra currencies ordered by currency id
+    pub other: Vec<CurrencyDelta>,
+}
+
+/// Change of the extra currency balance, values are decimal strings as they may
+/// exceed 128 bits, absent currency has zero value
+#[derive(Debug, PartialEq, serde_derive::Serialize)]
+pub struct CurrencyDelta {
+    pub currency: u32,
+    pub old: String,
+    pub new: String,
+    pub delta: String,
+}
+
+fn currency_deltas(old: &ExtraCurrencyCollection, new: &ExtraCurrencyCollection) -> Result<Vec<CurrencyDelta>> {
+    let values = |ecc: &ExtraCurrencyCollection| -> Result<std::collections::BTreeMap<u32, num::BigInt>> {
+        let mut values = std::collections::BTreeMap::new();
+        ecc.iterate_with_keys(|currency: u32, value: VarUInteger32| {
+            values.insert(currency, value.value().clone());
+            Ok(true)
+        })?;
+        Ok(values)
+    };
+    let (old, new) = (values(old)?, values(new)?);
+    let mut currencies = old.keys().chain(new.keys()).copied().collect::<Vec<_>>();
+    currencies.sort_unstable();
+    currencies.dedup();
+    Ok(currencies.into_iter().filter_map(|currency| {
+        let old = old.get(&currency).cloned().unwrap_or_default();
+        let new = new.get(&currency).cloned().unwrap_or_default();
+        (old != new).then(|| CurrencyDelta {
+            currency,
+            delta: (&new - &old).to_string(),
+            old: old.to_string(),
+            new: new.to_string(),
+        })
+    }).collect())
+}
+
+/// Differences of two state documents produced by `diff_states`
+#[derive(Debug, Default, PartialEq, serde_derive::Serialize)]
+pub struct StateDiff {
+    pub config_params: Vec<ConfigParamDiff>,
+    pub added_accounts: Vec<String>,
+    pub removed_accounts: Vec<String>,
+    pub balance_deltas: Vec<BalanceDelta>,
+    /// Hashes of the libraries, changed ones have another code or publishers
+    pub added_libraries: Vec<String>,
+    pub removed_libraries: Vec<String>,
+    pub changed_libraries: Vec<String>,
+}
+
+impl StateDiff {
+    pub fn is_empty(&self) -> bool {
+        *self == StateDiff::default()
+    }
+}
+
+fn collect_by_key<'m>(
+    map_path: &PathMap<'m, '_>,
+    name: &str,
+    key: &str
+) -> Result<HashMap<&'m str, &'m Value>> {
+    let mut items = HashMap::new();
+    if let Some(Value::Array(array)) = map_path.map.get(name) {
+        for item in array {
+            items.insert(PathMap::cont(map_path, name, item)?.get_str(key)?, item);
+        }
+    }
+    Ok(items)
+}
+
+fn sorted_keys(items: &HashMap<&str, &Value>, filter: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut keys = items.keys().filter(|key| filter(key)).map(|key| key.to_string()).collect::<Vec<_>>();
+    keys.sort();
+    keys
+}
+
+/// Compares two state documents, e.g. handcrafted zerostate before and after the edit:
+/// config params, accounts with their balances and libraries.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// assert!(diff_states(&map, &map)?.is_empty());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn diff_states(old: &Map<String, Value>, new: &Map<String, Value>) -> Result<StateDiff> {
+    let old_path = PathMap::new(old);
+    let new_path = PathMap::new(new);
+    let mut diff = StateDiff::default();
+
+    let config = |map_path: &PathMap| {
+        map_path.get_obj("master")
+            .and_then(|master| master.get_obj("config"))
+            .map(|config| config.map.clone())
+            .unwrap_or_default()
+    };
+    let (old_config, new_config) = (config(&old_path), config(&new_path));
+    let mut numbers = old_config.keys().chain(new_config.keys())
+        .filter_map(|key| key.strip_prefix('p')?.parse::<u32>().ok())
+        .collect::<Vec<_>>();
+    numbers.sort_unstable();
+    numbers.dedup();
+    for number in numbers {
+        let key = format!("p{}", number);
+        let (old, new) = (old_config.get(&key), new_config.get(&key));
+        if old != new {
+            diff.config_params.push(ConfigParamDiff { number, old: old.cloned(), new: new.cloned() });
+        }
+    }
+
+    let old_accounts = collect_by_key(&old_path, "accounts", "id")?;
+    let new_accounts = collect_by_key(&new_path, "accounts", "id")?;
+    diff.added_accounts = sorted_keys(&new_accounts, |id| !old_accounts.contains_key(id));
+    diff.removed_accounts = sorted_keys(&old_accounts, |id| !new_accounts.contains_key(id));
+    for account in sorted_keys(&old_accounts, |id| new_accounts.contains_key(id)) {
+        let balance = |state_path: &PathMap, accounts: &HashMap<&str, &Value>| -> Result<CurrencyCollection> {
+            PathMap::cont(state_path, "accounts", accounts[account.as_str()])?.get_cc("balance")
+        };
+        let old = balance(&old_path, &old_accounts)?;
+        let new = balance(&new_path, &new_accounts)?;
+        let other = currency_deltas(&old.other, &new.other)?;
+        let (old, new) = (old.grams.as_u128(), new.grams.as_u128());
+        if old != new || !other.is_empty() {
+            diff.balance_deltas.push(BalanceDelta { account, old, new, delta: new as i128 - old as i128, other });
+        }
+    }
+
+    let old_libraries = collect_by_key(&old_path, "libraries", "hash")?;
+    let new_libraries = collect_by_key(&new_path, "libraries", "hash")?;
+    diff.added_libraries = sorted_keys(&new_libraries, |hash| !old_libraries.contains_key(hash));
+    diff.removed_libraries = sorted_keys(&old_libraries, |hash| !new_libraries.contains_key(hash));
+    diff.changed_libraries = sorted_keys(&old_libraries, |hash| {
+        new_libraries.get(hash).map_or(false, |new| *new != old_libraries[hash])
+    });
+    Ok(diff)
+}
+
+/// Assembles state document from the chunks produced by `split_state_json`.
+/// Chunks may be passed in any order, the header is recognized by `accounts_chunks` field.
+pub fn merge_state_chunks(id_str: &str, chunks: &[Map<String, Value>]) -> Result<Map<String, Value>> {
+    let mut header = None;
+    let mut by_id = HashMap::new();
+    for chunk in chunks {
+        if chunk.contains_key("accounts_chunks") {
+            if header.replace(chunk).is_some() {
+                fail!("more than one state header in chunks")
+            }
+        } else {
+            let id = chunk.get(id_str).and_then(|id| id.as_str())
+                .ok_or_else(|| error!("state chunk has no `{}` field", id_str))?;
+            by_id.insert(id, chunk);
+        }
+    }
+    let header = header.ok_or_else(|| error!("no state header in chunks"))?;
+    let header_path = PathMap::new(header);
+    let count = header_path.get_num("accounts_chunks")? as usize;
+
+    let mut state = header.clone();
+    state.remove("accounts_chunks");
+    let mut next = state.remove("next");
+    let mut accounts = Vec::new();
+    for index in 1..=count {
+        let id = match &next {
+            Some(Value::String(id)) => id,
+            _ => fail!("state chunk {} of {} is not referenced", index, count)
+        };
+        let chunk = by_id.get(id.as_str()).ok_or_else(|| error!("state chunk {} is missing", id))?;
+        let chunk_path = PathMap::new(chunk);
+        accounts.extend(chunk_path.get_vec("accounts")?.iter().cloned());
+        next = chunk.get("next").cloned();
+    }
+    if next.is_some() {
+        fail!("state has more chunks than declared in header")
+    }
+    state.insert("accounts".to_owned(), accounts.into());
+    Ok(state)
+}
+
+/// Parses shard state from the chunks produced by `split_state_json`.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// let chunks = split_state_json("id", map.clone(), 100)?;
+/// assert_eq!(parse_state_chunks("id", &chunks)?, parse_state(&map)?);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_state_chunks(id_str: &str, chunks: &[Map<String, Value>]) -> Result<ShardStateUnsplit> {
+    parse_state(&merge_state_chunks(id_str, chunks)?)
+}
+
+/// Builds validator set diff (see `serialize_validator_set_diff`) from two key block JSONs
+/// or bare config JSONs using their current validator sets (p34).
+pub fn validator_set_diff_from_json(
+    prev: &Map<String, Value>,
+    next: &Map<String, Value>,
+    mode: crate::SerializationMode,
+) -> Result<Map<String, Value>> {
+    fn validator_set(map: &Map<String, Value>) -> Result<ValidatorSet> {
+        let config = match map.get("master").and_then(|master| master.get("config")) {
+            Some(Value::Object(config)) => config,
+            Some(_) => fail!("root/master/config must be the object"),
+            None => map,
+        };
+        parse_config_with_mandatory_params(config, &[34])?.validator_set()
+    }
+    crate::serialize_validator_set_diff(&validator_set(prev)?, &validator_set(next)?, mode)
+}
+
+fn set_raw_config_param(config: &mut ConfigParams, param: &PathMap) -> Result<()> {
+    let number = param.get_num("number")? as u32;
+    let cell = read_single_root_boc(param.get_base64("boc")?)?;
+    let key = SliceData::load_builder(number.write_to_new_cell()?)?;
+    config.config_params.setref(key, &cell)?;
+    Ok(())
+}
+
+/// Sets config param from its raw form `{ "number": n, "boc": "<base64>" }`
+/// as emitted in `unknown_config` or by `export_config_param_boc`.
+pub fn import_config_param(config: &mut ConfigParams, param: &Map<String, Value>) -> Result<()> {
+    set_raw_config_param(config, &PathMap::new(param))
 }
 
 fn parse_block_id_ext(map_path: &PathMap, mc: bool) -> Result<BlockIdExt> {
@@ -1086,6 +1913,662 @@ pub fn parse_remp_status(map: &Map<String, Value>)
     Ok((receipt, signature))
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountProofVerdict {
+    /// Proof is a Merkle proof of the state and contains the account from `boc`
+    Proven,
+    /// Document has no `proof` field
+    NoProof,
+    /// Proof is made for a state with another root hash
+    StateRootMismatch,
+    /// Account is absent or pruned in the proven state
+    AccountNotInProof,
+    /// Account in the proven state differs from the one in `boc`
+    AccountMismatch,
+}
+
+impl AccountProofVerdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountProofVerdict::Proven => "proven",
+            AccountProofVerdict::NoProof => "noProof",
+            AccountProofVerdict::StateRootMismatch => "stateRootMismatch",
+            AccountProofVerdict::AccountNotInProof => "accountNotInProof",
+            AccountProofVerdict::AccountMismatch => "accountMismatch",
+        }
+    }
+}
+
+/// Checks that `proof` field of the account document proves its `boc` against the shard state
+/// with root hash `state_root_hash`.
+pub fn verify_account_proof(map: &Map<String, Value>, state_root_hash: &UInt256) -> Result<AccountProofVerdict> {
+    if map.get("proof").map_or(true, Value::is_null) {
+        return Ok(AccountProofVerdict::NoProof)
+    }
+    let map_path = PathMap::new(map);
+    let proof = map_path.get_base64("proof")?;
+    let account_cell = read_single_root_boc(map_path.get_base64("boc")?)?;
+    let account_id = Account::construct_from_cell(account_cell.clone())?
+        .get_id()
+        .ok_or_else(|| error!("root/boc must contain existing account"))?;
+
+    let merkle_proof = MerkleProof::construct_from_bytes(&proof)?;
+    if &merkle_proof.hash != state_root_hash {
+        return Ok(AccountProofVerdict::StateRootMismatch)
+    }
+    let state = ShardStateUnsplit::construct_from_cell(merkle_proof.proof.virtualize(1))?;
+    let shard_account = match state.read_accounts().and_then(|accounts| accounts.account(&account_id)) {
+        Ok(Some(shard_account)) => shard_account,
+        _ => return Ok(AccountProofVerdict::AccountNotInProof)
+    };
+    if shard_account.account_cell().repr_hash() != account_cell.repr_hash() {
+        return Ok(AccountProofVerdict::AccountMismatch)
+    }
+    Ok(AccountProofVerdict::Proven)
+}
+
+/// Reads the address and replaces its anycast with the one of `anycast_name` field if present
+fn parse_address(
+    map_path: &PathMap,
+    name: &'static str,
+    anycast_name: &'static str
+) -> Result<MsgAddressInt> {
+    let mut address = MsgAddressInt::from_str(map_path.get_str(name)?)?;
+    if map_path.map.get(anycast_name).map_or(false, |value| !value.is_null()) {
+        let anycast_path = map_path.get_obj(anycast_name)?;
+        let depth = anycast_path.get_num("depth")?;
+        let rewrite_pfx = anycast_path.get_str("rewrite_pfx")?;
+        let rewrite_pfx = rewrite_pfx.strip_prefix("0x").unwrap_or(rewrite_pfx).to_ascii_lowercase();
+        let rewrite_pfx = SliceData::from_string(&rewrite_pfx)?;
+        if rewrite_pfx.remaining_bits() as i64 != depth {
+            fail!("{}/{}/depth {} doesn't match length of rewrite_pfx", map_path.path.join("/"), anycast_name, depth)
+        }
+        let anycast = Some(AnycastInfo::with_rewrite_pfx(rewrite_pfx)?);
+        match &mut address {
+            MsgAddressInt::AddrStd(addr) => addr.anycast = anycast,
+            MsgAddressInt::AddrVar(addr) => addr.anycast = anycast,
+        }
+    }
+    Ok(address)
+}
+
+fn parse_block_ref(map_path: &PathMap, name: &'static str) -> Result<Option<ExtBlkRef>> {
+    let blk_ref = match map_path.get_obj(name) {
+        Ok(blk_ref) => blk_ref,
+        Err(_) if map_path.map.get(name).map_or(true, Value::is_null) => return Ok(None),
+        Err(err) => return Err(err)
+    };
+    Ok(Some(ExtBlkRef {
+        end_lt: blk_ref.get_num("end_lt")? as u64,
+        seq_no: blk_ref.get_num("seq_no")? as u32,
+        root_hash: blk_ref.get_uint256("root_hash")?,
+        file_hash: blk_ref.get_uint256("file_hash")?,
+    }))
+}
+
+fn parse_blk_prev_info(
+    map_path: &PathMap,
+    name: &'static str,
+    alt_name: &'static str,
+) -> Result<Option<BlkPrevInfo>> {
+    let prev = match parse_block_ref(map_path, name)? {
+        Some(prev) => prev,
+        None => return Ok(None)
+    };
+    match parse_block_ref(map_path, alt_name)? {
+        Some(alt) => Ok(Some(BlkPrevInfo::Blocks {
+            prev1: ChildCell::with_struct(&prev)?,
+            prev2: ChildCell::with_struct(&alt)?,
+        })),
+        None => Ok(Some(BlkPrevInfo::Block { prev }))
+    }
+}
+
+/// Logs warnings for deprecated fields of the document consumed by the parser
+fn warn_deprecated_fields(map: &Map<String, Value>, kind: &str) {
+    for deprecated in crate::find_deprecated_fields(map, kind) {
+        log::warn!(
+            "{} field `{}` is deprecated and will be removed in json_version {}, use `{}`",
+            kind, deprecated.field, deprecated.removed_in, deprecated.replacement
+        );
+    }
+}
+
+/// Restores previous blocks info of the block JSON: `prev_ref` and `prev_alt_ref`, and for
+/// blocks of a new vertical chain `prev_vert_ref` and `prev_vert_alt_ref`.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_block_set()?;
+/// let doc = db_serialize_block("id", &set)?;
+/// let (prev_ref, prev_vert_ref) = parse_block_prev_refs(&doc)?;
+/// assert_eq!(prev_ref, set.block.read_info()?.read_prev_ref()?);
+/// assert!(prev_vert_ref.is_none());
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_block_prev_refs(map: &Map<String, Value>) -> Result<(BlkPrevInfo, Option<BlkPrevInfo>)> {
+    warn_deprecated_fields(map, "block");
+    let map_path = PathMap::new(map);
+    let prev_ref = parse_blk_prev_info(&map_path, "prev_ref", "prev_alt_ref")?
+        .ok_or_else(|| error!("root must have the field `prev_ref`"))?;
+    if map_path.get_bool("after_merge").unwrap_or_default() && prev_ref.prev2()?.is_none() {
+        fail!("merge block must have the field `prev_alt_ref`")
+    }
+    let prev_vert_ref = if map_path.get_num("vert_seqno_incr").unwrap_or_default() != 0 {
+        Some(parse_blk_prev_info(&map_path, "prev_vert_ref", "prev_vert_alt_ref")?
+            .ok_or_else(|| error!("root must have the field `prev_vert_ref`"))?)
+    } else {
+        None
+    };
+    Ok((prev_ref, prev_vert_ref))
+}
+
+/// Returns ids of the previous blocks of the block JSON: one block or both parents of
+/// the merge block. Uses `parents` if present, otherwise restores ids from `prev_ref`
+/// and `prev_alt_ref` and the block shard.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_block_set()?;
+/// let parents = parse_block_parents(&db_serialize_block("id", &set)?)?;
+/// assert_eq!(parents.len(), 1);
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_block_parents(map: &Map<String, Value>) -> Result<Vec<BlockIdExt>> {
+    let map_path = PathMap::new(map);
+    if let Ok(parents) = map_path.get_vec("parents") {
+        let mut ids = Vec::new();
+        for parent in parents {
+            let parent = PathMap::cont(&map_path, "parents", parent)?;
+            ids.push(BlockIdExt::with_params(
+                ShardIdent::with_tagged_prefix(
+                    parent.get_num("workchain_id")? as i32,
+                    u64::from_str_radix(parent.get_str("shard")?, 16)?
+                )?,
+                parent.get_num("seq_no")? as u32,
+                parent.get_uint256("root_hash")?,
+                parent.get_uint256("file_hash")?,
+            ));
+        }
+        return Ok(ids)
+    }
+    let shard = ShardIdent::with_tagged_prefix(
+        map_path.get_num("workchain_id")? as i32,
+        u64::from_str_radix(map_path.get_str("shard")?, 16)?
+    )?;
+    let (prev_ref, _) = parse_block_prev_refs(map)?;
+    let id = |blk_ref: &ExtBlkRef, shard: ShardIdent| BlockIdExt::with_params(
+        shard, blk_ref.seq_no, blk_ref.root_hash.clone(), blk_ref.file_hash.clone()
+    );
+    match prev_ref.prev2()? {
+        Some(prev2) => {
+            let (left, right) = shard.split()?;
+            Ok(vec![id(&prev_ref.prev1()?, left), id(&prev2, right)])
+        }
+        None if map_path.get_bool("after_split").unwrap_or_default() => {
+            Ok(vec![id(&prev_ref.prev1()?, shard.merge()?)])
+        }
+        None => Ok(vec![id(&prev_ref.prev1()?, shard)])
+    }
+}
+
+/// Parses `shard_hashes` of the block or masterchain state document in both flat
+/// (`[{"workchain_id", "shard", "descr"}]`) and nested by workchain (see `nest_shard_hashes`)
+/// forms. Returns shard descriptions as they are in the document.
+///
+/// ```
+/// use ever_block_json::*;
+/// let shard_hashes = serde_json::json!({
+///     "0": [{ "shard": "8000000000000000", "descr": { "seq_no": 1 } }],
+/// });
+/// let shards = parse_shard_hashes(&shard_hashes)?;
+/// assert_eq!(shards[0].0, ever_block::ShardIdent::full(0));
+/// assert_eq!(shards[0].1["seq_no"], 1);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_shard_hashes(value: &Value) -> Result<Vec<(ShardIdent, Map<String, Value>)>> {
+    fn parse_shard(workchain_id: i32, shard: &PathMap) -> Result<(ShardIdent, Map<String, Value>)> {
+        let prefix = u64::from_str_radix(shard.get_str("shard")?, 16)
+            .map_err(|err| error!("{}/shard must be the hex : {}", shard.path.join("/"), err))?;
+        let ident = ShardIdent::with_tagged_prefix(workchain_id, prefix)?;
+        Ok((ident, shard.get_obj("descr")?.map.clone()))
+    }
+    let root = Map::new();
+    let root = PathMap::new(&root);
+    let mut shard_hashes = Vec::new();
+    match value {
+        Value::Array(shards) => {
+            for shard in shards {
+                let shard = PathMap::cont(&root, "shard_hashes", shard)?;
+                let workchain_id = shard.get_int::<i32>("workchain_id")?;
+                shard_hashes.push(parse_shard(workchain_id, &shard)?);
+            }
+        }
+        Value::Object(workchains) => {
+            for (workchain_id, shards) in workchains {
+                let workchain_id = i32::from_str(workchain_id)
+                    .map_err(|err| error!("shard_hashes key {} must be the workchain id : {}", workchain_id, err))?;
+                let shards = shards.as_array()
+                    .ok_or_else(|| error!("shard_hashes/{} must be the vector", workchain_id))?;
+                for shard in shards {
+                    let shard = PathMap::cont(&root, "shard_hashes", shard)?;
+                    shard_hashes.push(parse_shard(workchain_id, &shard)?);
+                }
+            }
+        }
+        _ => fail!("shard_hashes must be the vector or the object")
+    }
+    Ok(shard_hashes)
+}
+
+fn has_field(map_path: &PathMap, name: &str) -> bool {
+    map_path.map.get(name).map_or(false, |value| !value.is_null())
+}
+
+/// Reads the cell of `boc` field, fails with `BocRequired` if there is no `boc`
+fn parse_boc_with_id(map_path: &PathMap, kind: &'static str) -> Result<Cell> {
+    if !has_field(map_path, "boc") {
+        return Err(DeserializationError::BocRequired { path: map_path.path.join("/"), kind }.into())
+    }
+    let cell = read_single_root_boc(map_path.get_base64("boc")?)?;
+    check_id(map_path, &cell)?;
+    Ok(cell)
+}
+
+/// Checks `id` field against the hash of the cell if the field is present
+fn check_id(map_path: &PathMap, cell: &Cell) -> Result<()> {
+    if has_field(map_path, "id") {
+        let id = map_path.get_uint256("id")?;
+        if id != cell.repr_hash() {
+            fail!(
+                "{}/id {} doesn't match hash of boc {}",
+                map_path.path.join("/"), id.as_hex_string(), cell.repr_hash().as_hex_string()
+            )
+        }
+    }
+    Ok(())
+}
+
+fn check_num_field(map_path: &PathMap, name: &'static str, expected: u64) -> Result<()> {
+    if has_field(map_path, name) {
+        let value = map_path.get_u64(name)?;
+        if value != expected {
+            fail!("{}/{} {} doesn't match value {} in boc", map_path.path.join("/"), name, value, expected)
+        }
+    }
+    Ok(())
+}
+
+/// Checks the address of the document with its anycast against the one in boc,
+/// documents without `<name>_anycast` are not checked as reducers may drop it
+fn check_address_field(
+    map_path: &PathMap,
+    name: &'static str,
+    anycast_name: &'static str,
+    expected: Option<&MsgAddressInt>
+) -> Result<()> {
+    if let Some(expected) = expected {
+        if has_field(map_path, name) && has_field(map_path, anycast_name) {
+            let address = parse_address(map_path, name, anycast_name)?;
+            if &address != expected {
+                fail!("{}/{} {} doesn't match address {} in boc", map_path.path.join("/"), name, address, expected)
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Restores the value flow from `value_flow` section of the block document produced
+/// in `Standart` mode, including `copyleft_rewards` given as `account` and `reward` pairs.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_block_set()?;
+/// let value_flow = set.block.read_value_flow()?;
+/// let doc = db_serialize_value_flow(&value_flow, SerializationMode::Standart)?;
+/// assert_eq!(parse_value_flow(doc.as_object().unwrap())?, value_flow);
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_value_flow(map: &Map<String, Value>) -> Result<ValueFlow> {
+    let map_path = PathMap::new(map);
+    let mut value_flow = ValueFlow {
+        from_prev_blk: map_path.get_cc("from_prev_blk")?,
+        to_next_blk: map_path.get_cc("to_next_blk")?,
+        imported: map_path.get_cc("imported")?,
+        exported: map_path.get_cc("exported")?,
+        fees_collected: map_path.get_cc("fees_collected")?,
+        fees_imported: map_path.get_cc("fees_imported")?,
+        recovered: map_path.get_cc("recovered")?,
+        created: map_path.get_cc("created")?,
+        minted: map_path.get_cc("minted")?,
+        ..Default::default()
+    };
+    if let Ok(rewards) = map_path.get_vec("copyleft_rewards") {
+        for reward in rewards {
+            let reward = PathMap::cont(&map_path, "copyleft_rewards", reward)?;
+            value_flow.copyleft_rewards.set(&reward.get_uint256("account")?, &reward.get_grams("reward")?)?;
+        }
+    }
+    Ok(value_flow)
+}
+
+/// Restores the transaction from the document produced by `db_serialize_transaction`
+/// in `Standart` or `QServer` mode. The transaction is built from `boc`, `id`, `lt`
+/// and `now` fields are checked against it. The document doesn't contain the phases and
+/// the out messages in full, so documents without `boc` are rejected with
+/// `DeserializationError::BocRequired`. The transaction keeps only the account id, so
+/// `account_addr` and `account_addr_anycast` are not read.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_transaction_set()?;
+/// let doc = db_serialize_transaction_ex("id", &set, SerializationMode::QServer)?;
+/// assert_eq!(parse_transaction(&doc)?, set.transaction);
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_transaction(map: &Map<String, Value>) -> Result<Transaction> {
+    let map_path = PathMap::new(map);
+    let transaction = Transaction::construct_from_cell(parse_boc_with_id(&map_path, "transaction")?)?;
+    check_num_field(&map_path, "lt", transaction.logical_time())?;
+    check_num_field(&map_path, "now", transaction.now() as u64)?;
+    Ok(transaction)
+}
+
+/// Restores the message from the document produced by `db_serialize_message`
+/// in `Standart` or `QServer` mode. The message is built from `boc`, `id`, `created_lt`
+/// and `src`/`dst` with `src_anycast`/`dst_anycast` fields are checked against it.
+/// Documents without `boc` are rebuilt from the header
+/// fields (`msg_type`, `src`, `dst` with their anycasts, values and fees, `created_lt`
+/// and `created_at`), `body` and the state init fields. The rebuilt message uses the default
+/// cell layout, so it must still match `id` if the field is present.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_message_set()?;
+/// let mut doc = db_serialize_message_ex("id", &set, SerializationMode::QServer)?;
+/// assert_eq!(parse_message(&doc)?, set.message);
+/// doc.remove("boc");
+/// assert_eq!(parse_message(&doc)?.header(), set.message.header());
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_message(map: &Map<String, Value>) -> Result<Message> {
+    let map_path = PathMap::new(map);
+    if !has_field(&map_path, "boc") {
+        return parse_message_fields(&map_path)
+    }
+    let message = Message::construct_from_cell(parse_boc_with_id(&map_path, "message")?)?;
+    if let Some(lt) = message.lt() {
+        check_num_field(&map_path, "created_lt", lt)?;
+    }
+    check_address_field(&map_path, "src", "src_anycast", message.src_ref())?;
+    check_address_field(&map_path, "dst", "dst_anycast", message.dst_ref())?;
+    Ok(message)
+}
+
+fn parse_message_fields(map_path: &PathMap) -> Result<Message> {
+    let src_int = || -> Result<MsgAddressIntOrNone> {
+        match map_path.get_str("src")? {
+            "" => Ok(MsgAddressIntOrNone::None),
+            _ => Ok(MsgAddressIntOrNone::Some(parse_address(map_path, "src", "src_anycast")?)),
+        }
+    };
+    let ext = |name: &'static str| -> Result<MsgAddressExt> {
+        match MsgAddress::from_str(map_path.get_str(name)?)? {
+            MsgAddress::AddrNone => Ok(MsgAddressExt::AddrNone),
+            MsgAddress::AddrExt(address) => Ok(MsgAddressExt::AddrExtern(address)),
+            _ => Err(map_path.invalid_value(name, "the external address", "internal address is given".to_string()))
+        }
+    };
+    let mut message = match map_path.get_num("msg_type")? {
+        0 => Message::with_int_header(InternalMessageHeader {
+            ihr_disabled: map_path.get_bool("ihr_disabled")?,
+            bounce: map_path.get_bool("bounce")?,
+            bounced: map_path.get_bool("bounced")?,
+            src: src_int()?,
+            dst: parse_address(map_path, "dst", "dst_anycast")?,
+            value: map_path.get_cc("value")?,
+            ihr_fee: map_path.get_grams("ihr_fee")?,
+            fwd_fee: map_path.get_grams("fwd_fee")?,
+            created_lt: map_path.get_u64("created_lt")?,
+            created_at: UnixTime32::new(map_path.get_int("created_at")?),
+        }),
+        1 => Message::with_ext_in_header(ExternalInboundMessageHeader {
+            src: ext("src")?,
+            dst: parse_address(map_path, "dst", "dst_anycast")?,
+            import_fee: map_path.get_grams("import_fee")?,
+        }),
+        2 => Message::with_ext_out_header(ExtOutMessageHeader {
+            src: src_int()?,
+            dst: ext("dst")?,
+            created_lt: map_path.get_u64("created_lt")?,
+            created_at: UnixTime32::new(map_path.get_int("created_at")?),
+        }),
+        msg_type => fail!("{}/msg_type {} is not the existing message type", map_path.path.join("/"), msg_type)
+    };
+    if let Some(state_init) = parse_state_init_fields(map_path)? {
+        message.set_state_init(state_init);
+    }
+    if has_field(map_path, "body") {
+        message.set_body(SliceData::load_cell(read_single_root_boc(map_path.get_base64("body")?)?)?);
+    }
+    check_id(map_path, &message.serialize()?)?;
+    Ok(message)
+}
+
+/// Reads the state init of account and message documents, `None` if there are no
+/// state init fields. Present fields must be valid.
+fn parse_state_init_fields(map_path: &PathMap) -> Result<Option<StateInit>> {
+    const FIELDS: [&str; 6] = ["code", "data", "library", "split_depth", "tick", "tock"];
+    if !FIELDS.iter().any(|name| has_field(map_path, name)) {
+        return Ok(None)
+    }
+    let cell = |name: &'static str| -> Result<Option<Cell>> {
+        match has_field(map_path, name) {
+            true => Ok(Some(read_single_root_boc(map_path.get_base64(name)?)?)),
+            false => Ok(None)
+        }
+    };
+    let mut state_init = StateInit::default();
+    state_init.code = cell("code")?;
+    state_init.data = cell("data")?;
+    state_init.library = StateInitLib::with_hashmap(cell("library")?);
+    if has_field(map_path, "split_depth") {
+        state_init.set_split_depth(Number5::new(map_path.get_int("split_depth")?)?);
+    }
+    match (has_field(map_path, "tick"), has_field(map_path, "tock")) {
+        (false, false) => (),
+        _ => state_init.set_special(TickTock::with_values(map_path.get_bool("tick")?, map_path.get_bool("tock")?)),
+    }
+    Ok(Some(state_init))
+}
+
+/// Restores the account from the document produced by `db_serialize_account`. Documents
+/// without `boc` (e.g. stripped by reducers) are rebuilt from the parts: `id` with `id_anycast`, `acc_type`,
+/// `balance` with `balance_other`, `last_trans_lt`, `last_paid`, `due_payment`,
+/// `code`/`data`/`library` with `split_depth`, `tick` and `tock` for active accounts and
+/// `state_hash` for frozen ones. Storage stat of the rebuilt account is recalculated.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_account_set()?;
+/// let mut doc = db_serialize_account("id", &set)?;
+/// assert_eq!(parse_account(&doc)?, set.account);
+/// doc.remove("boc");
+/// assert_eq!(parse_account(&doc)?.state_init(), set.account.state_init());
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_account(map: &Map<String, Value>) -> Result<Account> {
+    let map_path = PathMap::new(map);
+    if map.get("boc").map_or(false, |boc| !boc.is_null()) {
+        return Account::construct_from_bytes(&map_path.get_base64("boc")?)
+    }
+    let address = parse_address(&map_path, "id", "id_anycast")?;
+    let balance = map_path.get_cc("balance")?;
+    let last_trans_lt = map_path.get_u64("last_trans_lt")?;
+    let storage = match map_path.get_num("acc_type")? {
+        0b00 => AccountStorage::unint(last_trans_lt, balance),
+        0b01 => {
+            let state_init = parse_state_init_fields(&map_path)?.unwrap_or_default();
+            AccountStorage::active_by_init_code_hash(last_trans_lt, balance, state_init, false)
+        }
+        0b10 => AccountStorage::frozen(last_trans_lt, balance, map_path.get_uint256("state_hash")?),
+        acc_type => fail!("{}/acc_type {} is not the existing account status", map_path.path.join("/"), acc_type)
+    };
+    let due_payment = map_path.get_opt("due_payment", PathMap::get_grams)?;
+    let last_paid = map_path.get_opt("last_paid", PathMap::get_int)?.unwrap_or_default();
+    let mut account = Account::with_storage(
+        &address,
+        &StorageInfo::with_values(last_paid, due_payment),
+        &storage
+    );
+    if let Some(init_code_hash) = map_path.get_opt("init_code_hash", PathMap::get_uint256)? {
+        account.set_init_code_hash(init_code_hash);
+    }
+    account.update_storage_stat()?;
+    Ok(account)
+}
+
+/// Fields filled by the serializers from the serialization set rather than from the object,
+/// they are absent in the re-serialized documents but are not extras
+const TRANSACTION_CONTEXT_FIELDS: &[&str] = &["block_id", "proof", "status", "status_name"];
+const MESSAGE_CONTEXT_FIELDS: &[&str] = &[
+    "block_id", "transaction_id", "proof", "status", "status_name", "created_at",
+];
+const ACCOUNT_CONTEXT_FIELDS: &[&str] = &["proof", "boc1", "prev_code_hash"];
+
+/// Extra fields of the document absent in the re-serializations in all the modes
+/// and not filled from the serialization set context
+fn collect_document_extras(
+    doc: &Map<String, Value>,
+    known: [&Map<String, Value>; 3],
+    context: &[&str]
+) -> Map<String, Value> {
+    let mut extras = collect_extra_fields(doc, &known);
+    extras.retain(|name, _| !context.contains(&name.as_str()));
+    extras
+}
+
+/// Returns fields of the document absent in all of the `known` documents, nested objects
+/// are compared field by field, arrays and values are compared as a whole
+fn collect_extra_fields(doc: &Map<String, Value>, known: &[&Map<String, Value>]) -> Map<String, Value> {
+    let mut extras = Map::new();
+    for (name, value) in doc {
+        let known_values = known.iter().filter_map(|map| map.get(name)).collect::<Vec<_>>();
+        if known_values.is_empty() {
+            extras.insert(name.clone(), value.clone());
+        } else if let Value::Object(map) = value {
+            let known_maps = known_values.iter().filter_map(|value| value.as_object()).collect::<Vec<_>>();
+            let nested = collect_extra_fields(map, &known_maps);
+            if !nested.is_empty() {
+                extras.insert(name.clone(), nested.into());
+            }
+        }
+    }
+    extras
+}
+
+/// Adds the extra fields returned by `parse_*_with_extras` to the document produced
+/// by the serializer. Fields present in the document are not overwritten.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_message_set()?;
+/// let mut doc = db_serialize_message("id", &set)?;
+/// doc.insert("annotation".to_string(), "spam".into());
+/// let (message, extras) = parse_message_with_extras(&doc)?;
+/// let mut set = samples::sample_message_set()?;
+/// set.message = message;
+/// let mut reserialized = db_serialize_message("id", &set)?;
+/// attach_extra_fields(&mut reserialized, &extras);
+/// assert_eq!(reserialized["annotation"], "spam");
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn attach_extra_fields(doc: &mut Map<String, Value>, extras: &Map<String, Value>) {
+    for (name, value) in extras {
+        match (doc.get_mut(name), value) {
+            (Some(Value::Object(map)), Value::Object(extras)) => attach_extra_fields(map, extras),
+            (Some(_), _) => (),
+            (None, value) => {
+                doc.insert(name.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Parses the transaction like `parse_transaction` and returns fields of the document
+/// which are not produced by the serializer in any mode, e.g. third-party annotations,
+/// to be restored with `attach_extra_fields`.
+pub fn parse_transaction_with_extras(map: &Map<String, Value>) -> Result<(Transaction, Map<String, Value>)> {
+    let transaction = parse_transaction(map)?;
+    let cell = transaction.serialize()?;
+    let set = crate::TransactionSerializationSet {
+        id: cell.repr_hash(),
+        boc: write_boc(&cell)?,
+        transaction: transaction.clone(),
+        status: TransactionProcessingStatus::Finalized,
+        block_id: None,
+        workchain_id: PathMap::new(map).get_num("workchain_id").unwrap_or_default() as i32,
+        proof: None,
+    };
+    let standart = crate::db_serialize_transaction_ex("id", &set, crate::SerializationMode::Standart)?;
+    let q_server = crate::db_serialize_transaction_ex("id", &set, crate::SerializationMode::QServer)?;
+    let debug = crate::db_serialize_transaction_ex("id", &set, crate::SerializationMode::Debug)?;
+    let extras = collect_document_extras(map, [&standart, &q_server, &debug], TRANSACTION_CONTEXT_FIELDS);
+    Ok((transaction, extras))
+}
+
+/// Parses the message like `parse_message` and returns fields of the document
+/// which are not produced by the serializer in any mode, e.g. third-party annotations,
+/// to be restored with `attach_extra_fields`.
+pub fn parse_message_with_extras(map: &Map<String, Value>) -> Result<(Message, Map<String, Value>)> {
+    let message = parse_message(map)?;
+    let cell = message.serialize()?;
+    let set = crate::MessageSerializationSet {
+        id: cell.repr_hash(),
+        boc: write_boc(&cell)?,
+        message: message.clone(),
+        ..Default::default()
+    };
+    let standart = crate::db_serialize_message_ex("id", &set, crate::SerializationMode::Standart)?;
+    let q_server = crate::db_serialize_message_ex("id", &set, crate::SerializationMode::QServer)?;
+    let debug = crate::db_serialize_message_ex("id", &set, crate::SerializationMode::Debug)?;
+    let extras = collect_document_extras(map, [&standart, &q_server, &debug], MESSAGE_CONTEXT_FIELDS);
+    Ok((message, extras))
+}
+
+/// Parses the account like `parse_account` and returns fields of the document
+/// which are not produced by the serializer in any mode, e.g. third-party annotations,
+/// to be restored with `attach_extra_fields`.
+pub fn parse_account_with_extras(map: &Map<String, Value>) -> Result<(Account, Map<String, Value>)> {
+    let account = parse_account(map)?;
+    let set = crate::AccountSerializationSet {
+        boc: account.write_to_bytes()?,
+        account: account.clone(),
+        prev_code_hash: None,
+        boc1: None,
+        proof: None,
+    };
+    let standart = crate::db_serialize_account_ex("id", &set, crate::SerializationMode::Standart)?;
+    let q_server = crate::db_serialize_account_ex("id", &set, crate::SerializationMode::QServer)?;
+    let debug = crate::db_serialize_account_ex("id", &set, crate::SerializationMode::Debug)?;
+    let extras = collect_document_extras(map, [&standart, &q_server, &debug], ACCOUNT_CONTEXT_FIELDS);
+    Ok((account, extras))
+}
+
 pub fn parse_block_proof(
     map: &Map<String, Value>, block_file_hash: UInt256
 ) -> Result<ever_block::BlockProof> {
@@ -0,0 +1,245 @@
+/*
+ * Copyright (C) 2019-2023 EverX. All Rights Reserved.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.  You may obtain a copy of the
+ * License at:
+ *
+ * https://www.ever.dev/licenses
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific EVERX DEV software governing permissions and limitations
+ * under the License.
+ */
+
+//! Formatting of numbers, shards and addresses used by the serializers.
+//! The module depends on `core` and `alloc` only: verifiers built without `std` can copy
+//! or reuse it to get exactly the same strings as in the documents.
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::Write;
+
+#[derive(Clone, Copy)]
+pub enum SerializationMode {
+    Standart,
+    QServer,
+    Debug,
+}
+
+impl SerializationMode {
+    pub fn is_standart(&self) -> bool {
+        matches!(self, SerializationMode::Standart)
+    }
+
+    pub fn is_q_server(&self) -> bool {
+        matches!(self, SerializationMode::QServer | SerializationMode::Debug)
+    }
+}
+
+/// Options of the serializers: the mode of numbers, the format of hashes and
+/// the layout of `*_other` fields
+#[derive(Clone, Copy)]
+pub struct SerializationOptions {
+    pub mode: SerializationMode,
+    pub hash_format: HashFormat,
+    pub other_currencies_layout: OtherCurrenciesLayout,
+}
+
+impl SerializationOptions {
+    pub fn new(mode: SerializationMode) -> Self {
+        Self {
+            mode,
+            hash_format: HashFormat::default(),
+            other_currencies_layout: OtherCurrenciesLayout::default(),
+        }
+    }
+
+    pub fn with_hash_format(mut self, hash_format: HashFormat) -> Self {
+        self.hash_format = hash_format;
+        self
+    }
+
+    pub fn with_other_currencies_layout(mut self, layout: OtherCurrenciesLayout) -> Self {
+        self.other_currencies_layout = layout;
+        self
+    }
+
+    pub fn is_standart(&self) -> bool {
+        self.mode.is_standart()
+    }
+
+    pub fn is_q_server(&self) -> bool {
+        self.mode.is_q_server()
+    }
+}
+
+impl From<SerializationMode> for SerializationOptions {
+    fn from(mode: SerializationMode) -> Self {
+        Self::new(mode)
+    }
+}
+
+/// Hex string prefixed with the length of the hex minus one (one hex digit),
+/// so strings are ordered like numbers
+pub fn u64_to_string(value: u64) -> String {
+    let mut string = format!("{:x}", value);
+    string.insert_str(0, &format!("{:x}", string.len() - 1));
+    string
+}
+
+/// Same as `u64_to_string` but the length prefix has two hex digits
+pub fn u128_to_string(value: u128) -> String {
+    let mut string = format!("{:x}", value);
+    string.insert_str(0, &format!("{:02x}", string.len() - 1));
+    string
+}
+
+pub fn shard_to_string(value: u64) -> String {
+    format!("{:016x}", value)
+}
+
+/// Formats u64 values which have no `_lt` semantics
+pub fn format_u64(value: u64, mode: SerializationMode) -> String {
+    match mode {
+        SerializationMode::Standart => u64_to_string(value),
+        SerializationMode::QServer => format!("0x{:x}", value),
+        SerializationMode::Debug => format!("{}", value),
+    }
+}
+
+/// Formats logical time, in debug mode as `<millions>_<rest>`
+pub fn format_lt(value: u64, mode: SerializationMode) -> String {
+    match mode {
+        SerializationMode::Debug => format!("{}_{}", value / 1_000_000, value % 1_000_000),
+        _ => format_u64(value, mode),
+    }
+}
+
+/// Formats grams and other currency values
+pub fn format_grams(value: u128, mode: SerializationMode) -> String {
+    match mode {
+        SerializationMode::Standart => u128_to_string(value),
+        SerializationMode::QServer => format!("0x{:x}", value),
+        SerializationMode::Debug => format!("{}", value),
+    }
+}
+
+/// `<workchain_id>:<hex account id>` as in `MsgAddressInt` display
+pub fn std_address_to_string(workchain_id: i32, account_id: &[u8]) -> String {
+    let mut string = format!("{}:", workchain_id);
+    for byte in account_id {
+        let _ = write!(string, "{:02x}", byte);
+    }
+    string
+}
+
+/// Address prefix of the intermediate address
+pub fn address_prefix_to_string(workchain_id: i32, addr_pfx: u64) -> String {
+    format!("{}:{:x}", workchain_id, addr_pfx)
+}
+
+/// Format of 256-bit hashes in documents, by default lowercase hex without prefix
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HashFormat {
+    /// `0x` prefix
+    pub prefix: bool,
+    pub uppercase: bool,
+}
+
+impl HashFormat {
+    pub fn format_hash(&self, hash: &[u8]) -> String {
+        let mut string = String::with_capacity(hash.len() * 2 + 2);
+        if self.prefix {
+            string.push_str("0x");
+        }
+        for byte in hash {
+            let _ = match self.uppercase {
+                true => write!(string, "{:02X}", byte),
+                false => write!(string, "{:02x}", byte),
+            };
+        }
+        string
+    }
+
+    /// Formats a hex string of arbitrary length like `rewrite_pfx`
+    pub fn format_hex(&self, hex: &str) -> String {
+        let mut string = String::with_capacity(hex.len() + 2);
+        if self.prefix {
+            string.push_str("0x");
+        }
+        match self.uppercase {
+            true => string.extend(hex.chars().map(|c| c.to_ascii_uppercase())),
+            false => string.extend(hex.chars().map(|c| c.to_ascii_lowercase())),
+        }
+        string
+    }
+}
+
+/// Layout of `*_other` extra currencies fields in documents
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OtherCurrenciesLayout {
+    /// Array of `{ currency, value }` objects
+    #[default]
+    Array,
+    /// Object with values keyed by currency id: `{ "123": "0x..." }`,
+    /// values are decimal strings in `Standart` mode
+    Object,
+}
+
+/// Known bits of the global capabilities as in `GlobalCapabilities` of ever-block,
+/// `test_capabilities_match_global_capabilities` checks the table against the enum
+pub const CAPABILITIES: &[(u64, &str)] = &[
+    (0x0000_0001, "CapIhrEnabled"),
+    (0x0000_0002, "CapCreateStatsEnabled"),
+    (0x0000_0004, "CapBounceMsgBody"),
+    (0x0000_0008, "CapReportVersion"),
+    (0x0000_0010, "CapSplitMergeTransactions"),
+    (0x0000_0020, "CapShortDequeue"),
+    (0x0000_0040, "CapMbppEnabled"),
+    (0x0000_0080, "CapFastStorageStat"),
+    (0x0000_0100, "CapInitCodeHash"),
+    (0x0000_0200, "CapOffHypercube"),
+    (0x0000_0400, "CapMycode"),
+    (0x0000_0800, "CapSetLibCode"),
+    (0x0000_1000, "CapFixTupleIndexBug"),
+    (0x0000_2000, "CapRemp"),
+    (0x0000_4000, "CapDelections"),
+    (0x0001_0000, "CapFullBodyInBounced"),
+    (0x0002_0000, "CapStorageFeeToTvm"),
+    (0x0004_0000, "CapCopyleft"),
+    (0x0008_0000, "CapIndexAccounts"),
+    (0x0010_0000, "CapDiff"),
+    (0x0020_0000, "CapsTvmBugfixes2022"),
+    (0x0040_0000, "CapWorkchains"),
+    (0x0080_0000, "CapStcontNewFormat"),
+    (0x0100_0000, "CapFastStorageStatBugfix"),
+    (0x0200_0000, "CapResolveMerkleCell"),
+    (0x0400_0000, "CapSignatureWithId"),
+    (0x0800_0000, "CapBounceAfterFailedAction"),
+    (0x1000_0000, "CapGroth16"),
+    (0x2000_0000, "CapFeeInGasUnits"),
+    (0x4000_0000, "CapBigCells"),
+    (0x8000_0000, "CapSuspendedList"),
+    (0x0001_0000_0000, "CapFastFinality"),
+    (0x0002_0000_0000, "CapTvmV19"),
+    (0x0004_0000_0000, "CapSmft"),
+    (0x0008_0000_0000, "CapNoSplitOutQueue"),
+    (0x0010_0000_0000, "CapUndeletableAccounts"),
+    (0x0020_0000_0000, "CapTvmV20"),
+    (0x0040_0000_0000, "CapDuePaymentFix"),
+    (0x0080_0000_0000, "CapCommonMessage"),
+];
+
+/// Names of the known capabilities set in `capabilities`, unknown bits are skipped
+pub fn capabilities_names(capabilities: u64) -> Vec<&'static str> {
+    CAPABILITIES.iter()
+        .filter(|(bit, _)| capabilities & bit != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "tests/test_format.rs"]
+mod tests;
@@ -14,12 +14,22 @@
  * under the License.
  */
 
+extern crate alloc;
+
+mod format;
+pub use self::format::*;
 mod serialize;
 pub use self::serialize::*;
+mod status;
+pub use self::status::*;
+#[cfg(any(test, doc, feature = "samples"))]
+pub mod samples;
 mod block_parser;
+mod columnar;
 mod deserialize;
 
 pub use self::deserialize::*;
 pub use block_parser::*;
+pub use self::columnar::*;
 
 include!("../common/src/info.rs");
@@ -0,0 +1,149 @@
+/*
+ * Copyright (C) 2019-2023 EverX. All Rights Reserved.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.  You may obtain a copy of the
+ * License at:
+ *
+ * https://www.ever.dev/licenses
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific EVERX DEV software governing permissions and limitations
+ * under the License.
+ */
+
+//! Tiny synthetic structures used by the examples in the documentation of serializers
+//! and parsers. The structures are valid enough to be serialized but don't come from
+//! any real network.
+//!
+//! The module is built for tests and documentation only or with the `samples` feature,
+//! the examples using it run with `cargo test --features samples`.
+
+use crate::{
+    AccountSerializationSet, BlockSerializationSet, MessageSerializationSet,
+    ShardStateSerializationSet, TransactionSerializationSet,
+};
+use ever_block::{
+    generate_test_account_by_init_code_hash, Account, AccountId, AccountStatus, Block,
+    BlockExtra, BlockInfo, BlockProcessingStatus, CommonMessage, CurrencyCollection, HashUpdate,
+    InternalMessageHeader, MerkleUpdate, Message, MessageProcessingStatus, MsgAddressInt, Result,
+    Serializable, ShardIdent, ShardStateUnsplit, SliceData, Transaction, TransactionDescr,
+    TransactionProcessingStatus, ValueFlow,
+};
+
+/// Account id of all the sample addresses
+pub const SAMPLE_ACCOUNT_ID: [u8; 32] = [0x11; 32];
+
+pub fn sample_address(workchain_id: i8) -> Result<MsgAddressInt> {
+    MsgAddressInt::with_standart(None, workchain_id, AccountId::from(SAMPLE_ACCOUNT_ID))
+}
+
+/// Internal message of 1 token from the masterchain to the basechain sample address
+pub fn sample_message() -> Result<Message> {
+    let header = InternalMessageHeader::with_addresses(
+        sample_address(-1)?,
+        sample_address(0)?,
+        CurrencyCollection::with_grams(1_000_000_000),
+    );
+    let mut message = Message::with_int_header(header);
+    message.set_body(SliceData::new(vec![0x12, 0x34, 0x80]));
+    Ok(message)
+}
+
+/// Ordinary transaction of the basechain sample account with `sample_message` inbound
+pub fn sample_transaction() -> Result<Transaction> {
+    let mut transaction = Transaction::with_address_and_status(
+        AccountId::from(SAMPLE_ACCOUNT_ID),
+        AccountStatus::AccStateActive,
+    );
+    transaction.set_logical_time(2_000_001);
+    transaction.write_in_msg(Some(&CommonMessage::Std(sample_message()?)))?;
+    transaction.write_state_update(&HashUpdate::default())?;
+    transaction.write_description(&TransactionDescr::default())?;
+    Ok(transaction)
+}
+
+/// Active account with code, data and libraries
+pub fn sample_account() -> Account {
+    generate_test_account_by_init_code_hash(false)
+}
+
+/// Empty basechain block without transactions
+pub fn sample_block() -> Result<Block> {
+    Block::with_params(
+        0,
+        BlockInfo::default(),
+        ValueFlow::default(),
+        MerkleUpdate::default(),
+        BlockExtra::default(),
+    )
+}
+
+/// Empty masterchain state
+pub fn sample_state() -> ShardStateUnsplit {
+    ShardStateUnsplit::with_ident(ShardIdent::masterchain())
+}
+
+pub fn sample_block_set() -> Result<BlockSerializationSet> {
+    let block = sample_block()?;
+    let cell = block.serialize()?;
+    Ok(BlockSerializationSet {
+        id: cell.repr_hash(),
+        boc: block.write_to_bytes()?,
+        block,
+        status: BlockProcessingStatus::Finalized,
+    })
+}
+
+pub fn sample_transaction_set() -> Result<TransactionSerializationSet> {
+    let transaction = sample_transaction()?;
+    let cell = transaction.serialize()?;
+    Ok(TransactionSerializationSet {
+        id: cell.repr_hash(),
+        boc: transaction.write_to_bytes()?,
+        transaction,
+        status: TransactionProcessingStatus::Finalized,
+        block_id: None,
+        workchain_id: 0,
+        proof: None,
+    })
+}
+
+pub fn sample_message_set() -> Result<MessageSerializationSet> {
+    let message = sample_message()?;
+    let cell = message.serialize()?;
+    Ok(MessageSerializationSet {
+        id: cell.repr_hash(),
+        boc: message.write_to_bytes()?,
+        message,
+        block_id: None,
+        transaction_id: None,
+        transaction_now: None,
+        status: MessageProcessingStatus::Finalized,
+        proof: None,
+    })
+}
+
+pub fn sample_account_set() -> Result<AccountSerializationSet> {
+    let account = sample_account();
+    Ok(AccountSerializationSet {
+        boc: account.write_to_bytes()?,
+        account,
+        prev_code_hash: None,
+        boc1: None,
+        proof: None,
+    })
+}
+
+pub fn sample_state_set() -> Result<ShardStateSerializationSet> {
+    let state = sample_state();
+    Ok(ShardStateSerializationSet {
+        boc: state.write_to_bytes()?,
+        id: format!("{}", state.shard()),
+        workchain_id: state.shard().workchain_id(),
+        block_id: None,
+        state,
+    })
+}
@@ -14,14 +14,22 @@
  * under the License.
  */
 
+use crate::format::{
+    address_prefix_to_string, capabilities_names, format_grams, format_lt, format_u64,
+    shard_to_string, u64_to_string, OtherCurrenciesLayout, SerializationMode,
+    SerializationOptions,
+};
+use crate::status::{
+    BlockProcessingStatusJson, MessageProcessingStatusJson, TransactionProcessingStatusJson,
+};
 use ever_block::*;
 use ton_api::ton::ton_node::{RempMessageLevel, RempMessageStatus, RempReceipt};
 use num::BigInt;
 use num_traits::sign::Signed;
 use serde_json::{Map, Value};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-const VERSION: u32 = 8;
+const VERSION: u32 = 21;
 // Version changes
 // 2 - fix var account addresses tag in block (`8_` postfix)
 // 3 - `balance_delta` added to transaction
@@ -30,23 +38,193 @@ const VERSION: u32 = 8;
 // 6 - init_code_hash in account
 // 7 - ext_in_msg_fee in transaction
 // 8 - file_hash in block and shard state
+// 9 - action phase totals in transaction
+// 10 - vertical block refs with `vert_seq_no`
+// 11 - last_active_at in account
+// 12 - parents of merge blocks
+// 13 - capabilities flag names
+// 14 - value and destination of recover and mint messages
+// 15 - in_msg value, source and bounce in parsed transactions
+// 16 - master_anchor in shard block
+// 17 - dictionary indices of storage prices in config p18
+// 18 - gen_utime_ms in block and shard descriptions
+// 19 - explicit anycast fields of addresses
+// 20 - collators and proof chain in shard descriptions
+// 21 - proof_for and block refs in proof
 
 const STD_ACCOUNT_ID_LENGTH: usize = 256;
 
-#[derive(Clone, Copy)]
-pub enum SerializationMode {
-    Standart,
-    QServer,
-    Debug,
+/// Field scheduled for removal from documents of the given kind
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeprecatedField {
+    /// `block`, `transaction`, `message` or `account`
+    pub kind: &'static str,
+    pub field: &'static str,
+    /// `json_version` without the field
+    pub removed_in: u32,
+    pub replacement: &'static str,
 }
 
-impl SerializationMode {
-    pub fn is_standart(&self) -> bool {
-        matches!(self, SerializationMode::Standart)
+/// No fields are scheduled for removal yet
+pub const DEPRECATED_FIELDS: &[DeprecatedField] = &[];
+
+/// Deprecated fields present in the document of the given kind
+pub fn find_deprecated_fields(doc: &Map<String, Value>, kind: &str) -> Vec<&'static DeprecatedField> {
+    find_fields(DEPRECATED_FIELDS, doc, kind)
+}
+
+fn find_fields<'a>(fields: &'a [DeprecatedField], doc: &Map<String, Value>, kind: &str) -> Vec<&'a DeprecatedField> {
+    fields.iter()
+        .filter(|deprecated| deprecated.kind == kind && doc.contains_key(deprecated.field))
+        .collect()
+}
+
+/// Annotates deprecated fields of the document under `_deprecated` to let consumers
+/// migrate before the fields are removed.
+pub fn annotate_deprecated_fields(doc: &mut Map<String, Value>, kind: &str) {
+    annotate_fields(DEPRECATED_FIELDS, doc, kind)
+}
+
+fn annotate_fields(fields: &[DeprecatedField], doc: &mut Map<String, Value>, kind: &str) {
+    let deprecated = find_fields(fields, doc, kind).into_iter()
+        .map(|deprecated| serde_json::json!({
+            "field": deprecated.field,
+            "removed_in": deprecated.removed_in,
+            "replacement": deprecated.replacement,
+        }))
+        .collect::<Vec<_>>();
+    if !deprecated.is_empty() {
+        serialize_field(doc, "_deprecated", deprecated);
+    }
+}
+
+/// Optional fields of the documents of each kind, emitted only when the value exists.
+/// Fields of nested sections are given by `/` separated paths, arrays on the path are
+/// traversed item by item. Documents of other kinds embedded into the document
+/// are listed in `NESTED_DOCUMENTS`.
+pub const OPTIONAL_FIELDS: &[(&str, &[&str])] = &[
+    ("block", &[
+        "master", "master_anchor", "prev_alt_ref", "prev_vert_ref", "prev_vert_alt_ref", "master_ref",
+        "gen_software_version", "gen_software_capabilities", "gen_utime_ms", "file_hash",
+        "stats", "value_flow/minted_other", "value_flow/fees_imported_other",
+        "value_flow/from_prev_blk_other", "value_flow/to_next_blk_other", "value_flow/imported_other",
+        "value_flow/exported_other", "value_flow/fees_collected_other", "value_flow/recovered_other",
+        "value_flow/created_other", "master/config", "master/config_addr", "master/shard_fees",
+        "master/recover_create_msg", "master/mint_msg",
+    ]),
+    ("transaction", &[
+        "block_id", "proof", "in_msg", "storage", "credit", "compute", "action", "bounce",
+        "split_info", "prepare_transaction", "installed", "ext_in_msg_fee", "account_addr_anycast",
+        "action_success", "action_valid", "msgs_created", "skipped_actions", "balance_delta_other",
+        "total_fees_other", "storage/storage_fees_due", "credit/due_fees_collected", "credit/credit_other",
+        "compute/skipped_reason", "compute/success", "compute/msg_state_used", "compute/account_activated",
+        "compute/gas_fees", "compute/gas_used", "compute/gas_limit", "compute/gas_credit", "compute/mode",
+        "compute/exit_code", "compute/exit_arg", "compute/vm_steps", "compute/vm_init_state_hash",
+        "compute/vm_final_state_hash", "action/total_fwd_fees", "action/total_action_fees",
+        "action/result_arg", "bounce/msg_size_cells", "bounce/msg_size_bits", "bounce/req_fwd_fees",
+        "bounce/msg_fees", "bounce/fwd_fees",
+    ]),
+    ("message", &[
+        "block_id", "transaction_id", "proof", "split_depth", "tick", "tock", "code", "code_hash",
+        "data", "data_hash", "library", "library_hash", "body", "body_hash", "src", "dst",
+        "src_workchain_id", "dst_workchain_id", "value", "value_other", "ihr_fee", "fwd_fee",
+        "import_fee", "bounce", "bounced", "created_lt", "created_at", "src_anycast", "dst_anycast",
+    ]),
+    ("account", &[
+        "proof", "boc1", "split_depth", "tick", "tock", "code", "code_hash", "data", "data_hash",
+        "library", "library_hash", "state_hash", "due_payment", "init_code_hash", "prev_code_hash",
+        "balance_other", "id_anycast",
+    ]),
+    ("block_proof", &[
+        "master_ref", "prev_alt_ref", "prev_vert_ref", "prev_vert_alt_ref",
+        "validator_list_hash_short", "catchain_seqno", "sig_weight", "signatures", "signers_bitmap",
+    ]),
+    ("block_signatures", &["proof", "signers_bitmap"]),
+    ("shard_state", &[
+        "block_id", "master_ref", "master", "total_balance_other", "total_validator_fees_other",
+        "workchains",
+    ]),
+];
+
+/// Documents embedded into the documents of other kinds as `(kind, path, embedded kind)`
+pub const NESTED_DOCUMENTS: &[(&str, &str, &str)] = &[
+    ("block", "account_blocks/transactions/transaction", "transaction"),
+    ("transaction", "in_message", "message"),
+    ("transaction", "out_messages", "message"),
+    ("shard_state", "accounts", "account"),
+];
+
+/// How absent optional fields are represented in the documents
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AbsentFieldsPolicy {
+    /// Absent fields are not present in the document, `null` fields are removed
+    /// at any depth
+    #[default]
+    OmitAbsent,
+    /// Absent optional fields of `OPTIONAL_FIELDS` are present with `null` value,
+    /// including the fields of nested sections and embedded documents
+    NullAbsent,
+}
+
+/// Applies the policy to the document of the given kind produced by any of the serializers.
+/// Parsers treat `null` fields the same way as absent ones, so both forms are accepted.
+pub fn apply_absent_fields_policy(doc: &mut Map<String, Value>, kind: &str, policy: AbsentFieldsPolicy) {
+    match policy {
+        AbsentFieldsPolicy::OmitAbsent => remove_null_fields(doc),
+        AbsentFieldsPolicy::NullAbsent => {
+            let fields = OPTIONAL_FIELDS.iter()
+                .filter(|(fields_kind, _)| *fields_kind == kind)
+                .flat_map(|(_, fields)| fields.iter());
+            for path in fields {
+                insert_null_field(doc, path);
+            }
+            for (_, path, nested_kind) in NESTED_DOCUMENTS.iter().filter(|(doc_kind, _, _)| *doc_kind == kind) {
+                for_each_section(doc, path, &mut |nested| apply_absent_fields_policy(nested, nested_kind, policy));
+            }
+        }
+    }
+}
+
+fn remove_null_fields(map: &mut Map<String, Value>) {
+    map.retain(|_, value| !value.is_null());
+    for value in map.values_mut() {
+        match value {
+            Value::Object(map) => remove_null_fields(map),
+            Value::Array(items) => items.iter_mut()
+                .filter_map(Value::as_object_mut)
+                .for_each(remove_null_fields),
+            _ => ()
+        }
+    }
+}
+
+fn insert_null_field(map: &mut Map<String, Value>, path: &str) {
+    match path.rsplit_once('/') {
+        Some((sections, field)) => for_each_section(map, sections, &mut |section| {
+            section.entry(field.to_string()).or_insert(Value::Null);
+        }),
+        None => {
+            map.entry(path.to_string()).or_insert(Value::Null);
+        }
     }
+}
 
-    pub fn is_q_server(&self) -> bool {
-        matches!(self, SerializationMode::QServer | SerializationMode::Debug)
+/// Calls `f` for every object found by the `/` separated path, arrays are traversed
+fn for_each_section(map: &mut Map<String, Value>, path: &str, f: &mut dyn FnMut(&mut Map<String, Value>)) {
+    let (name, rest) = match path.split_once('/') {
+        Some((name, rest)) => (name, Some(rest)),
+        None => (path, None),
+    };
+    let sections: Vec<&mut Map<String, Value>> = match map.get_mut(name) {
+        Some(Value::Object(section)) => vec![section],
+        Some(Value::Array(items)) => items.iter_mut().filter_map(Value::as_object_mut).collect(),
+        _ => return,
+    };
+    for section in sections {
+        match rest {
+            Some(rest) => for_each_section(section, rest, f),
+            None => f(section),
+        }
     }
 }
 
@@ -116,12 +294,6 @@ fn get_msg_fees(msg: &Message) -> Option<(&Grams, &Grams)> {
     }
 }
 
-pub fn u64_to_string(value: u64) -> String {
-    let mut string = format!("{:x}", value);
-    string.insert_str(0, &format!("{:x}", string.len() - 1));
-    string
-}
-
 pub fn bigint_to_string(value: &BigInt) -> String {
     if num::bigint::Sign::Minus == value.sign() {
         let bytes: Vec<u8> = value.to_bytes_be().1.iter().map(|byte| byte ^ 0xFF).collect();
@@ -160,70 +332,68 @@ fn serialize_grams(
     map: &mut Map<String, Value>,
     id_str: &'static str,
     value: &Grams,
-    mode: SerializationMode
+    mode: SerializationOptions
 ) {
-    let string = match mode {
-        SerializationMode::Standart => {
-            serialize_field(map, &(id_str.to_owned() + "_dec"), value.to_string());
-            let mut string = format!("{:x}", value.as_u128());
-            string.insert_str(0, &format!("{:02x}", string.len() - 1));
-            string
-        }
-        SerializationMode::QServer => {
-            format!("0x{:x}", value.as_u128())
-        }
-        SerializationMode::Debug => format!("{}", value.as_u128())
-    };
-
-    serialize_field(map, id_str, string);
+    if mode.is_standart() {
+        serialize_field(map, &(id_str.to_owned() + "_dec"), value.to_string());
+    }
+    serialize_field(map, id_str, format_grams(value.as_u128(), mode.mode));
 }
 
+/// Fields written by `serialize_u64` as strings. Strict parsing accepts numeric strings
+/// only in them and in logical times.
+pub(crate) const U64_STRING_FIELDS: &[&str] = &[
+    "bit_price", "bit_price_ps", "bits", "block_gas_limit", "capabilities", "cell_price",
+    "cell_price_ps", "cells", "delete_due_limit", "flat_gas_limit", "flat_gas_price",
+    "freeze_due_limit", "gas_credit", "gas_limit", "gas_price", "gas_price_used", "gas_used",
+    "gen_software_capabilities", "lump_price", "mc_bit_price_ps", "mc_cell_price_ps",
+    "overload_history", "prev_weight", "public_cells", "sig_weight", "special_gas_limit",
+    "total", "total_weight", "transaction_id", "underload_history", "weight",
+];
+
 fn serialize_u64(
     map: &mut Map<String, Value>,
     id_str: &'static str,
     value: &u64,
-    mode: SerializationMode
+    mode: SerializationOptions
 ) {
-    let string = match mode {
-        SerializationMode::Standart => {
-            serialize_field(map, &(id_str.to_owned() + "_dec"), value.to_string());
-            u64_to_string(*value)
-        }
-        SerializationMode::QServer => {
-            format!("0x{:x}", value)
-        }
-        SerializationMode::Debug => format!("{}", value)
-    };
-    serialize_field(map, id_str, string);
+    if mode.is_standart() {
+        serialize_field(map, &(id_str.to_owned() + "_dec"), value.to_string());
+    }
+    serialize_field(map, id_str, format_u64(*value, mode.mode));
 }
 
-fn serialize_lt(
+pub(crate) fn serialize_lt(
     map: &mut Map<String, Value>,
     id_str: &'static str,
     value: &u64,
-    mode: SerializationMode
+    mode: SerializationOptions
 ) {
-    let string = match mode {
-        SerializationMode::Standart => {
-            serialize_field(map, &(id_str.to_owned() + "_dec"), value.to_string());
-            u64_to_string(*value)
-        }
-        SerializationMode::QServer => {
-            format!("0x{:x}", value)
-        }
-        SerializationMode::Debug => format!("{}_{}", value / 1_000_000, value % 1_000_000)
-    };
+    if mode.is_standart() {
+        serialize_field(map, &(id_str.to_owned() + "_dec"), value.to_string());
+    }
+    serialize_field(map, id_str, format_lt(*value, mode.mode));
+}
 
-    serialize_field(map, id_str, string);
+fn serialize_capabilities(
+    map: &mut Map<String, Value>,
+    id_str: &'static str,
+    capabilities: u64,
+    mode: SerializationOptions
+) {
+    serialize_u64(map, id_str, &capabilities, mode);
+    if mode.is_q_server() {
+        serialize_field(map, &format!("{}_names", id_str), capabilities_names(capabilities));
+    }
 }
 
 fn serialize_bigint(
     map: &mut Map<String, Value>,
     id_str: &'static str,
     value: &BigInt,
-    mode: SerializationMode
+    mode: SerializationOptions
 ) {
-    let string = match mode {
+    let string = match mode.mode {
         SerializationMode::Standart => {
             bigint_to_string(value)
         }
@@ -237,16 +407,12 @@ fn serialize_bigint(
         SerializationMode::Debug => format!("{}", value)
     };
 
-    if let SerializationMode::Standart = mode {
+    if let SerializationMode::Standart = mode.mode {
         serialize_field(map, &(id_str.to_owned() + "_dec"), value.to_string());
     }
     serialize_field(map, id_str, string);
 }
 
-pub fn shard_to_string(value: u64) -> String {
-    format!("{:016x}", value)
-}
-
 fn construct_address(workchain_id: i32, account_id: AccountId) -> Result<MsgAddressInt> {
     if (-128..=127).contains(&workchain_id)
         && account_id.remaining_bits() == STD_ACCOUNT_ID_LENGTH
@@ -262,6 +428,7 @@ fn serialize_cell(
     id_str: &'static str,
     cell: Option<&Cell>,
     write_hash: bool,
+    mode: SerializationOptions,
 ) -> Result<()> {
     if let Some(cell) = cell {
         if !cell.is_pruned() {
@@ -270,7 +437,7 @@ fn serialize_cell(
         }
         if write_hash {
             let string = id_str.to_owned() + "_hash";
-            serialize_uint256(map, &string, &cell.repr_hash())
+            serialize_uint256(map, &string, &cell.repr_hash(), mode)
         }
     }
     Ok(())
@@ -281,6 +448,7 @@ fn serialize_slice(
     id_str: &'static str,
     slice: Option<&SliceData>,
     write_hash: bool,
+    mode: SerializationOptions,
 ) -> Result<()> {
     if let Some(slice) = slice {
         let cell = slice.clone().into_cell();
@@ -288,34 +456,38 @@ fn serialize_slice(
         serialize_field(map, id_str, base64_encode(bytes));
         if write_hash {
             let string = id_str.to_owned() + "_hash";
-            serialize_uint256(map, &string, &cell.repr_hash())
+            serialize_uint256(map, &string, &cell.repr_hash(), mode)
         }
     }
     Ok(())
 }
 
-fn serialize_id(map: &mut Map<String, Value>, id_str: & str, id: Option<&UInt256>) {
+pub(crate) fn format_uint256(value: &UInt256, mode: SerializationOptions) -> String {
+    mode.hash_format.format_hash(value.as_slice())
+}
+
+fn serialize_id(map: &mut Map<String, Value>, id_str: & str, id: Option<&UInt256>, mode: SerializationOptions) {
     if let Some(id) = id {
-        map.insert(id_str.to_string(), id.as_hex_string().into());
+        map.insert(id_str.to_string(), format_uint256(id, mode).into());
     }
 }
 
-fn serialize_uint256(map: &mut Map<String, Value>, name: & str, value: &UInt256) {
-    map.insert(name.to_string(), value.as_hex_string().into());
+fn serialize_uint256(map: &mut Map<String, Value>, name: & str, value: &UInt256, mode: SerializationOptions) {
+    map.insert(name.to_string(), format_uint256(value, mode).into());
 }
 
 fn serialize_field(map: &mut Map<String, Value>, id_str: &str, value: impl Into<Value>) {
     map.insert(id_str.to_string(), value.into());
 }
 
-fn serialize_split_info(map: &mut Map<String, Value>, split_info: &SplitMergeInfo) {
+fn serialize_split_info(map: &mut Map<String, Value>, split_info: &SplitMergeInfo, mode: SerializationOptions) {
     serialize_field(map, "cur_shard_pfx_len", split_info.cur_shard_pfx_len);
     serialize_field(map, "acc_split_depth", split_info.acc_split_depth);
-    serialize_id(map, "this_addr", Some(&split_info.this_addr));
-    serialize_id(map, "sibling_addr", Some(&split_info.sibling_addr));
+    serialize_id(map, "this_addr", Some(&split_info.this_addr), mode);
+    serialize_id(map, "sibling_addr", Some(&split_info.sibling_addr), mode);
 }
 
-fn serialize_storage_phase<'a>(map: &mut Map<String, Value>, ph: Option<&'a TrStoragePhase>, mode: SerializationMode) -> Option<&'a Grams> {
+fn serialize_storage_phase<'a>(map: &mut Map<String, Value>, ph: Option<&'a TrStoragePhase>, mode: SerializationOptions) -> Option<&'a Grams> {
     if let Some(ph) = ph {
         let mut ph_map = serde_json::Map::new();
         serialize_grams(&mut ph_map, "storage_fees_collected", &ph.storage_fees_collected, mode);
@@ -343,7 +515,7 @@ fn serialize_storage_phase<'a>(map: &mut Map<String, Value>, ph: Option<&'a TrSt
     }
 }
 
-fn serialize_compute_phase<'a>(map: &mut Map<String, Value>, ph: Option<&'a TrComputePhase>, mode: SerializationMode) -> Option<&'a Grams> {
+fn serialize_compute_phase<'a>(map: &mut Map<String, Value>, ph: Option<&'a TrComputePhase>, mode: SerializationOptions) -> Option<&'a Grams> {
     let mut ph_map = serde_json::Map::new();
     let mut fees = None;
     let (type_, type_name) = match ph {
@@ -381,8 +553,8 @@ fn serialize_compute_phase<'a>(map: &mut Map<String, Value>, ph: Option<&'a TrCo
             ph_map.insert("exit_code".to_string(), ph.exit_code.into());
             ph.exit_arg.map(|value| ph_map.insert("exit_arg".to_string(), value.into()));
             ph_map.insert("vm_steps".to_string(), ph.vm_steps.into());
-            serialize_id(&mut ph_map, "vm_init_state_hash", Some(&ph.vm_init_state_hash));
-            serialize_id(&mut ph_map, "vm_final_state_hash", Some(&ph.vm_final_state_hash));
+            serialize_id(&mut ph_map, "vm_init_state_hash", Some(&ph.vm_init_state_hash), mode);
+            serialize_id(&mut ph_map, "vm_final_state_hash", Some(&ph.vm_final_state_hash), mode);
             (1, "vm")
         }
         None => return None
@@ -396,7 +568,7 @@ fn serialize_compute_phase<'a>(map: &mut Map<String, Value>, ph: Option<&'a TrCo
     fees
 }
 
-fn serialize_credit_phase(map: &mut Map<String, Value>, ph: Option<&TrCreditPhase>, mode: SerializationMode) -> Result<()> {
+fn serialize_credit_phase(map: &mut Map<String, Value>, ph: Option<&TrCreditPhase>, mode: SerializationOptions) -> Result<()> {
     if let Some(ph) = ph {
         let mut ph_map = serde_json::Map::new();
         if let Some(grams) = &ph.due_fees_collected {
@@ -408,7 +580,7 @@ fn serialize_credit_phase(map: &mut Map<String, Value>, ph: Option<&TrCreditPhas
     Ok(())
 }
 
-fn serialize_action_phase<'a>(map: &mut Map<String, Value>, ph: Option<&'a TrActionPhase>, mode: SerializationMode) -> Option<&'a Grams> {
+fn serialize_action_phase<'a>(map: &mut Map<String, Value>, ph: Option<&'a TrActionPhase>, mode: SerializationOptions) -> Option<&'a Grams> {
     if let Some(ph) = ph {
         let mut ph_map = serde_json::Map::new();
         ph_map.insert("success".to_string(), ph.success.into());
@@ -433,17 +605,22 @@ fn serialize_action_phase<'a>(map: &mut Map<String, Value>, ph: Option<&'a TrAct
         ph_map.insert("spec_actions".to_string(), ph.spec_actions.into());
         ph_map.insert("skipped_actions".to_string(), ph.skipped_actions.into());
         ph_map.insert("msgs_created".to_string(), ph.msgs_created.into());
-        ph_map.insert("action_list_hash".to_string(), ph.action_list_hash.as_hex_string().into());
+        serialize_id(&mut ph_map, "action_list_hash", Some(&ph.action_list_hash), mode);
         ph_map.insert("tot_msg_size_cells".to_string(), ph.tot_msg_size.cells().into());
         ph_map.insert("tot_msg_size_bits".to_string(), ph.tot_msg_size.bits().into());
         serialize_field(map, "action", ph_map);
+        // duplicated at the top level to allow simple filters without nested queries
+        serialize_field(map, "action_success", ph.success);
+        serialize_field(map, "action_valid", ph.valid);
+        serialize_field(map, "msgs_created", ph.msgs_created);
+        serialize_field(map, "skipped_actions", ph.skipped_actions);
         fees
     } else {
         None
     }
 }
 
-fn serialize_bounce_phase<'a>(map: &mut Map<String, Value>, ph: Option<&'a TrBouncePhase>, mode: SerializationMode) -> Option<&'a Grams> {
+fn serialize_bounce_phase<'a>(map: &mut Map<String, Value>, ph: Option<&'a TrBouncePhase>, mode: SerializationOptions) -> Option<&'a Grams> {
     let mut ph_map = serde_json::Map::new();
     let mut fees = None;
     let (bounce_type, type_name) = match ph {
@@ -472,20 +649,57 @@ fn serialize_bounce_phase<'a>(map: &mut Map<String, Value>, ph: Option<&'a TrBou
     fees
 }
 
-fn serialize_cc(map: &mut Map<String, Value>, prefix: &'static str, cc: &CurrencyCollection, mode: SerializationMode) -> Result<()> {
+fn serialize_cc(map: &mut Map<String, Value>, prefix: &'static str, cc: &CurrencyCollection, mode: SerializationOptions) -> Result<()> {
     serialize_grams(map,  prefix, &cc.grams, mode);
-    let other = serialize_ecc(&cc.other, mode)?;
-    if !other.is_empty() {
-        map.insert(format!("{}_other", prefix), other.into());
-    }
+    serialize_other(map, prefix, serialize_ecc(&cc.other, mode)?, mode);
     Ok(())
 }
 
-fn serialize_ecc(ecc: &ExtraCurrencyCollection, mode: SerializationMode) -> Result<Vec<Map<String, Value>>> {
+/// Inserts `<prefix>_other` with the items of `serialize_ecc` in the layout of the options
+fn serialize_other(
+    map: &mut Map<String, Value>,
+    prefix: &str,
+    other: Vec<Map<String, Value>>,
+    mode: SerializationOptions
+) {
+    if other.is_empty() {
+        return
+    }
+    let other = match mode.other_currencies_layout {
+        OtherCurrenciesLayout::Array => other.into(),
+        OtherCurrenciesLayout::Object => other.into_iter().map(|mut item| {
+            // ids are numbers or decimal strings for ids not fitting u32, see `serialize_currency_id`
+            let currency = match item.remove("currency") {
+                Some(Value::String(currency)) => currency,
+                Some(Value::Number(currency)) => currency.as_u64().unwrap_or_default().to_string(),
+                _ => String::new(),
+            };
+            let value = item.remove("value_dec").or_else(|| item.remove("value")).unwrap_or_default();
+            (currency, value)
+        }).collect::<Map<_, _>>().into(),
+    };
+    map.insert(format!("{}_other", prefix), other);
+}
+
+/// Currency id is the number while it fits u32, longer keys are emitted as decimal strings
+/// to be not truncated
+fn serialize_currency_id(mut key: SliceData) -> Result<Value> {
+    let mut id = num::BigUint::default();
+    while key.remaining_bits() > 0 {
+        id = id * 2u32 + key.get_next_bit()? as u32;
+    }
+    Ok(match num::ToPrimitive::to_u32(&id) {
+        Some(id) => id.into(),
+        None => id.to_string().into(),
+    })
+}
+
+fn serialize_ecc(ecc: &ExtraCurrencyCollection, mode: SerializationOptions) -> Result<Vec<Map<String, Value>>> {
     let mut other = Vec::new();
-    ecc.iterate_with_keys(|key: u32, ref mut value| -> Result<bool> {
+    ecc.iterate_slices(|key, mut value| -> Result<bool> {
+        let value = VarUInteger32::construct_from(&mut value)?;
         let mut other_map = Map::new();
-        serialize_field(&mut other_map, "currency", key);
+        other_map.insert("currency".to_string(), serialize_currency_id(key)?);
         serialize_bigint(&mut other_map, "value", value.value(), mode);
         other.push(other_map);
         Ok(true)
@@ -493,11 +707,24 @@ fn serialize_ecc(ecc: &ExtraCurrencyCollection, mode: SerializationMode) -> Resu
     Ok(other)
 }
 
+fn serialize_anycast(map: &mut Map<String, Value>, name: &str, address: &MsgAddressInt, mode: SerializationOptions) {
+    let anycast = match address {
+        MsgAddressInt::AddrStd(addr) => addr.anycast.as_ref(),
+        MsgAddressInt::AddrVar(addr) => addr.anycast.as_ref(),
+    };
+    if let Some(anycast) = anycast {
+        let mut anycast_map = Map::new();
+        serialize_field(&mut anycast_map, "depth", anycast.depth.as_u32());
+        serialize_field(&mut anycast_map, "rewrite_pfx", mode.hash_format.format_hex(&anycast.rewrite_pfx.as_hex_string()));
+        map.insert(format!("{}_anycast", name), anycast_map.into());
+    }
+}
+
 fn serialize_scc(
     map: &mut Map<String, Value>,
     prefix: &'static str,
     scc: &SignedCurrencyCollection,
-    mode: SerializationMode
+    mode: SerializationOptions
 ) {
     serialize_bigint(map, prefix, &scc.grams, mode);
     let mut other = Vec::new();
@@ -507,9 +734,7 @@ fn serialize_scc(
         serialize_bigint(&mut other_map, "value", value, mode);
         other.push(other_map);
     }
-    if !other.is_empty() {
-        map.insert(format!("{}_other", prefix), other.into());
-    }
+    serialize_other(map, prefix, other, mode);
 }
 
 fn serialize_intermidiate_address(map: &mut Map<String, Value>, id_str: &'static str, addr: &IntermediateAddress) {
@@ -518,20 +743,38 @@ fn serialize_intermidiate_address(map: &mut Map<String, Value>, id_str: &'static
             addr.use_src_bits().to_string()
         },
         IntermediateAddress::Simple(addr) => {
-            format!("{}:{:x}", addr.workchain_id, addr.addr_pfx)
+            address_prefix_to_string(addr.workchain_id as i32, addr.addr_pfx)
         },
         IntermediateAddress::Ext(addr) => {
-            format!("{}:{:x}", addr.workchain_id, addr.addr_pfx)
+            address_prefix_to_string(addr.workchain_id, addr.addr_pfx)
         }
     };
     map.insert(id_str.to_string(), addr.into());
 }
 
-fn serialize_envelope_msg(env: &MsgEnvelope, mode: SerializationMode) -> Map<String, Value> {
+/// Serializes the message envelope like `in_msg`/`out_msg` envelopes of the message descriptors
+pub fn db_serialize_envelope_msg(env: &MsgEnvelope, mode: impl Into<SerializationOptions>) -> Map<String, Value> {
+    let mode = mode.into();
+    serialize_envelope_msg(env, mode)
+}
+
+/// Serializes the inbound message descriptor like items of `in_msg_descr` of the block document
+pub fn db_serialize_in_msg(msg: &InMsg, mode: impl Into<SerializationOptions>) -> Result<Value> {
+    let mode = mode.into();
+    serialize_in_msg(msg, mode)
+}
+
+/// Serializes the outbound message descriptor like items of `out_msg_descr` of the block document
+pub fn db_serialize_out_msg(msg: &OutMsg, mode: impl Into<SerializationOptions>) -> Result<Value> {
+    let mode = mode.into();
+    serialize_out_msg(msg, mode)
+}
+
+fn serialize_envelope_msg(env: &MsgEnvelope, mode: SerializationOptions) -> Map<String, Value> {
     let mut map = Map::new();
     let msg = env.read_message().unwrap_or_default();
-    serialize_id(&mut map, "msg_id", Some(&env.message_cell().repr_hash()));
-    if let SerializationMode::Debug = mode {
+    serialize_id(&mut map, "msg_id", Some(&env.message_cell().repr_hash()), mode);
+    if let SerializationMode::Debug = mode.mode {
         let (cur_prefix, next_prefix) = env.calc_cur_next_prefix().unwrap_or_default();
         if let Some(src) = msg.src_ref() {
             if let Ok(src_prefix) = AccountIdPrefixFull::prefix(src) {
@@ -553,30 +796,30 @@ fn serialize_envelope_msg(env: &MsgEnvelope, mode: SerializationMode) -> Map<Str
     map
 }
 
-fn serialize_in_msg(msg: &InMsg, mode: SerializationMode) -> Result<Value> {
+fn serialize_in_msg(msg: &InMsg, mode: SerializationOptions) -> Result<Value> {
     let mut map = Map::new();
     let (type_, type_name) = match msg {
         InMsg::External(msg) => {
-            serialize_id(&mut map, "msg_id", Some(&msg.message_cell().repr_hash()));
-            serialize_id(&mut map, "transaction_id", Some(&msg.transaction_cell().repr_hash()));
+            serialize_id(&mut map, "msg_id", Some(&msg.message_cell().repr_hash()), mode);
+            serialize_id(&mut map, "transaction_id", Some(&msg.transaction_cell().repr_hash()), mode);
             (0, "external")
         }
         InMsg::IHR(msg) => {
-            serialize_id(&mut map, "msg_id", Some(&msg.message_cell().repr_hash()));
-            serialize_id(&mut map, "transaction_id", Some(&msg.transaction_cell().repr_hash()));
+            serialize_id(&mut map, "msg_id", Some(&msg.message_cell().repr_hash()), mode);
+            serialize_id(&mut map, "transaction_id", Some(&msg.transaction_cell().repr_hash()), mode);
             serialize_grams(&mut map, "ihr_fee", msg.ihr_fee(), mode);
-            serialize_cell(&mut map, "proof_created", Some(msg.proof_created()), false)?;
+            serialize_cell(&mut map, "proof_created", Some(msg.proof_created()), false, mode)?;
             (1, "ihr")
         }
         InMsg::Immediate(msg) => {
             map.insert("in_msg".to_string(), serialize_envelope_msg(&msg.read_envelope_message()?, mode).into());
-            serialize_id(&mut map, "transaction_id", Some(&msg.transaction_cell().repr_hash()));
+            serialize_id(&mut map, "transaction_id", Some(&msg.transaction_cell().repr_hash()), mode);
             serialize_grams(&mut map, "fwd_fee", &msg.fwd_fee, mode);
             (2, "immediately")
         }
         InMsg::Final(msg) => {
             map.insert("in_msg".to_string(), serialize_envelope_msg(&msg.read_envelope_message()?, mode).into());
-            serialize_id(&mut map, "transaction_id", Some(&msg.transaction_cell().repr_hash()));
+            serialize_id(&mut map, "transaction_id", Some(&msg.transaction_cell().repr_hash()), mode);
             serialize_grams(&mut map, "fwd_fee", &msg.fwd_fee, mode);
             (3, "final")
         }
@@ -596,7 +839,7 @@ fn serialize_in_msg(msg: &InMsg, mode: SerializationMode) -> Result<Value> {
             map.insert("in_msg".to_string(), serialize_envelope_msg(&msg.read_envelope_message()?, mode).into());
             serialize_u64(&mut map, "transaction_id", &msg.transaction_id(), mode);
             serialize_grams(&mut map, "fwd_fee", msg.fwd_fee(), mode);
-            serialize_cell(&mut map, "proof_delivered", Some(msg.proof_delivered()), false)?;
+            serialize_cell(&mut map, "proof_delivered", Some(msg.proof_delivered()), false, mode)?;
             (6, "discardedTransit")
         }
         _ => (-1, "none")
@@ -608,23 +851,23 @@ fn serialize_in_msg(msg: &InMsg, mode: SerializationMode) -> Result<Value> {
     Ok(map.into())
 }
 
-fn serialize_out_msg(msg: &OutMsg, mode: SerializationMode) -> Result<Value> {
+fn serialize_out_msg(msg: &OutMsg, mode: SerializationOptions) -> Result<Value> {
     let mut map = Map::new();
     let (type_, type_name) = match msg {
         OutMsg::External(msg) => {
-            serialize_id(&mut map, "msg_id", Some(&msg.message_cell().repr_hash()));
-            serialize_id(&mut map, "transaction_id", Some(&msg.transaction_cell().repr_hash()));
+            serialize_id(&mut map, "msg_id", Some(&msg.message_cell().repr_hash()), mode);
+            serialize_id(&mut map, "transaction_id", Some(&msg.transaction_cell().repr_hash()), mode);
             (0, "external")
         }
         OutMsg::Immediate(msg) => {
             map.insert("out_msg".to_string(), serialize_envelope_msg(&msg.read_out_message()?, mode).into());
-            serialize_id(&mut map, "transaction_id", Some(&msg.transaction_cell().repr_hash()));
+            serialize_id(&mut map, "transaction_id", Some(&msg.transaction_cell().repr_hash()), mode);
             map.insert("reimport".to_string(), serialize_in_msg(&msg.read_reimport_message()?, mode)?);
             (1, "immediately")
         }
         OutMsg::New(msg) => {
             map.insert("out_msg".to_string(), serialize_envelope_msg(&msg.read_out_message()?, mode).into());
-            serialize_id(&mut map, "transaction_id", Some(&msg.transaction_cell().repr_hash()));
+            serialize_id(&mut map, "transaction_id", Some(&msg.transaction_cell().repr_hash()), mode);
             (2, "outMsgNew")
         }
         OutMsg::Transit(msg) => {
@@ -648,10 +891,10 @@ fn serialize_out_msg(msg: &OutMsg, mode: SerializationMode) -> Result<Value> {
             (6, "transitRequeued")
         }
         OutMsg::DequeueShort(msg) => {
-            serialize_id(&mut map, "msg_env_hash", Some(&msg.msg_env_hash));
+            serialize_id(&mut map, "msg_env_hash", Some(&msg.msg_env_hash), mode);
             map.insert("next_workchain".to_string(), msg.next_workchain.into());
             map.insert("next_addr_pfx".to_string(), shard_to_string(msg.next_addr_pfx).into());
-            if let SerializationMode::Debug = mode {
+            if let SerializationMode::Debug = mode.mode {
                 map.insert("next_prefix".to_string(), format!("{}:{:016X}", msg.next_workchain, msg.next_addr_pfx).into());
             }
             serialize_lt(&mut map, "import_block_lt", &msg.import_block_lt, mode);
@@ -666,20 +909,28 @@ fn serialize_out_msg(msg: &OutMsg, mode: SerializationMode) -> Result<Value> {
     Ok(map.into())
 }
 
-fn serialize_shard_descr(descr: &ShardDescr, mode: SerializationMode) -> Result<Value> {
+/// Generation time of the shard block in milliseconds
+fn shard_descr_gen_utime_ms(descr: &ShardDescr) -> u64 {
+    descr.gen_utime as u64 * 1000 + descr.gen_utime_ms_part as u64
+}
+
+fn serialize_shard_descr(descr: &ShardDescr, mode: SerializationOptions) -> Result<Value> {
     let mut map = Map::new();
     serialize_field(&mut map, "seq_no", descr.seq_no);
     serialize_field(&mut map, "reg_mc_seqno", descr.reg_mc_seqno);
     serialize_lt(&mut map, "start_lt", &descr.start_lt, mode);
     serialize_lt(&mut map, "end_lt", &descr.end_lt, mode);
-    serialize_field(&mut map, "root_hash", descr.root_hash.as_hex_string());
-    serialize_field(&mut map, "file_hash", descr.file_hash.as_hex_string());
+    serialize_uint256(&mut map, "root_hash", &descr.root_hash, mode);
+    serialize_uint256(&mut map, "file_hash", &descr.file_hash, mode);
     serialize_field(&mut map, "before_split", descr.before_split);
     serialize_field(&mut map, "before_merge", descr.before_merge);
     serialize_field(&mut map, "want_split", descr.want_split);
     serialize_field(&mut map, "want_merge", descr.want_merge);
     serialize_field(&mut map, "nx_cc_updated", descr.nx_cc_updated);
     serialize_field(&mut map, "gen_utime", descr.gen_utime);
+    if descr.gen_utime_ms_part != 0 {
+        serialize_field(&mut map, "gen_utime_ms", shard_descr_gen_utime_ms(descr));
+    }
     serialize_field(&mut map, "next_catchain_seqno", descr.next_catchain_seqno);
     serialize_field(&mut map, "next_validator_shard", shard_to_string(descr.next_validator_shard));
     serialize_field(&mut map, "min_ref_mc_seqno", descr.min_ref_mc_seqno);
@@ -698,9 +949,38 @@ fn serialize_shard_descr(descr: &ShardDescr, mode: SerializationMode) -> Result<
         }
         FutureSplitMerge::None => ()
     };
+    if let Some(collators) = &descr.collators {
+        serialize_shard_collators(&mut map, collators);
+    }
+    if let Some(proof_chain) = &descr.proof_chain {
+        serialize_cell(&mut map, "proof_chain", Some(&proof_chain.serialize()?), true, mode)?;
+    }
     Ok(map.into())
 }
 
+fn serialize_collator_range(range: &CollatorRange) -> Value {
+    let mut map = Map::new();
+    serialize_field(&mut map, "collator", range.collator);
+    serialize_field(&mut map, "start", range.start);
+    serialize_field(&mut map, "finish", range.finish);
+    map.into()
+}
+
+fn serialize_shard_collators(map: &mut Map<String, Value>, collators: &ShardCollators) {
+    let mut collators_map = Map::new();
+    collators_map.insert("prev".to_string(), serialize_collator_range(&collators.prev));
+    if let Some(prev2) = &collators.prev2 {
+        collators_map.insert("prev2".to_string(), serialize_collator_range(prev2));
+    }
+    collators_map.insert("current".to_string(), serialize_collator_range(&collators.current));
+    collators_map.insert("next".to_string(), serialize_collator_range(&collators.next));
+    if let Some(next2) = &collators.next2 {
+        collators_map.insert("next2".to_string(), serialize_collator_range(next2));
+    }
+    serialize_field(&mut collators_map, "updated_at", collators.updated_at);
+    serialize_field(map, "collators", collators_map);
+}
+
 fn serialize_config_proposal_setup(cps: &ConfigProposalSetup) -> Result<Value> {
     let mut map = Map::new();
     serialize_field(&mut map, "min_tot_rounds", cps.min_tot_rounds);
@@ -723,11 +1003,12 @@ fn serialize_mandatory_params(mp: &MandatoryParams) -> Result<Value> {
     Ok(vector.into())
 }
 
-fn serialize_workchains(wcs: &Workchains) -> Result<Value> {
+fn serialize_workchains(wcs: &Workchains, mode: SerializationOptions) -> Result<Value> {
     let mut vector = Vec::new();
     wcs.iterate_with_keys(|key: u32, wc: WorkchainDescr| -> Result<bool> {
         let mut map = Map::new();
-        serialize_field(&mut map, "workchain_id", key);
+        // workchain ids are signed, dictionary keys are their 32-bit representation
+        serialize_field(&mut map, "workchain_id", key as i32);
         serialize_field(&mut map, "enabled_since", wc.enabled_since);
         serialize_field(&mut map, "actual_min_split", wc.actual_min_split());
         serialize_field(&mut map, "min_split", wc.min_split());
@@ -735,8 +1016,8 @@ fn serialize_workchains(wcs: &Workchains) -> Result<Value> {
         serialize_field(&mut map, "active", wc.active);
         serialize_field(&mut map, "accept_msgs", wc.accept_msgs);
         serialize_field(&mut map, "flags", wc.flags);
-        serialize_uint256(&mut map, "zerostate_root_hash", &wc.zerostate_root_hash);
-        serialize_uint256(&mut map, "zerostate_file_hash", &wc.zerostate_file_hash);
+        serialize_uint256(&mut map, "zerostate_root_hash", &wc.zerostate_root_hash, mode);
+        serialize_uint256(&mut map, "zerostate_file_hash", &wc.zerostate_file_hash, mode);
         serialize_field(&mut map, "version", wc.version);
         match wc.format {
             WorkchainFormat::Basic(f) => {
@@ -758,10 +1039,11 @@ fn serialize_workchains(wcs: &Workchains) -> Result<Value> {
     Ok(vector.into())
 }
 
-fn serialize_storage_prices(wcs: &ConfigParam18Map, mode: SerializationMode) -> Result<Value> {
+fn serialize_storage_prices(wcs: &ConfigParam18Map, mode: SerializationOptions) -> Result<Value> {
     let mut vector = Vec::new();
-    wcs.iterate(|val| {
+    wcs.iterate_with_keys(|index: u32, val| {
         let mut map = Map::new();
+        serialize_field(&mut map, "index", index);
         serialize_field(&mut map, "utime_since", val.utime_since);
         serialize_u64(&mut map, "bit_price_ps", &val.bit_price_ps, mode);
         serialize_u64(&mut map, "cell_price_ps", &val.cell_price_ps, mode);
@@ -773,7 +1055,7 @@ fn serialize_storage_prices(wcs: &ConfigParam18Map, mode: SerializationMode) ->
     Ok(vector.into())
 }
 
-fn serialize_gas_limits_prices(map: &mut Map<String, Value>, gp: &GasLimitsPrices, mode: SerializationMode) {
+fn serialize_gas_limits_prices(map: &mut Map<String, Value>, gp: &GasLimitsPrices, mode: SerializationOptions) {
     serialize_u64(map, "flat_gas_limit", &gp.flat_gas_limit, mode);
     serialize_u64(map, "flat_gas_price", &gp.flat_gas_price, mode);
     serialize_u64(map, "gas_price", &gp.gas_price, mode);
@@ -800,7 +1082,7 @@ fn serialize_block_limits(map: &mut Map<String, Value>, bl: &BlockLimits) -> Res
     Ok(())
 }
 
-fn serialize_msg_fwd_prices(map: &mut Map<String, Value>, fp: &MsgForwardPrices, mode: SerializationMode) -> Result<()> {
+fn serialize_msg_fwd_prices(map: &mut Map<String, Value>, fp: &MsgForwardPrices, mode: SerializationOptions) -> Result<()> {
     serialize_u64(map, "lump_price", &fp.lump_price, mode);
     serialize_u64(map, "bit_price", &fp.bit_price, mode);
     serialize_u64(map, "cell_price", &fp.cell_price, mode);
@@ -810,42 +1092,122 @@ fn serialize_msg_fwd_prices(map: &mut Map<String, Value>, fp: &MsgForwardPrices,
     Ok(())
 }
 
-fn serialize_fundamental_smc_addresses(addresses: &FundamentalSmcAddresses) -> Result<Value> {
+fn serialize_fundamental_smc_addresses(addresses: &FundamentalSmcAddresses, mode: SerializationOptions) -> Result<Value> {
     let mut vector = Vec::<Value>::new();
     addresses.iterate_keys(|k: UInt256| -> Result<bool> {
-        vector.push(k.as_hex_string().into());
+        vector.push(format_uint256(&k, mode).into());
         Ok(true)
     })?;
     Ok(vector.into())
 }
 
-fn serialize_validators_set(map: &mut Map<String, Value>, set: &ValidatorSet, mode: SerializationMode) -> Result<()> {
+/// Validator set in the same shape as config params 32-37.
+///
+/// ```
+/// use ever_block_json::*;
+/// let set = ever_block::ValidatorSet::new(1, 2, 0, vec![])?;
+/// let doc = db_serialize_validator_set(&set, SerializationMode::Standart)?;
+/// assert_eq!(doc["utime_until"], 2);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_validator_set(set: &ValidatorSet, mode: impl Into<SerializationOptions>) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let mut map = Map::new();
+    serialize_validators_set(&mut map, set, mode)?;
+    Ok(map)
+}
+
+fn serialize_validators_set(map: &mut Map<String, Value>, set: &ValidatorSet, mode: SerializationOptions) -> Result<()> {
     serialize_field(map, "utime_since", set.utime_since());
     serialize_field(map, "utime_until", set.utime_until());
     serialize_field(map, "total", set.total());
     serialize_field(map, "main", set.main());
     serialize_u64(map, "total_weight", &set.total_weight(), mode);
-    let mut vector = Vec::<Value>::new();
-    for v in set.list() {
-        let mut map = Map::new();
-        serialize_field(&mut map, "public_key", hex::encode(v.public_key.as_slice()));
-        serialize_u64(&mut map, "weight", &v.weight, mode);
-        serialize_id(&mut map, "adnl_addr", v.adnl_addr.as_ref());
-        if let Some(bls_public_key) = v.bls_public_key {
-            serialize_field(&mut map, "bls_public_key", hex::encode(bls_public_key));
-        }
-        vector.push(map.into());
-    };
+    let vector = set.list().iter().map(|v| serialize_validator_descr(v, mode)).collect::<Vec<_>>();
     serialize_field(map, "list", Value::from(vector));
     Ok(())
 }
 
-fn serialize_validator_signed_temp_keys(stk: &ValidatorKeys) -> Result<Value> {
+fn serialize_validator_descr(v: &ValidatorDescr, mode: SerializationOptions) -> Value {
+    let mut map = Map::new();
+    serialize_field(&mut map, "public_key", hex::encode(v.public_key.as_slice()));
+    serialize_u64(&mut map, "weight", &v.weight, mode);
+    serialize_id(&mut map, "adnl_addr", v.adnl_addr.as_ref(), mode);
+    if let Some(bls_public_key) = v.bls_public_key {
+        serialize_field(&mut map, "bls_public_key", hex::encode(bls_public_key));
+    }
+    map.into()
+}
+
+/// Describes changes between two validator sets: added and removed validators,
+/// weight and ADNL address changes and the share of rotated validators in percents.
+pub fn serialize_validator_set_diff(
+    prev: &ValidatorSet,
+    next: &ValidatorSet,
+    mode: impl Into<SerializationOptions>,
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let next_by_key = next.list().iter()
+        .map(|v| (v.public_key.as_slice(), v))
+        .collect::<HashMap<_, _>>();
+    let prev_by_key = prev.list().iter()
+        .map(|v| (v.public_key.as_slice(), v))
+        .collect::<HashMap<_, _>>();
+
+    let mut removed = Vec::new();
+    let mut weight_changed = Vec::new();
+    let mut adnl_changed = Vec::new();
+    for v in prev.list() {
+        let public_key = hex::encode(v.public_key.as_slice());
+        match next_by_key.get(v.public_key.as_slice()) {
+            None => removed.push(serialize_validator_descr(v, mode)),
+            Some(next_v) => {
+                if next_v.weight != v.weight {
+                    let mut map = Map::new();
+                    serialize_field(&mut map, "public_key", public_key.as_str());
+                    serialize_u64(&mut map, "prev_weight", &v.weight, mode);
+                    serialize_u64(&mut map, "weight", &next_v.weight, mode);
+                    weight_changed.push(Value::from(map));
+                }
+                if next_v.adnl_addr != v.adnl_addr {
+                    let mut map = Map::new();
+                    serialize_field(&mut map, "public_key", public_key.as_str());
+                    serialize_id(&mut map, "prev_adnl_addr", v.adnl_addr.as_ref(), mode);
+                    serialize_id(&mut map, "adnl_addr", next_v.adnl_addr.as_ref(), mode);
+                    adnl_changed.push(Value::from(map));
+                }
+            }
+        }
+    }
+    let added = next.list().iter()
+        .filter(|v| !prev_by_key.contains_key(v.public_key.as_slice()))
+        .map(|v| serialize_validator_descr(v, mode))
+        .collect::<Vec<_>>();
+    let rotation = if prev.list().is_empty() {
+        0.0
+    } else {
+        removed.len() as f64 * 100.0 / prev.list().len() as f64
+    };
+
+    let mut map = Map::new();
+    serialize_field(&mut map, "prev_utime_since", prev.utime_since());
+    serialize_field(&mut map, "utime_since", next.utime_since());
+    serialize_field(&mut map, "prev_total", prev.total());
+    serialize_field(&mut map, "total", next.total());
+    serialize_field(&mut map, "added", added);
+    serialize_field(&mut map, "removed", removed);
+    serialize_field(&mut map, "weight_changed", weight_changed);
+    serialize_field(&mut map, "adnl_changed", adnl_changed);
+    serialize_field(&mut map, "rotation", rotation);
+    Ok(map)
+}
+
+fn serialize_validator_signed_temp_keys(stk: &ValidatorKeys, mode: SerializationOptions) -> Result<Value> {
     let mut vector = Vec::<Value>::new();
     stk.iterate_with_keys(|key: UInt256, val| -> Result<bool> {
         let mut map = Map::new();
-        serialize_uint256(&mut map, "map_key", &key);
-        serialize_uint256(&mut map, "adnl_addr", val.key().adnl_addr());
+        serialize_uint256(&mut map, "map_key", &key, mode);
+        serialize_uint256(&mut map, "adnl_addr", val.key().adnl_addr(), mode);
         serialize_field(&mut map, "temp_public_key", hex::encode(val.key().temp_public_key().key_bytes()));
         serialize_field(&mut map, "seqno", val.key().seqno());
         serialize_field(&mut map, "valid_until", val.key().valid_until());
@@ -858,7 +1220,7 @@ fn serialize_validator_signed_temp_keys(stk: &ValidatorKeys) -> Result<Value> {
     Ok(vector.into())
 }
 
-fn serialize_copyleft_param(map: &mut Map<String, Value>, copyleft: &ConfigCopyleft, mode: SerializationMode) -> Result<()> {
+fn serialize_copyleft_param(map: &mut Map<String, Value>, copyleft: &ConfigCopyleft, mode: SerializationOptions) -> Result<()> {
     serialize_grams(map, "threshold", &copyleft.copyleft_reward_threshold, mode);
     let mut vector = Vec::<Value>::new();
     copyleft.license_rates.iterate_with_keys(|key: u8, val| -> Result<bool> {
@@ -881,22 +1243,22 @@ fn serialize_suspended_addresses(sa: &SuspendedAddresses) -> Result<Value> {
     Ok(vector.into())
 }
 
-fn serialize_mesh_config(mc: &MeshConfig) -> Result<Value> {
+fn serialize_mesh_config(mc: &MeshConfig, mode: SerializationOptions) -> Result<Value> {
     let mut vector = Vec::new();
     mc.iterate_with_keys(|k: i32, v| {
         let mut map = Map::new();
         serialize_field(&mut map, "network_id", k);
-        serialize_field(&mut map, "zerostate", serialize_separated_block_id_ext(&v.zerostate)?);
+        serialize_field(&mut map, "zerostate", serialize_separated_block_id_ext(&v.zerostate, mode)?);
         serialize_field(&mut map, "is_active", v.is_active);
         serialize_field(&mut map, "currency_id", v.currency_id);
-        serialize_field(&mut map, "init_block", serialize_separated_block_id_ext(&v.init_block)?);
-        serialize_uint256(&mut map, "emergency_guard_addr", &v.emergency_guard_addr);
-        serialize_uint256(&mut map, "pull_addr", &v.pull_addr);
-        serialize_uint256(&mut map, "minter_addr", &v.minter_addr);
+        serialize_field(&mut map, "init_block", serialize_separated_block_id_ext(&v.init_block, mode)?);
+        serialize_uint256(&mut map, "emergency_guard_addr", &v.emergency_guard_addr, mode);
+        serialize_uint256(&mut map, "pull_addr", &v.pull_addr, mode);
+        serialize_uint256(&mut map, "minter_addr", &v.minter_addr, mode);
         if !v.hardforks.is_empty() {
             let mut hardforks: Vec<Value> = Vec::new();
             for hf in &v.hardforks {
-                hardforks.push(serialize_separated_block_id_ext(hf)?)
+                hardforks.push(serialize_separated_block_id_ext(hf, mode)?)
             }
             serialize_field(&mut map, "hardforks", hardforks);
         }
@@ -906,36 +1268,37 @@ fn serialize_mesh_config(mc: &MeshConfig) -> Result<Value> {
     Ok(vector.into())
 }
 
-fn serialize_crypto_signature(s: &CryptoSignaturePair) -> Result<Value> {
+fn serialize_crypto_signature(s: &CryptoSignaturePair, mode: SerializationOptions) -> Result<Value> {
     let mut map = Map::new();
-    serialize_uint256(&mut map, "node_id", &s.node_id_short);
+    serialize_uint256(&mut map, "node_id", &s.node_id_short, mode);
     let (r, s) = s.sign.as_r_s_bytes();
     serialize_field(&mut map, "r", hex::encode(r));
     serialize_field(&mut map, "s", hex::encode(s));
     Ok(map.into())
 }
 
-pub fn serialize_known_config_param(number: u32, param: &mut SliceData, mode: SerializationMode) -> Result<Option<Value>> {
+pub fn serialize_known_config_param(number: u32, param: &mut SliceData, mode: impl Into<SerializationOptions>) -> Result<Option<Value>> {
+    let mode = mode.into();
     let mut map = Map::new();
 
     match ConfigParamEnum::construct_from_slice_and_number(param, number)? {
         ConfigParamEnum::ConfigParam0(ref c) => {
-            return Ok(Some(c.config_addr.as_hex_string().into()));
+            return Ok(Some(format_uint256(&c.config_addr, mode).into()));
         },
         ConfigParamEnum::ConfigParam1(ref c) => {
-            return Ok(Some(c.elector_addr.as_hex_string().into()));
+            return Ok(Some(format_uint256(&c.elector_addr, mode).into()));
         },
         ConfigParamEnum::ConfigParam2(ref c) => {
-            return Ok(Some(c.minter_addr.as_hex_string().into()));
+            return Ok(Some(format_uint256(&c.minter_addr, mode).into()));
         },
         ConfigParamEnum::ConfigParam3(ref c) => {
-            return Ok(Some(c.fee_collector_addr.as_hex_string().into()));
+            return Ok(Some(format_uint256(&c.fee_collector_addr, mode).into()));
         },
         ConfigParamEnum::ConfigParam4(ref c) => {
-            return Ok(Some(c.dns_root_addr.as_hex_string().into()));
+            return Ok(Some(format_uint256(&c.dns_root_addr, mode).into()));
         },
         ConfigParamEnum::ConfigParam5(ref c) => {
-            return Ok(Some(c.owner_addr.as_hex_string().into()));
+            return Ok(Some(format_uint256(&c.owner_addr, mode).into()));
         },
         ConfigParamEnum::ConfigParam6(ref c) => {
             serialize_grams(&mut map, "mint_new_price", &c.mint_new_price, mode);
@@ -946,7 +1309,7 @@ pub fn serialize_known_config_param(number: u32, param: &mut SliceData, mode: Se
         },
         ConfigParamEnum::ConfigParam8(ref c) => {
             serialize_field(&mut map, "version", c.global_version.version);
-            serialize_u64(&mut map, "capabilities", &c.global_version.capabilities, mode);
+            serialize_capabilities(&mut map, "capabilities", c.global_version.capabilities, mode);
         },
         ConfigParamEnum::ConfigParam9(ref c) => {
             return Ok(Some(serialize_mandatory_params(&c.mandatory_params)?));
@@ -961,7 +1324,7 @@ pub fn serialize_known_config_param(number: u32, param: &mut SliceData, mode: Se
                 serialize_config_proposal_setup(&c.read_critical_params()?)?);
         },
         ConfigParamEnum::ConfigParam12(ref c) => {
-            return Ok(Some(serialize_workchains(&c.workchains)?));
+            return Ok(Some(serialize_workchains(&c.workchains, mode)?));
         },
         ConfigParamEnum::ConfigParam13(ref c) => {
             let boc = write_boc(&c.cell)?;
@@ -1032,11 +1395,11 @@ pub fn serialize_known_config_param(number: u32, param: &mut SliceData, mode: Se
         },
         ConfigParamEnum::ConfigParam30(ref c) => {
             serialize_field(&mut map, "delections_step", c.delections_step);
-            serialize_uint256(&mut map, "staker_init_code_hash", &c.staker_init_code_hash);
-            serialize_uint256(&mut map, "validator_init_code_hash", &c.validator_init_code_hash);
+            serialize_uint256(&mut map, "staker_init_code_hash", &c.staker_init_code_hash, mode);
+            serialize_uint256(&mut map, "validator_init_code_hash", &c.validator_init_code_hash, mode);
         },
         ConfigParamEnum::ConfigParam31(ref c) => {
-            return Ok(Some(serialize_fundamental_smc_addresses(&c.fundamental_smc_addr)?));
+            return Ok(Some(serialize_fundamental_smc_addresses(&c.fundamental_smc_addr, mode)?));
         },
         ConfigParamEnum::ConfigParam32(ref c) => {
             serialize_validators_set(&mut map, &c.prev_validators, mode)?;
@@ -1057,7 +1420,7 @@ pub fn serialize_known_config_param(number: u32, param: &mut SliceData, mode: Se
             serialize_validators_set(&mut map, &c.next_temp_validators, mode)?;
         },
         ConfigParamEnum::ConfigParam39(ref c) => {
-            return Ok(Some(serialize_validator_signed_temp_keys(&c.validator_keys)?));
+            return Ok(Some(serialize_validator_signed_temp_keys(&c.validator_keys, mode)?));
         },
         ConfigParamEnum::ConfigParam40(ref c) => {
             serialize_field(&mut map, "slashing_period_mc_blocks_count", c.slashing_config.slashing_period_mc_blocks_count);
@@ -1076,7 +1439,7 @@ pub fn serialize_known_config_param(number: u32, param: &mut SliceData, mode: Se
             return Ok(Some(serialize_suspended_addresses(c)?));
         },
         ConfigParamEnum::ConfigParam58(ref c) => {
-            return Ok(Some(serialize_mesh_config(c)?));
+            return Ok(Some(serialize_mesh_config(c, mode)?));
         },
         _ => {
             return Ok(None)
@@ -1086,43 +1449,111 @@ pub fn serialize_known_config_param(number: u32, param: &mut SliceData, mode: Se
     Ok(Some(map.into()))
 }
 
-fn serialize_unknown_config_param(number: u32, param: &mut SliceData) -> Result<Value> {
+fn serialize_unknown_config_param(number: u32, param: &mut SliceData, mode: SerializationOptions) -> Result<Value> {
     let mut map = Map::new();
 
     map.insert("number".to_string(), number.into());
-    serialize_slice(&mut map, "boc", Some(param), false)?;
+    serialize_slice(&mut map, "boc", Some(param), false, mode)?;
 
     Ok(map.into())
 }
 
-fn serialize_block_ref(blk_ref: &ExtBlkRef, key: Option<bool>, mode: SerializationMode) -> Value {
+fn serialize_block_ref(blk_ref: &ExtBlkRef, key: Option<bool>, mode: SerializationOptions) -> Value {
     let mut blk_ref_map = Map::new();
     serialize_lt(&mut blk_ref_map, "end_lt", &blk_ref.end_lt, mode);
     blk_ref_map.insert("seq_no".to_string(), blk_ref.seq_no.into());
     if let Some(key) = key {
         blk_ref_map.insert("key".to_string(), key.into());
     }
-    serialize_id(&mut blk_ref_map, "root_hash", Some(&blk_ref.root_hash));
-    serialize_id(&mut blk_ref_map, "file_hash", Some(&blk_ref.file_hash));
+    serialize_id(&mut blk_ref_map, "root_hash", Some(&blk_ref.root_hash), mode);
+    serialize_id(&mut blk_ref_map, "file_hash", Some(&blk_ref.file_hash), mode);
     blk_ref_map.into()
 }
 
-fn serialize_shard_hashes(map: &mut Map<String, Value>, id_str: &str, hashes: &ShardHashes, mode: SerializationMode) -> Result<()> {
+/// Shard hashes taken from the masterchain block or state: `shard_hashes` with description
+/// of every shard and `min_shard_gen_utime`/`max_shard_gen_utime`, as in the `master` section.
+pub fn db_serialize_shard_hashes(hashes: &ShardHashes, mode: impl Into<SerializationOptions>) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let mut map = Map::new();
+    serialize_shard_hashes(&mut map, "shard_hashes", hashes, None, mode)?;
+    Ok(map)
+}
+
+/// Shard hashes like `db_serialize_shard_hashes` with collation outcome of every shard as seen
+/// from the masterchain block `mc_seq_no`: `mc_blocks_since_update` and, if hashes of
+/// the previous masterchain block are given, `seq_no_advanced`.
+pub fn db_serialize_shard_hashes_with_outcome(
+    hashes: &ShardHashes,
+    prev_hashes: Option<&ShardHashes>,
+    mc_seq_no: u32,
+    mode: impl Into<SerializationOptions>
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let prev_seq_nos = match prev_hashes {
+        Some(prev_hashes) => {
+            let mut prev_seq_nos = HashMap::new();
+            prev_hashes.iterate_with_keys(&mut |key: i32, InRefValue(tree): InRefValue<BinTree<ShardDescr>>| {
+                tree.iterate(&mut |shard: SliceData, descr: ShardDescr| {
+                    prev_seq_nos.insert(ShardIdent::with_prefix_slice(key, shard)?, descr.seq_no);
+                    Ok(true)
+                })
+            })?;
+            Some(prev_seq_nos)
+        }
+        None => None
+    };
+    let outcome = CollationOutcome { prev_seq_nos, mc_seq_no };
+    let mut map = Map::new();
+    serialize_shard_hashes(&mut map, "shard_hashes", hashes, Some(&outcome), mode)?;
+    Ok(map)
+}
+
+struct CollationOutcome {
+    prev_seq_nos: Option<HashMap<ShardIdent, u32>>,
+    mc_seq_no: u32,
+}
+
+impl CollationOutcome {
+    fn serialize(&self, map: &mut Map<String, Value>, shard: &ShardIdent, descr: &ShardDescr) {
+        if let Some(prev_seq_nos) = &self.prev_seq_nos {
+            let advanced = prev_seq_nos.get(shard).map_or(true, |seq_no| *seq_no != descr.seq_no);
+            serialize_field(map, "seq_no_advanced", advanced);
+        }
+        serialize_field(map, "mc_blocks_since_update", self.mc_seq_no.saturating_sub(descr.reg_mc_seqno));
+    }
+}
+
+fn serialize_shard_hashes(
+    map: &mut Map<String, Value>,
+    id_str: &str,
+    hashes: &ShardHashes,
+    outcome: Option<&CollationOutcome>,
+    mode: SerializationOptions
+) -> Result<()> {
     let mut shard_hashes = Vec::new();
     let mut min_gen_utime = u32::max_value();
     let mut max_gen_utime = 0;
+    let mut min_gen_utime_ms = u64::max_value();
+    let mut max_gen_utime_ms = 0;
+    let mut with_ms = false;
     hashes.iterate_with_keys(&mut |key: i32, InRefValue(tree): InRefValue<BinTree<ShardDescr>>| {
         tree.iterate(&mut |shard: SliceData, descr| {
-            if let Ok(descr) = serialize_shard_descr(&descr, mode) {
+            if let Ok(mut descr_value) = serialize_shard_descr(&descr, mode) {
                 let shard_ident = ShardIdent::with_prefix_slice(key, shard)?;
+                if let (Some(outcome), Some(descr_map)) = (outcome, descr_value.as_object_mut()) {
+                    outcome.serialize(descr_map, &shard_ident, &descr);
+                }
                 shard_hashes.push(serde_json::json!({
                     "workchain_id": key,
                     "shard": shard_ident.shard_prefix_as_str_with_tag(),
-                    "descr": descr,
+                    "descr": descr_value,
                 }));
             }
             min_gen_utime = std::cmp::min(min_gen_utime, descr.gen_utime);
             max_gen_utime = std::cmp::max(max_gen_utime, descr.gen_utime);
+            min_gen_utime_ms = std::cmp::min(min_gen_utime_ms, shard_descr_gen_utime_ms(&descr));
+            max_gen_utime_ms = std::cmp::max(max_gen_utime_ms, shard_descr_gen_utime_ms(&descr));
+            with_ms |= descr.gen_utime_ms_part != 0;
             Ok(true)
         })
     })?;
@@ -1130,13 +1561,98 @@ fn serialize_shard_hashes(map: &mut Map<String, Value>, id_str: &str, hashes: &S
         map.insert(id_str.to_string(), shard_hashes.into());
         serialize_field(map, "min_shard_gen_utime", min_gen_utime);
         serialize_field(map, "max_shard_gen_utime", max_gen_utime);
+        if with_ms {
+            serialize_field(map, "min_shard_gen_utime_ms", min_gen_utime_ms);
+            serialize_field(map, "max_shard_gen_utime_ms", max_gen_utime_ms);
+        }
     }
 
     Ok(())
 }
 
-pub fn serialize_config(map: &mut Map<String, Value>, config: &ConfigParams, mode: SerializationMode) -> Result<()> {
-    serialize_id(map, "config_addr", Some(&config.config_addr));
+/// Replaces flat `master.shard_hashes` array of the block or masterchain state document
+/// with the object grouped by workchain: `{"<workchain_id>": [{"shard", "descr"}, ...]}`.
+/// `parse_shard_hashes` accepts both representations.
+pub fn nest_shard_hashes(doc: &mut Map<String, Value>) -> Result<()> {
+    let master = match doc.get_mut("master").and_then(|master| master.as_object_mut()) {
+        Some(master) => master,
+        None => return Ok(())
+    };
+    let shard_hashes = match master.get_mut("shard_hashes") {
+        Some(Value::Array(shard_hashes)) => std::mem::take(shard_hashes),
+        _ => return Ok(())
+    };
+    let mut nested = Map::new();
+    for mut shard in shard_hashes {
+        let shard = shard.as_object_mut()
+            .ok_or_else(|| error!("shard_hashes item must be the object"))?;
+        let workchain_id = shard.remove("workchain_id")
+            .and_then(|workchain_id| workchain_id.as_i64())
+            .ok_or_else(|| error!("shard_hashes item must have the field `workchain_id`"))?;
+        let workchain_id = i32::try_from(workchain_id)
+            .map_err(|_| error!("shard_hashes item workchain_id {} is out of range of i32", workchain_id))?;
+        let shards = nested.entry(workchain_id.to_string()).or_insert_with(|| Value::Array(Vec::new()));
+        if let Value::Array(shards) = shards {
+            shards.push(std::mem::take(shard).into());
+        }
+    }
+    master.insert("shard_hashes".to_string(), nested.into());
+    Ok(())
+}
+
+/// Extension point for network-specific config params which have no structured support
+/// in ever-block: `serialize_config_with` tries extensions for every param it doesn't know,
+/// params not handled by extensions are emitted in `unknown_config`.
+pub trait ConfigParamSerializer {
+    /// Returns `None` if the param with this number is not handled by the extension
+    fn serialize(&self, number: u32, param: &mut SliceData, mode: SerializationOptions) -> Result<Option<Value>>;
+}
+
+/// Oracle bridge params (p71 - p73) as defined by the networks which use them:
+/// `bridge_address:bits256 oracle_multisig_address:bits256
+/// oracles:(HashmapE 256 uint256) external_chain_address:bits256`
+pub struct BridgeConfigParams;
+
+impl BridgeConfigParams {
+    pub const NUMBERS: [u32; 3] = [71, 72, 73];
+}
+
+impl ConfigParamSerializer for BridgeConfigParams {
+    fn serialize(&self, number: u32, param: &mut SliceData, mode: SerializationOptions) -> Result<Option<Value>> {
+        if !Self::NUMBERS.contains(&number) {
+            return Ok(None)
+        }
+        let mut map = Map::new();
+        serialize_uint256(&mut map, "bridge_address", &param.get_next_hash()?, mode);
+        serialize_uint256(&mut map, "oracle_multisig_address", &param.get_next_hash()?, mode);
+        let oracles = HashmapE::with_hashmap(256, param.get_next_dictionary()?);
+        let mut oracles_vec = Vec::new();
+        oracles.iterate_slices(|mut key, mut value| -> Result<bool> {
+            let mut oracle = Map::new();
+            serialize_uint256(&mut oracle, "address", &key.get_next_hash()?, mode);
+            serialize_uint256(&mut oracle, "public_key", &value.get_next_hash()?, mode);
+            oracles_vec.push(Value::from(oracle));
+            Ok(true)
+        })?;
+        serialize_field(&mut map, "oracles", oracles_vec);
+        serialize_uint256(&mut map, "external_chain_address", &param.get_next_hash()?, mode);
+        Ok(Some(map.into()))
+    }
+}
+
+pub fn serialize_config(map: &mut Map<String, Value>, config: &ConfigParams, mode: impl Into<SerializationOptions>) -> Result<()> {
+    let mode = mode.into();
+    serialize_config_with(map, config, mode, &[])
+}
+
+pub fn serialize_config_with(
+    map: &mut Map<String, Value>,
+    config: &ConfigParams,
+    mode: impl Into<SerializationOptions>,
+    extensions: &[&dyn ConfigParamSerializer],
+) -> Result<()> {
+    let mode = mode.into();
+    serialize_id(map, "config_addr", Some(&config.config_addr), mode);
     let mut known_cp_map = Map::new();
     let mut unknown_cp_vec = Vec::new();
     config.config_params.iterate_slices(|mut num, mut cp_ref| -> Result<bool> {
@@ -1145,9 +1661,15 @@ pub fn serialize_config(map: &mut Map<String, Value>, config: &ConfigParams, mod
             let mut cp = SliceData::load_cell(cp_ref.checked_drain_reference()?)?;
             if let Some(cp) = serialize_known_config_param(num, &mut cp.clone(), mode)? {
                 known_cp_map.insert(format!("p{}", num), cp);
-            } else {
-                unknown_cp_vec.push(serialize_unknown_config_param(num, &mut cp)?);
+                return Ok(true)
+            }
+            for extension in extensions {
+                if let Some(cp) = extension.serialize(num, &mut cp.clone(), mode)? {
+                    known_cp_map.insert(format!("p{}", num), cp);
+                    return Ok(true)
+                }
             }
+            unknown_cp_vec.push(serialize_unknown_config_param(num, &mut cp, mode)?);
             Ok(true)
         })?;
     serialize_field(map, "config", known_cp_map);
@@ -1157,7 +1679,26 @@ pub fn serialize_config(map: &mut Map<String, Value>, config: &ConfigParams, mod
     Ok(())
 }
 
-fn serialize_counters(counters: &Counters, mode: SerializationMode) -> Value {
+/// Standalone config document: `config_addr`, `config` with `p<N>` params and `unknown_config`,
+/// the same as in the `master` section of key blocks.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// let config = parse_config(map["master"]["config"].as_object().unwrap())?;
+/// let doc = db_serialize_config(&config, SerializationMode::Debug)?;
+/// assert_eq!(doc["config"], map["master"]["config"]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_config(config: &ConfigParams, mode: impl Into<SerializationOptions>) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let mut map = Map::new();
+    serialize_config(&mut map, config, mode)?;
+    Ok(map)
+}
+
+fn serialize_counters(counters: &Counters, mode: SerializationOptions) -> Value {
     let mut map = Map::new();
     map.insert("valid".to_string(), counters.is_valid().into());
     map.insert("last_updated".to_string(), counters.last_updated().into());
@@ -1167,7 +1708,7 @@ fn serialize_counters(counters: &Counters, mode: SerializationMode) -> Value {
     map.into()
 }
 
-fn serialize_block_create_stats(map: &mut Map<String, Value>, id_str: &str, stats: &BlockCreateStats, mode: SerializationMode) -> Result<()> {
+fn serialize_block_create_stats(map: &mut Map<String, Value>, id_str: &str, stats: &BlockCreateStats, mode: SerializationOptions) -> Result<()> {
     let mut counters = Vec::new();
     stats.counters.iterate_slices_with_keys(|ref mut key, ref mut value| -> Result<bool> {
         let value = CreatorStats::construct_from(value)?;
@@ -1182,25 +1723,55 @@ fn serialize_block_create_stats(map: &mut Map<String, Value>, id_str: &str, stat
     Ok(())
 }
 
-fn serialize_shard_accounts(map: &mut Map<String, Value>, id_str: &str, shard_accounts: &ShardAccounts, mode: SerializationMode) -> Result<()> {
+fn shard_account_set(shard_account: &ShardAccount) -> Result<AccountSerializationSet> {
+    let account = shard_account.read_account()?;
+    let mut boc1 = None;
+    if account.init_code_hash().is_some() {
+        let mut builder = BuilderData::new();
+        account.write_original_format(&mut builder)?;
+        boc1 = Some(write_boc(&builder.into_cell()?)?);
+    }
+    Ok(AccountSerializationSet {
+        account,
+        prev_code_hash: None,
+        boc: write_boc(&shard_account.account_cell())?,
+        boc1,
+        proof: None,
+    })
+}
+
+/// Serializes the account of the shard state with the reference to its last transaction:
+/// `last_trans_hash` and `last_trans_lt` are taken from the shard account, so `last_trans_lt`
+/// is the logical time of the last transaction itself.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let account = samples::sample_account();
+/// let hash = ever_block::UInt256::with_array([7; 32]);
+/// let shard_account = ever_block::ShardAccount::with_params(&account, hash.clone(), 5)?;
+/// let doc = db_serialize_shard_account("id", &shard_account, SerializationMode::Debug)?;
+/// assert_eq!(doc["last_trans_hash"], hash.as_hex_string());
+/// assert_eq!(doc["last_trans_lt"], "0_5");
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_shard_account(
+    id_str: &'static str,
+    shard_account: &ShardAccount,
+    mode: impl Into<SerializationOptions>
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let mut map = db_serialize_account_ex(id_str, &shard_account_set(shard_account)?, mode)?;
+    serialize_id(&mut map, "last_trans_hash", Some(shard_account.last_trans_hash()), mode);
+    serialize_lt(&mut map, "last_trans_lt", &shard_account.last_trans_lt(), mode);
+    Ok(map)
+}
+
+fn serialize_shard_accounts(map: &mut Map<String, Value>, id_str: &str, shard_accounts: &ShardAccounts, mode: SerializationOptions) -> Result<()> {
     let mut accounts = Vec::new();
     shard_accounts.iterate_objects(&mut |ref mut value: ShardAccount| -> Result<bool> {
-        let account = value.read_account()?;
-        let mut boc1 = None;
-        if account.init_code_hash().is_some() {
-            let mut builder = BuilderData::new();
-            account.write_original_format(&mut builder)?;
-            boc1 = Some(write_boc(&builder.into_cell()?)?);
-        }
-
-        let account_set = AccountSerializationSet {
-            account,
-            prev_code_hash: None,
-            boc: write_boc(&value.account_cell())?,
-            boc1,
-            proof: None,
-        };
-        let mut account = db_serialize_account_ex("id", &account_set, mode)?;
+        let mut account = db_serialize_account_ex("id", &shard_account_set(value)?, mode)?;
         account.remove("json_version");
         accounts.push(account);
         Ok(true)
@@ -1209,28 +1780,53 @@ fn serialize_shard_accounts(map: &mut Map<String, Value>, id_str: &str, shard_ac
     Ok(())
 }
 
-fn serialize_libraries(map: &mut Map<String, Value>, id_str: &str, libraries: &Libraries) -> Result<()> {
+/// Shared libraries of the state as `[{hash, publishers, lib}]`, the `libraries` field
+/// of the shard state read by `parse_state`.
+pub fn db_serialize_libraries(libraries: &Libraries, mode: impl Into<SerializationOptions>) -> Result<Value> {
+    let mode = mode.into();
     let mut libraries_vec = Vec::new();
     libraries.iterate_slices_with_keys(|ref mut key, ref mut value| -> Result<bool> {
         let value = LibDescr::construct_from(value)?;
         let mut publishers = Vec::new();
         value.publishers().iterate_slices_with_keys(|ref mut key, _| -> Result<bool> {
-            publishers.push(key.as_hex_string());
+            publishers.push(mode.hash_format.format_hex(&key.as_hex_string()));
             Ok(true)
         })?;
 
         libraries_vec.push(serde_json::json!({
-            "hash": key.as_hex_string(),
+            "hash": mode.hash_format.format_hex(&key.as_hex_string()),
             "publishers": publishers,
             "lib": base64_encode(write_boc(value.lib())?)
         }));
         Ok(true)
     })?;
-    map.insert(id_str.to_string(), libraries_vec.into());
+    Ok(libraries_vec.into())
+}
+
+fn serialize_libraries(map: &mut Map<String, Value>, id_str: &str, libraries: &Libraries, mode: SerializationOptions) -> Result<()> {
+    map.insert(id_str.to_string(), db_serialize_libraries(libraries, mode)?);
+    Ok(())
+}
+
+fn serialize_out_msg_queue_info(map: &mut Map<String, Value>, id_str: &str, info: &OutMsgQueueInfo, mode: SerializationOptions) -> Result<()> {
+    map.insert(id_str.to_string(), db_serialize_out_msg_queue_info(info, mode)?.into());
     Ok(())
 }
 
-fn serialize_out_msg_queue_info(map: &mut Map<String, Value>, id_str: &str, info: &OutMsgQueueInfo, mode: SerializationMode) -> Result<()> {
+/// Out message queue of the shard state as in `out_msg_queue_info` of the state document:
+/// `out_queue` with enqueued messages, `proc_info` and `ihr_pending`.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// let info = parse_state(&map)?.read_out_msg_queue_info()?;
+/// let doc = db_serialize_out_msg_queue_info(&info, SerializationMode::Debug)?;
+/// assert_eq!(serde_json::Value::from(doc), map["out_msg_queue_info"]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_out_msg_queue_info(info: &OutMsgQueueInfo, mode: impl Into<SerializationOptions>) -> Result<Map<String, Value>> {
+    let mode = mode.into();
     let mut out_queue = Vec::new();
     info.out_queue().iterate_with_keys(&mut |ref mut key: OutMsgQueueKey, value: EnqueuedMsg| -> Result<bool> {
         let mut msg_map = serialize_envelope_msg(&value.read_out_msg()?, mode);
@@ -1248,7 +1844,7 @@ fn serialize_out_msg_queue_info(map: &mut Map<String, Value>, id_str: &str, info
         processed_map.insert("shard".to_string(), shard_to_string(key.get_next_u64()?).into());
         processed_map.insert("mc_seqno".to_string(), key.get_next_u32()?.into());
         serialize_lt(&mut processed_map, "last_msg_lt", &value.last_msg_lt, mode);
-        processed_map.insert("last_msg_hash".to_string(), value.last_msg_hash.as_hex_string().into());
+        serialize_uint256(&mut processed_map, "last_msg_hash", &value.last_msg_hash, mode);
         proc_info.push(processed_map);
         Ok(true)
     })?;
@@ -1258,24 +1854,40 @@ fn serialize_out_msg_queue_info(map: &mut Map<String, Value>, id_str: &str, info
         let value = IhrPendingSince::construct_from(value)?;
         let mut ihr_map = Map::new();
         ihr_map.insert("dest_addr_prefix".to_string(), shard_to_string(key.get_next_u64()?).into());
-        ihr_map.insert("msg_id".to_string(), format!("{:x}", key).into());
+        ihr_map.insert("msg_id".to_string(), mode.hash_format.format_hex(&format!("{:x}", key)).into());
         serialize_lt(&mut ihr_map, "import_lt", &value.import_lt(), mode);
         ihr_pending.push(ihr_map);
         Ok(true)
     })?;
 
-    map.insert(id_str.to_string(), serde_json::json!({
-        "out_queue": out_queue,
-        "proc_info": proc_info,
-        "ihr_pending": ihr_pending,
-    }));
+    let mut map = Map::new();
+    serialize_field(&mut map, "out_queue", out_queue);
+    serialize_field(&mut map, "proc_info", proc_info);
+    serialize_field(&mut map, "ihr_pending", ihr_pending);
+    Ok(map)
+}
 
+fn serialize_mc_state_extra(map: &mut Map<String, Value>, id_str: &str, master: &McStateExtra, mode: SerializationOptions) -> Result<()> {
+    map.insert(id_str.to_string(), db_serialize_mc_state_extra(master, mode)?.into());
     Ok(())
 }
 
-fn serialize_mc_state_extra(map: &mut Map<String, Value>, id_str: &str, master: &McStateExtra, mode: SerializationMode) -> Result<()> {
+/// Masterchain part of the shard state as in the `master` section of the state document:
+/// shard hashes, config, validator info, flags and global balance.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// let master = parse_state(&map)?.read_custom()?.unwrap();
+/// let doc = db_serialize_mc_state_extra(&master, SerializationMode::Debug)?;
+/// assert_eq!(serde_json::Value::from(doc), map["master"]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_mc_state_extra(master: &McStateExtra, mode: impl Into<SerializationOptions>) -> Result<Map<String, Value>> {
+    let mode = mode.into();
     let mut master_map = Map::new();
-    serialize_shard_hashes(&mut master_map, "shard_hashes", master.shards(), mode)?;
+    serialize_shard_hashes(&mut master_map, "shard_hashes", master.shards(), None, mode)?;
     serialize_config(&mut master_map, &master.config, mode)?;
     serialize_field(&mut master_map, "validator_list_hash_short", master.validator_info.validator_list_hash_short);
     serialize_field(&mut master_map, "catchain_seqno", master.validator_info.catchain_seqno);
@@ -1291,22 +1903,21 @@ fn serialize_mc_state_extra(map: &mut Map<String, Value>, id_str: &str, master:
     }
     serialize_cc(&mut master_map, "global_balance", &master.global_balance, mode)?;
     serialize_copyleft_rewards(&mut master_map, "state_copyleft_rewards", &master.state_copyleft_rewards, mode)?;
-    map.insert(id_str.to_string(), master_map.into());
-    Ok(())
+    Ok(master_map)
 }
 
-fn serialize_file_hash(map: &mut Map<String, Value>, file_hash: Option<&UInt256>, boc: &[u8]) {
+fn serialize_file_hash(map: &mut Map<String, Value>, file_hash: Option<&UInt256>, boc: &[u8], mode: SerializationOptions) {
     match file_hash {
-        Some(file_hash) => serialize_id(map, "file_hash", Some(file_hash)),
-        None => serialize_id(map, "file_hash", Some(&UInt256::calc_file_hash(boc))),
+        Some(file_hash) => serialize_id(map, "file_hash", Some(file_hash), mode),
+        None => serialize_id(map, "file_hash", Some(&UInt256::calc_file_hash(boc)), mode),
     }
 }
 
-fn serialize_copyleft_rewards(map: &mut Map<String, Value>, id_str: &str, rewards: &CopyleftRewards, mode: SerializationMode) -> Result<()> {
+fn serialize_copyleft_rewards(map: &mut Map<String, Value>, id_str: &str, rewards: &CopyleftRewards, mode: SerializationOptions) -> Result<()> {
     let mut rewards_vec = Vec::new();
     rewards.iterate_with_keys(|ref mut key: SliceData, ref mut value| -> Result<bool> {
         let mut reward_map = Map::new();
-        reward_map.insert("account".to_owned(), key.as_hex_string().into());
+        serialize_field(&mut reward_map, "account", mode.hash_format.format_hex(&key.as_hex_string()));
         serialize_grams(&mut reward_map, "reward", value, mode);
         rewards_vec.push(reward_map);
         Ok(true)
@@ -1373,70 +1984,424 @@ pub fn serialize_config_param(config: &ConfigParams, config_number: u32) -> Resu
     Ok(json)
 }
 
-pub fn debug_block_full(block: &Block) -> Result<String> {
-    let root_cell = block.serialize()?;
-    let set = BlockSerializationSet {
-        block: block.clone(),
-        id: root_cell.repr_hash(),
-        status: BlockProcessingStatus::Finalized,
-        boc: Vec::new(),
-    };
-    let map = db_serialize_block_ex("id", &set, SerializationMode::Debug)?;
+/// Returns base64 BOC of the raw config param, `None` if the param is absent.
+pub fn export_config_param_boc(config: &ConfigParams, config_number: u32) -> Result<Option<String>> {
+    let key = SliceData::load_builder(config_number.write_to_new_cell()?)?;
+    match config.config_params.get(key)? {
+        Some(mut cell) => {
+            let cp = cell.checked_drain_reference()?;
+            Ok(Some(base64_encode(write_boc(&cp)?)))
+        }
+        None => Ok(None)
+    }
+}
 
-    let mut text = format!("Block: {:#}\n", serde_json::json!(map));
-    let extra = block.read_extra()?;
-    let in_msgs = extra.read_in_msg_descr()?;
-    in_msgs.iterate_objects(|in_msg| {
-        let msg = in_msg.read_message()?;
-        text += &format!("InMsg: {}\n", debug_message(msg)?);
+/// Decodes the config contract data:
+/// `cfg_dict:^Cell stored_seqno:uint32 public_key:uint256 vote_dict:(HashmapE 256 ConfigProposalStatus)`
+/// into `seqno`, `public_key` and `proposals` with active config proposals. Proposed values
+/// are serialized like config params, values of unknown params are left as `value_boc` only.
+pub fn serialize_config_proposals(data: &Cell, mode: impl Into<SerializationOptions>) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let mut data = SliceData::load_cell_ref(data)?;
+    data.checked_drain_reference()?;
+    let mut map = Map::new();
+    serialize_field(&mut map, "seqno", data.get_next_u32()?);
+    serialize_uint256(&mut map, "public_key", &data.get_next_hash()?, mode);
+    let votes = HashmapE::with_hashmap(256, data.get_next_dictionary()?);
+    let mut proposals = Vec::new();
+    votes.iterate_slices(|mut key, mut status| -> Result<bool> {
+        let mut proposal = Map::new();
+        serialize_uint256(&mut proposal, "hash", &key.get_next_hash()?, mode);
+        serialize_config_proposal_status(&mut proposal, &mut status, mode)?;
+        proposals.push(Value::from(proposal));
         Ok(true)
     })?;
-    let out_msgs = extra.read_out_msg_descr()?;
-    out_msgs.iterate_objects(|out_msg| {
-        if let Some(msg) = out_msg.read_message()? {
-            text += &format!("OutMsg: {}\n", debug_message(msg)?);
-        }
+    serialize_field(&mut map, "proposals", proposals);
+    Ok(map)
+}
+
+/// `cfg_proposal_status#ce expires:uint32 proposal:^ConfigProposal is_critical:Bool
+/// voters:(HashmapE 16 True) remaining_weight:int64 validator_set_id:uint256
+/// rounds_remaining:uint8 wins:uint8 losses:uint8`
+fn serialize_config_proposal_status(
+    map: &mut Map<String, Value>,
+    status: &mut SliceData,
+    mode: SerializationOptions
+) -> Result<()> {
+    let tag = status.get_next_byte()?;
+    if tag != 0xce {
+        fail!("wrong config proposal status tag {:x}", tag)
+    }
+    serialize_field(map, "expires", status.get_next_u32()?);
+    let mut proposal = SliceData::load_cell(status.checked_drain_reference()?)?;
+    serialize_config_proposal(map, &mut proposal, mode)?;
+    serialize_field(map, "is_critical", status.get_next_bit()?);
+    let voters = HashmapE::with_hashmap(16, status.get_next_dictionary()?);
+    let mut voters_vec = Vec::new();
+    voters.iterate_slices(|mut key, _| -> Result<bool> {
+        voters_vec.push(Value::from(key.get_next_u16()?));
         Ok(true)
     })?;
-    let acc_blocks = extra.read_account_blocks()?;
-    acc_blocks.iterate_objects(|block| {
-        block.transactions().iterate_objects(|InRefValue(tr)| {
-            text += &format!("Transaction: {}\n", debug_transaction(tr)?);
-            Ok(true)
-        })
-    })?;
-    Ok(text)
+    serialize_field(map, "voters", voters_vec);
+    serialize_field(map, "remaining_weight", status.get_next_i64()?);
+    serialize_uint256(map, "validator_set_id", &status.get_next_hash()?, mode);
+    serialize_field(map, "rounds_remaining", status.get_next_byte()?);
+    serialize_field(map, "wins", status.get_next_byte()?);
+    serialize_field(map, "losses", status.get_next_byte()?);
+    Ok(())
 }
 
-pub fn db_serialize_block<'a>(
-    id_str: &'static str,
-    set: impl Into<BlockSerializationSetFH<'a>>
-) -> Result<Map<String, Value>> {
-    db_serialize_block_ex(id_str, set, SerializationMode::Standart)
+/// `cfg_proposal#f3 param_id:int32 param_value:(Maybe ^Cell) if_hash_equal:(Maybe uint256)`
+fn serialize_config_proposal(
+    map: &mut Map<String, Value>,
+    proposal: &mut SliceData,
+    mode: SerializationOptions
+) -> Result<()> {
+    let tag = proposal.get_next_byte()?;
+    if tag != 0xf3 {
+        fail!("wrong config proposal tag {:x}", tag)
+    }
+    let param = proposal.get_next_i32()?;
+    serialize_field(map, "param", param);
+    if proposal.get_next_bit()? {
+        let value = proposal.checked_drain_reference()?;
+        if param >= 0 {
+            let mut slice = SliceData::load_cell_ref(&value)?;
+            if let Ok(Some(json)) = serialize_known_config_param(param as u32, &mut slice, mode) {
+                serialize_field(map, "value", json);
+            }
+        }
+        serialize_cell(map, "value_boc", Some(&value), true, mode)?;
+    }
+    if proposal.get_next_bit()? {
+        serialize_uint256(map, "if_hash_equal", &proposal.get_next_hash()?, mode);
+    }
+    Ok(())
 }
 
-pub fn db_serialize_block_ex<'a>(
-    id_str: &'static str,
-    set:  impl Into<BlockSerializationSetFH<'a>>,
-    mode: SerializationMode
+/// Fields dropped from documents for compact payloads, e.g. for analytics pipelines
+/// where raw BOCs are stored elsewhere. Applied to the result of any `db_serialize_*`
+/// function, the fields are removed at every level of the document.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FieldFilter {
+    pub fields: Vec<String>,
+    /// Also drop `<field>_hash` companions of the dropped fields
+    pub drop_hashes: bool,
+}
+
+impl FieldFilter {
+    pub fn new(fields: &[&str]) -> Self {
+        Self {
+            fields: fields.iter().map(|field| field.to_string()).collect(),
+            drop_hashes: false,
+        }
+    }
+
+    /// Drops BOCs and proofs, `code_hash`, `data_hash` and other hashes are kept
+    /// to identify the dropped cells
+    pub fn compact() -> Self {
+        Self::new(&["boc", "proof", "code", "data", "library"])
+    }
+
+    fn drops(&self, name: &str) -> bool {
+        if self.fields.iter().any(|field| field == name) {
+            return true
+        }
+        self.drop_hashes && name.strip_suffix("_hash")
+            .map_or(false, |name| self.fields.iter().any(|field| field == name))
+    }
+
+    pub fn apply(&self, doc: &mut Map<String, Value>) {
+        fn apply_value(filter: &FieldFilter, value: &mut Value) {
+            match value {
+                Value::Object(map) => filter.apply(map),
+                Value::Array(array) => array.iter_mut().for_each(|value| apply_value(filter, value)),
+                _ => ()
+            }
+        }
+        doc.retain(|name, _| !self.drops(name));
+        doc.values_mut().for_each(|value| apply_value(self, value));
+    }
+}
+
+/// Fields with BOCs which are moved to the cell pool by `pool_repeated_cells`
+pub const POOLED_CELL_FIELDS: [&str; 5] = ["boc", "code", "data", "library", "body"];
+
+/// Moves BOCs repeated in the document into the top-level `cell_pool` object keyed by root
+/// cell hash, the fields are replaced with `{ "pooled": "<hash>" }`. The pool is ordered
+/// by the first appearance of the BOC in the document, strings of the pooled fields which
+/// are not BOCs are kept in place. Use `inline_pooled_cells` to get back the original document.
+pub fn pool_repeated_cells(mut map: Map<String, Value>) -> Result<Map<String, Value>> {
+    fn count(value: &Value, counts: &mut Vec<(String, usize)>, indices: &mut HashMap<String, usize>) {
+        match value {
+            Value::Object(map) => for (name, value) in map {
+                match value {
+                    Value::String(boc) if POOLED_CELL_FIELDS.contains(&name.as_str()) => {
+                        let index = *indices.entry(boc.clone()).or_insert_with(|| {
+                            counts.push((boc.clone(), 0));
+                            counts.len() - 1
+                        });
+                        counts[index].1 += 1;
+                    }
+                    value => count(value, counts, indices)
+                }
+            }
+            Value::Array(array) => array.iter().for_each(|value| count(value, counts, indices)),
+            _ => ()
+        }
+    }
+    fn replace(value: &mut Value, pooled: &HashMap<String, String>) {
+        match value {
+            Value::Object(map) => for (name, value) in map.iter_mut() {
+                let hash = match value {
+                    Value::String(boc) if POOLED_CELL_FIELDS.contains(&name.as_str()) => {
+                        pooled.get(boc).cloned()
+                    }
+                    value => {
+                        replace(value, pooled);
+                        None
+                    }
+                };
+                if let Some(hash) = hash {
+                    *value = serde_json::json!({ "pooled": hash });
+                }
+            }
+            Value::Array(array) => array.iter_mut().for_each(|value| replace(value, pooled)),
+            _ => ()
+        }
+    }
+
+    if map.contains_key("cell_pool") {
+        fail!("document already has `cell_pool` field")
+    }
+    let mut value = Value::Object(std::mem::take(&mut map));
+    let mut counts = Vec::new();
+    count(&value, &mut counts, &mut HashMap::new());
+    let mut pooled = HashMap::new();
+    let mut pool = Map::new();
+    for (boc, _) in counts.into_iter().filter(|(_, count)| *count > 1) {
+        let cell = base64_decode(&boc).ok().and_then(|bytes| read_single_root_boc(bytes).ok());
+        let Some(cell) = cell else {
+            continue
+        };
+        let hash = cell.repr_hash().as_hex_string();
+        pool.insert(hash.clone(), boc.clone().into());
+        pooled.insert(boc, hash);
+    }
+    replace(&mut value, &pooled);
+    let Value::Object(mut map) = value else { unreachable!() };
+    if !pool.is_empty() {
+        serialize_field(&mut map, "cell_pool", pool);
+    }
+    Ok(map)
+}
+
+/// BOC embedded in the document: JSON pointer of the field, root cell hash and size in bytes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BocIndexEntry {
+    pub path: String,
+    pub hash: UInt256,
+    pub size: usize,
+}
+
+/// Collects BOCs of `POOLED_CELL_FIELDS` fields embedded in the document
+pub fn collect_boc_index(map: &Map<String, Value>) -> Result<Vec<BocIndexEntry>> {
+    fn collect_map(map: &Map<String, Value>, path: &mut String, index: &mut Vec<BocIndexEntry>) -> Result<()> {
+        let len = path.len();
+        for (name, value) in map {
+            path.push('/');
+            path.push_str(&name.replace('~', "~0").replace('/', "~1"));
+            match value {
+                Value::String(boc) if POOLED_CELL_FIELDS.contains(&name.as_str()) => {
+                    let bytes = base64_decode(boc)?;
+                    index.push(BocIndexEntry {
+                        path: path.clone(),
+                        hash: read_single_root_boc(&bytes)?.repr_hash(),
+                        size: bytes.len(),
+                    });
+                }
+                value => collect(value, path, index)?
+            }
+            path.truncate(len);
+        }
+        Ok(())
+    }
+    fn collect(value: &Value, path: &mut String, index: &mut Vec<BocIndexEntry>) -> Result<()> {
+        match value {
+            Value::Object(map) => collect_map(map, path, index)?,
+            Value::Array(array) => for (i, value) in array.iter().enumerate() {
+                let len = path.len();
+                path.push_str(&format!("/{}", i));
+                collect(value, path, index)?;
+                path.truncate(len);
+            }
+            _ => ()
+        }
+        Ok(())
+    }
+
+    let mut index = Vec::new();
+    collect_map(map, &mut String::new(), &mut index)?;
+    Ok(index)
+}
+
+/// Serializes block like `db_serialize_block_ex` and returns index of the embedded BOCs
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_block_set()?;
+/// let (_, index) = db_serialize_block_with_boc_index("id", &set, SerializationMode::Standart)?;
+/// assert_eq!(index[0].path, "/boc");
+/// assert_eq!(index[0].hash, set.id);
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_block_with_boc_index<'a>(
+    id_str: &'static str,
+    set: impl Into<BlockSerializationSetFH<'a>>,
+    mode: impl Into<SerializationOptions>
+) -> Result<(Map<String, Value>, Vec<BocIndexEntry>)> {
+    let mode = mode.into();
+    let map = db_serialize_block_ex(id_str, set, mode)?;
+    let index = collect_boc_index(&map)?;
+    Ok((map, index))
+}
+
+/// Serializes shard state like `db_serialize_shard_state_ex` and returns index of the
+/// embedded BOCs
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_state_set()?;
+/// let (_, index) = db_serialize_shard_state_with_boc_index("id", &set, SerializationMode::Standart)?;
+/// assert_eq!(index[0].path, "/boc");
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_shard_state_with_boc_index(
+    id_str: &'static str,
+    set: &ShardStateSerializationSet,
+    mode: impl Into<SerializationOptions>
+) -> Result<(Map<String, Value>, Vec<BocIndexEntry>)> {
+    let mode = mode.into();
+    let map = db_serialize_shard_state_ex(id_str, set, mode)?;
+    let index = collect_boc_index(&map)?;
+    Ok((map, index))
+}
+
+/// Serializes block like `db_serialize_block_ex` and adds the whole state update
+/// Merkle update cell as `state_update_boc`, so state transition can be applied
+/// having only the JSON document
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_block_set()?;
+/// let doc = db_serialize_block_with_state_update("id", &set, SerializationMode::Standart)?;
+/// assert!(doc["state_update_boc"].is_string());
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_block_with_state_update<'a>(
+    id_str: &'static str,
+    set: impl Into<BlockSerializationSetFH<'a>>,
+    mode: impl Into<SerializationOptions>
 ) -> Result<Map<String, Value>> {
+    let mode = mode.into();
     let set: BlockSerializationSetFH = set.into();
-    let mut map = Map::new();
-    serialize_field(&mut map, "json_version", VERSION);
-    serialize_id(&mut map, id_str, Some(set.id));
-    serialize_file_hash(&mut map, set.file_hash, set.boc);
-    serialize_field(&mut map, "status", set.status as u8);
-    if mode.is_q_server() {
-        serialize_field(&mut map, "status_name", match set.status {
-            BlockProcessingStatus::Unknown => "unknown",
-            BlockProcessingStatus::Proposed => "proposed",
-            BlockProcessingStatus::Finalized => "finalized",
-            BlockProcessingStatus::Refused => "refused",
-        });
-    }
-    map.insert("boc".to_string(), base64_encode(set.boc).into());
-    map.insert("global_id".to_string(), set.block.global_id.into());
-    let block_info = set.block.read_info()?;
+    let state_update = set.block.read_state_update()?.serialize()?;
+    let mut map = db_serialize_block_ex(id_str, set, mode)?;
+    serialize_cell(&mut map, "state_update_boc", Some(&state_update), false, mode)?;
+    Ok(map)
+}
+
+/// Serializes block like `db_serialize_block_ex` and embeds full documents of the transactions
+/// as `transaction` field of `account_blocks[].transactions[]` items, so the block document
+/// is self-contained. With `with_messages` transactions are serialized by
+/// `db_serialize_transaction_deep` and contain full documents of their messages too.
+pub fn db_serialize_block_deep<'a>(
+    id_str: &'static str,
+    set: impl Into<BlockSerializationSetFH<'a>>,
+    with_messages: bool,
+    mode: impl Into<SerializationOptions>
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let set: BlockSerializationSetFH = set.into();
+    let (block, block_id) = (set.block, set.id);
+    let mut map = db_serialize_block_ex(id_str, set, mode)?;
+    let workchain_id = block.read_info()?.shard().workchain_id();
+    let mut account_blocks = match map.get_mut("account_blocks") {
+        Some(Value::Array(account_blocks)) => account_blocks.iter_mut(),
+        _ => return Ok(map)
+    };
+    block.read_extra()?.read_account_blocks()?.iterate_objects(|account_block| {
+        let Some(Value::Array(transactions)) = account_blocks.next()
+            .and_then(|group| group.get_mut("transactions")) else {
+            fail!("block document has no transactions of account block {}", account_block.account_addr().as_hex_string())
+        };
+        let mut transactions = transactions.iter_mut();
+        account_block.transaction_iterate_full(|_, cell, _| {
+            let Some(Value::Object(item)) = transactions.next() else {
+                fail!("block document has no transaction {:x}", cell.repr_hash())
+            };
+            let set = TransactionSerializationSet {
+                transaction: Transaction::construct_from_cell(cell.clone())?,
+                id: cell.repr_hash(),
+                status: TransactionProcessingStatus::Finalized,
+                block_id: Some(block_id.clone()),
+                workchain_id,
+                boc: write_boc(&cell)?,
+                proof: None,
+            };
+            let doc = match with_messages {
+                true => db_serialize_transaction_deep("id", &set, mode)?,
+                false => db_serialize_transaction_ex("id", &set, mode)?,
+            };
+            item.insert("transaction".to_string(), doc.into());
+            Ok(true)
+        })
+    })?;
+    Ok(map)
+}
+
+pub fn debug_block_full(block: &Block) -> Result<String> {
+    let root_cell = block.serialize()?;
+    let set = BlockSerializationSet {
+        block: block.clone(),
+        id: root_cell.repr_hash(),
+        status: BlockProcessingStatus::Finalized,
+        boc: Vec::new(),
+    };
+    let map = db_serialize_block_ex("id", &set, SerializationMode::Debug)?;
+
+    let mut text = format!("Block: {:#}\n", serde_json::json!(map));
+    let extra = block.read_extra()?;
+    let in_msgs = extra.read_in_msg_descr()?;
+    in_msgs.iterate_objects(|in_msg| {
+        let msg = in_msg.read_message()?;
+        text += &format!("InMsg: {}\n", debug_message(msg)?);
+        Ok(true)
+    })?;
+    let out_msgs = extra.read_out_msg_descr()?;
+    out_msgs.iterate_objects(|out_msg| {
+        if let Some(msg) = out_msg.read_message()? {
+            text += &format!("OutMsg: {}\n", debug_message(msg)?);
+        }
+        Ok(true)
+    })?;
+    let acc_blocks = extra.read_account_blocks()?;
+    acc_blocks.iterate_objects(|block| {
+        block.transactions().iterate_objects(|InRefValue(tr)| {
+            text += &format!("Transaction: {}\n", debug_transaction(tr)?);
+            Ok(true)
+        })
+    })?;
+    Ok(text)
+}
+
+fn serialize_block_info(map: &mut Map<String, Value>, block_info: &BlockInfo, mode: SerializationOptions) -> Result<()> {
     map.insert("version".to_string(), block_info.version().into());
     map.insert("after_merge".to_string(), block_info.after_merge().into());
     map.insert("before_split".to_string(), block_info.before_split().into());
@@ -1448,8 +2413,11 @@ pub fn db_serialize_block_ex<'a>(
     map.insert("seq_no".to_string(), block_info.seq_no().into());
     map.insert("vert_seq_no".to_string(), block_info.vert_seq_no().into());
     map.insert("gen_utime".to_string(), block_info.gen_utime().as_u32().into());
-    serialize_lt(&mut map, "start_lt", &block_info.start_lt(), mode);
-    serialize_lt(&mut map, "end_lt", &block_info.end_lt(), mode);
+    if block_info.gen_utime_ms_part() != 0 {
+        serialize_field(map, "gen_utime_ms", block_info.gen_utime_ms());
+    }
+    serialize_lt(map, "start_lt", &block_info.start_lt(), mode);
+    serialize_lt(map, "end_lt", &block_info.end_lt(), mode);
     map.insert("gen_validator_list_hash_short".to_string(), block_info.gen_validator_list_hash_short().into());
     map.insert("gen_catchain_seqno".to_string(), block_info.gen_catchain_seqno().into());
     map.insert("min_ref_mc_seqno".to_string(), block_info.min_ref_mc_seqno().into());
@@ -1458,13 +2426,44 @@ pub fn db_serialize_block_ex<'a>(
     map.insert("shard".to_string(), block_info.shard().shard_prefix_as_str_with_tag().into());
 
     if let Some(gs) = block_info.gen_software() {
-        serialize_field(&mut map, "gen_software_version", gs.version);
-        serialize_u64(&mut map, "gen_software_capabilities", &gs.capabilities, mode);
+        serialize_field(map, "gen_software_version", gs.version);
+        serialize_capabilities(map, "gen_software_capabilities", gs.capabilities, mode);
     }
 
     let prev_block_ref = block_info.read_prev_ref()?;
     map.insert("prev_seq_no".to_string(), prev_block_ref.prev1()?.seq_no.into());
 
+    serialize_block_refs(map, block_info, mode)?;
+    if let Some(prev2) = prev_block_ref.prev2()? {
+        // merge block: both parents with their shards for uniform handling of the block graph
+        let (left, right) = block_info.shard().split()?;
+        let parents = [(prev_block_ref.prev1()?, left), (prev2, right)].iter()
+            .map(|(blk_ref, shard)| {
+                let mut parent = serialize_block_ref(blk_ref, None, mode);
+                if let Value::Object(parent) = &mut parent {
+                    serialize_field(parent, "workchain_id", shard.workchain_id());
+                    serialize_field(parent, "shard", shard.shard_prefix_as_str_with_tag());
+                }
+                parent
+            })
+            .collect::<Vec<_>>();
+        serialize_field(map, "parents", parents);
+    }
+    if block_info.vert_seqno_incr() != 0 {
+        // vertical refs point to the blocks of the previous vertical chain
+        let prev_vert_seq_no = block_info.vert_seq_no().saturating_sub(1);
+        for id_str in ["prev_vert_ref", "prev_vert_alt_ref"] {
+            if let Some(Value::Object(blk_ref)) = map.get_mut(id_str) {
+                serialize_field(blk_ref, "vert_seq_no", prev_vert_seq_no);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `master_ref`, `prev_ref`, `prev_alt_ref`, `prev_vert_ref` and `prev_vert_alt_ref` of the block
+fn serialize_block_refs(map: &mut Map<String, Value>, block_info: &BlockInfo, mode: SerializationOptions) -> Result<()> {
+    let prev_block_ref = block_info.read_prev_ref()?;
     let (vert_prev1, vert_prev2) = match &block_info.read_prev_vert_ref()? {
         Some(blk) => (Some(blk.prev1()?), blk.prev2()?),
         None => (None, None)
@@ -1477,7 +2476,256 @@ pub fn db_serialize_block_ex<'a>(
     ].iter().for_each(|(id_str, blk_ref)| if let Some(blk_ref) = blk_ref {
         map.insert(id_str.to_string(), serialize_block_ref(blk_ref, None, mode));
     });
-    let value_flow = set.block.read_value_flow()?;
+    Ok(())
+}
+
+fn serialize_account_block(
+    account_block: &AccountBlock,
+    workchain_id: i32,
+    mode: SerializationOptions
+) -> Result<(Map<String, Value>, usize)> {
+    let address = construct_address(workchain_id, account_block.account_addr())?;
+    let mut map = Map::new();
+    serialize_field(&mut map, "account_addr", address.to_string());
+    let mut transactions = Vec::new();
+    account_block.transaction_iterate_full(|key, transaction_cell, cc| {
+        let mut map = Map::new();
+        serialize_lt(&mut map, "lt", &key, mode);
+        serialize_id(&mut map, "transaction_id", Some(&transaction_cell.repr_hash()), mode);
+        serialize_cc(&mut map, "total_fees", &cc, mode)?;
+        transactions.push(map);
+        Ok(true)
+    })?;
+    serialize_field(&mut map, "transactions", transactions);
+    let state_update = account_block.read_state_update()?;
+    serialize_id(&mut map, "old_hash", Some(&state_update.old_hash), mode);
+    serialize_id(&mut map, "new_hash", Some(&state_update.new_hash), mode);
+    let tr_count = account_block.transaction_count()?;
+    serialize_field(&mut map, "tr_count", tr_count);
+    Ok((map, tr_count))
+}
+
+/// Serializes the account block like items of `account_blocks` of the block document:
+/// `account_addr`, `transactions` with `lt`, `transaction_id` and `total_fees`,
+/// `old_hash`/`new_hash` of the state update and `tr_count`.
+pub fn db_serialize_account_block(
+    account_block: &AccountBlock,
+    workchain_id: i32,
+    mode: impl Into<SerializationOptions>
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    Ok(serialize_account_block(account_block, workchain_id, mode)?.0)
+}
+
+/// Serializes account blocks of the block as standalone documents with transactions of
+/// one account: `account_blocks` items with `id` (`<account_addr>/<block_id>`), `block_id`,
+/// `workchain_id` and `total_fees` of all the transactions.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_block_set()?;
+/// // the sample block has no transactions
+/// let docs = db_serialize_account_transactions(&set.id, &set.block, SerializationMode::Standart)?;
+/// assert!(docs.is_empty());
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_account_transactions(
+    block_id: &UInt256,
+    block: &Block,
+    mode: impl Into<SerializationOptions>
+) -> Result<Vec<Map<String, Value>>> {
+    let mode = mode.into();
+    let workchain_id = block.read_info()?.shard().workchain_id();
+    let mut docs = Vec::new();
+    block.read_extra()?.read_account_blocks()?.iterate_objects(|account_block| {
+        let (group, _) = serialize_account_block(&account_block, workchain_id, mode)?;
+        let mut map = Map::new();
+        serialize_field(&mut map, "json_version", VERSION);
+        let account_addr = group["account_addr"].as_str().unwrap_or_default();
+        serialize_field(&mut map, "id", format!("{}/{}", account_addr, format_uint256(block_id, mode)));
+        serialize_id(&mut map, "block_id", Some(block_id), mode);
+        serialize_field(&mut map, "workchain_id", workchain_id);
+        map.extend(group);
+        serialize_cc(&mut map, "total_fees", account_block.total_fee(), mode)?;
+        docs.push(map);
+        Ok(true)
+    })?;
+    Ok(docs)
+}
+
+/// Serializes account creation event if the transaction turns the nonexistent account into
+/// uninit or active one: `created_in_block`, the transaction and the funding message with
+/// its source (`funded_by`) and value. Returns `None` for other transactions.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_transaction_set()?;
+/// // the sample account is active before the transaction
+/// let doc = db_serialize_account_creation(
+///     &ever_block::UInt256::default(), 0, &set.id, &set.transaction, SerializationMode::Standart
+/// )?;
+/// assert!(doc.is_none());
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_account_creation(
+    block_id: &UInt256,
+    workchain_id: i32,
+    transaction_id: &UInt256,
+    transaction: &Transaction,
+    mode: impl Into<SerializationOptions>
+) -> Result<Option<Map<String, Value>>> {
+    let mode = mode.into();
+    if transaction.orig_status != AccountStatus::AccStateNonexist
+        || !matches!(transaction.end_status, AccountStatus::AccStateUninit | AccountStatus::AccStateActive)
+    {
+        return Ok(None)
+    }
+    let address = construct_address(workchain_id, transaction.account_id().clone())?;
+    let mut map = Map::new();
+    serialize_field(&mut map, "json_version", VERSION);
+    serialize_field(&mut map, "id", address.to_string());
+    serialize_field(&mut map, "account_addr", address.to_string());
+    serialize_field(&mut map, "workchain_id", workchain_id);
+    serialize_id(&mut map, "created_in_block", Some(block_id), mode);
+    serialize_id(&mut map, "transaction_id", Some(transaction_id), mode);
+    serialize_lt(&mut map, "lt", &transaction.logical_time(), mode);
+    serialize_field(&mut map, "now", transaction.now());
+    serialize_account_status(&mut map, "end_status", &transaction.end_status, mode);
+    if let Some(msg_cell) = transaction.in_msg_cell() {
+        serialize_id(&mut map, "funding_msg_id", Some(&msg_cell.repr_hash()), mode);
+        let msg = Message::construct_from_cell(msg_cell)?;
+        if let Some(src) = msg.src_ref() {
+            serialize_field(&mut map, "funded_by", src.to_string());
+        }
+        if let Some(value) = msg.get_value() {
+            serialize_cc(&mut map, "value", value, mode)?;
+        }
+    }
+    Ok(Some(map))
+}
+
+/// Header of the block: `id`, `global_id` and the `BlockInfo` fields as in the block document.
+/// Neither value flow nor extra of the block is read.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let block = samples::sample_block()?;
+/// let doc = db_serialize_block_info("id", &block, SerializationMode::Standart)?;
+/// assert_eq!(doc["seq_no"], 0);
+/// assert!(doc.get("value_flow").is_none());
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_block_info(
+    id_str: &'static str,
+    block: &Block,
+    mode: impl Into<SerializationOptions>
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let mut map = Map::new();
+    serialize_field(&mut map, "json_version", VERSION);
+    serialize_id(&mut map, id_str, Some(&block.hash()?), mode);
+    map.insert("global_id".to_string(), block.global_id.into());
+    serialize_block_info(&mut map, &block.read_info()?, mode)?;
+    Ok(map)
+}
+
+/// Summary of the block for list views: `id`, `status`, `workchain_id`, `shard`, `seq_no`,
+/// `gen_utime`, `key_block`, `tr_count` and the block refs. Fields have the same format as in
+/// the full block document; messages descriptors and the masterchain part are not read.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_block_set()?;
+/// let doc = db_serialize_block_brief("id", &set, SerializationMode::Standart)?;
+/// assert_eq!(doc["tr_count"], 0);
+/// assert!(doc.get("boc").is_none());
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_block_brief<'a>(
+    id_str: &'static str,
+    set: impl Into<BlockSerializationSetFH<'a>>,
+    mode: impl Into<SerializationOptions>
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let set: BlockSerializationSetFH = set.into();
+    let mut map = Map::new();
+    serialize_field(&mut map, "json_version", VERSION);
+    serialize_id(&mut map, id_str, Some(set.id), mode);
+    serialize_status(&mut map, set.status as u8, BlockProcessingStatusJson::from(set.status).name(), mode);
+    let block_info = set.block.read_info()?;
+    serialize_field(&mut map, "workchain_id", block_info.shard().workchain_id());
+    serialize_field(&mut map, "shard", block_info.shard().shard_prefix_as_str_with_tag());
+    serialize_field(&mut map, "seq_no", block_info.seq_no());
+    serialize_field(&mut map, "gen_utime", block_info.gen_utime().as_u32());
+    serialize_field(&mut map, "key_block", block_info.key_block());
+    let mut tr_count = 0;
+    set.block.read_extra()?.read_account_blocks()?.iterate_objects(|account_block| {
+        tr_count += account_block.transaction_count()?;
+        Ok(true)
+    })?;
+    serialize_field(&mut map, "tr_count", tr_count);
+    serialize_block_refs(&mut map, &block_info, mode)?;
+    Ok(map)
+}
+
+/// Economics of the masterchain block taken from its value flow and the state after the block:
+/// `global_balance`, `total_balance`, `total_validator_fees` and block deltas `created`,
+/// `minted`, `recovered` and `fees_collected`. Returns `None` if the state has no masterchain part.
+/// Fails if the shard or seq_no of the state differ from the block ones.
+pub fn db_serialize_block_economics(
+    block_id: &UInt256,
+    block: &Block,
+    state: &ShardStateUnsplit,
+    mode: impl Into<SerializationOptions>
+) -> Result<Option<Map<String, Value>>> {
+    let mode = mode.into();
+    let block_info = block.read_info()?;
+    if state.shard() != block_info.shard() || state.seq_no() != block_info.seq_no() {
+        fail!(
+            "state {}:{} is not the state after the block {}:{}",
+            state.shard(), state.seq_no(), block_info.shard(), block_info.seq_no()
+        )
+    }
+    let Some(master) = state.read_custom()? else {
+        return Ok(None)
+    };
+    let value_flow = block.read_value_flow()?;
+    let mut map = Map::new();
+    serialize_field(&mut map, "json_version", VERSION);
+    serialize_id(&mut map, "id", Some(block_id), mode);
+    serialize_field(&mut map, "seq_no", block_info.seq_no());
+    serialize_field(&mut map, "gen_utime", block_info.gen_utime().as_u32());
+    serialize_cc(&mut map, "global_balance", &master.global_balance, mode)?;
+    serialize_cc(&mut map, "total_balance", state.total_balance(), mode)?;
+    serialize_cc(&mut map, "total_validator_fees", state.total_validator_fees(), mode)?;
+    serialize_cc(&mut map, "created", &value_flow.created, mode)?;
+    serialize_cc(&mut map, "minted", &value_flow.minted, mode)?;
+    serialize_cc(&mut map, "recovered", &value_flow.recovered, mode)?;
+    serialize_cc(&mut map, "fees_collected", &value_flow.fees_collected, mode)?;
+    Ok(Some(map))
+}
+
+/// Value flow of the block as the `value_flow` section of the block document.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let block = samples::sample_block()?;
+/// let doc = db_serialize_value_flow(&block.read_value_flow()?, SerializationMode::QServer)?;
+/// assert_eq!(doc["fees_collected"], "0x0");
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_value_flow(value_flow: &ValueFlow, mode: impl Into<SerializationOptions>) -> Result<Value> {
+    let mode = mode.into();
     let mut value_map = Map::new();
     serialize_cc(&mut value_map, "from_prev_blk",  &value_flow.from_prev_blk, mode)?;
     serialize_cc(&mut value_map, "to_next_blk",    &value_flow.to_next_blk, mode)?;
@@ -1489,11 +2737,60 @@ pub fn db_serialize_block_ex<'a>(
     serialize_cc(&mut value_map, "created",        &value_flow.created, mode)?;
     serialize_cc(&mut value_map, "minted",         &value_flow.minted, mode)?;
     serialize_copyleft_rewards(&mut value_map, "copyleft_rewards", &value_flow.copyleft_rewards, mode)?;
-    map.insert("value_flow".to_string(), value_map.into());
+    Ok(value_map.into())
+}
+
+/// Serializes the block in `Standart` mode.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_block_set()?;
+/// let doc = db_serialize_block("id", &set)?;
+/// assert_eq!(doc["id"], set.id.as_hex_string());
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_block<'a>(
+    id_str: &'static str,
+    set: impl Into<BlockSerializationSetFH<'a>>
+) -> Result<Map<String, Value>> {
+    db_serialize_block_ex(id_str, set, SerializationMode::Standart)
+}
+
+/// Serializes the block with its info, value flow, messages descriptors, account blocks and masterchain part if any.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_block_set()?;
+/// let doc = db_serialize_block_ex("id", &set, SerializationMode::QServer)?;
+/// let (prev_ref, _) = parse_block_prev_refs(&doc)?;
+/// assert_eq!(prev_ref, set.block.read_info()?.read_prev_ref()?);
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_block_ex<'a>(
+    id_str: &'static str,
+    set:  impl Into<BlockSerializationSetFH<'a>>,
+    mode: impl Into<SerializationOptions>
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let set: BlockSerializationSetFH = set.into();
+    let mut map = Map::new();
+    serialize_field(&mut map, "json_version", VERSION);
+    serialize_id(&mut map, id_str, Some(set.id), mode);
+    serialize_file_hash(&mut map, set.file_hash, set.boc, mode);
+    serialize_status(&mut map, set.status as u8, BlockProcessingStatusJson::from(set.status).name(), mode);
+    map.insert("boc".to_string(), base64_encode(set.boc).into());
+    map.insert("global_id".to_string(), set.block.global_id.into());
+    let block_info = set.block.read_info()?;
+    serialize_block_info(&mut map, &block_info, mode)?;
+    map.insert("value_flow".to_string(), db_serialize_value_flow(&set.block.read_value_flow()?, mode)?);
 
     let state_update = set.block.read_state_update()?;
-    serialize_id(&mut map, "old_hash", Some(&state_update.old_hash));
-    serialize_id(&mut map, "new_hash", Some(&state_update.new_hash));
+    serialize_id(&mut map, "old_hash", Some(&state_update.old_hash), mode);
+    serialize_id(&mut map, "new_hash", Some(&state_update.new_hash), mode);
     map.insert("old_depth".to_string(), state_update.old_depth.into());
     map.insert("new_depth".to_string(), state_update.new_depth.into());
 
@@ -1514,25 +2811,7 @@ pub fn db_serialize_block_ex<'a>(
     let mut total_tr_count = 0;
     let mut account_blocks = Vec::new();
     extra.read_account_blocks()?.iterate_objects(|account_block| {
-        let workchain = block_info.shard().workchain_id();
-        let address = construct_address(workchain, account_block.account_addr())?;
-        let mut map = Map::new();
-        serialize_field(&mut map, "account_addr", address.to_string());
-        let mut transactions = Vec::new();
-        account_block.transaction_iterate_full(|key, transaction_cell, cc| {
-            let mut map = Map::new();
-            serialize_lt(&mut map, "lt", &key, mode);
-            serialize_id(&mut map, "transaction_id", Some(&transaction_cell.repr_hash()));
-            serialize_cc(&mut map, "total_fees", &cc, mode)?;
-            transactions.push(map);
-            Ok(true)
-        })?;
-        serialize_field(&mut map, "transactions", transactions);
-        let state_update = account_block.read_state_update()?;
-        serialize_id(&mut map, "old_hash", Some(&state_update.old_hash));
-        serialize_id(&mut map, "new_hash", Some(&state_update.new_hash));
-        let tr_count = account_block.transaction_count()?;
-        serialize_field(&mut map, "tr_count", tr_count);
+        let (map, tr_count) = serialize_account_block(&account_block, block_info.shard().workchain_id(), mode)?;
         account_blocks.push(map);
         total_tr_count += tr_count;
         Ok(true)
@@ -1542,12 +2821,12 @@ pub fn db_serialize_block_ex<'a>(
     }
     serialize_field(&mut map, "tr_count", total_tr_count);
 
-    serialize_id(&mut map, "rand_seed", Some(&extra.rand_seed));
-    serialize_id(&mut map, "created_by", Some(&extra.created_by));
+    serialize_id(&mut map, "rand_seed", Some(&extra.rand_seed), mode);
+    serialize_id(&mut map, "created_by", Some(&extra.created_by), mode);
 
     if let Some(master) = extra.read_custom()? {
         let mut master_map = Map::new();
-        serialize_shard_hashes(&mut master_map, "shard_hashes", master.hashes(), mode)?;
+        serialize_shard_hashes(&mut master_map, "shard_hashes", master.hashes(), None, mode)?;
         let mut fees_map = Vec::new();
         master.fees().iterate_slices(|mut key, ref mut shard| {
             let workchain_id = key.get_next_i32()?;
@@ -1566,7 +2845,7 @@ pub fn db_serialize_block_ex<'a>(
         }
         let mut crypto_signs = vec![];
         master.prev_blk_signatures().iterate(|s| {
-            crypto_signs.push(serialize_crypto_signature(&s)?);
+            crypto_signs.push(serialize_crypto_signature(&s, mode)?);
             Ok(true)
         })?;
         master_map.insert("prev_blk_signatures".to_string(), crypto_signs.into());
@@ -1584,6 +2863,366 @@ pub fn db_serialize_block_ex<'a>(
     Ok(map)
 }
 
+/// Adds value and destination of `recover_create_msg` and `mint_msg` to the `master` section
+/// of the block document as `<msg>_value` and `<msg>_dst`, so treasury monitoring doesn't
+/// need to decode the messages.
+pub fn serialize_special_msg_values(
+    doc: &mut Map<String, Value>,
+    block: &Block,
+    mode: impl Into<SerializationOptions>
+) -> Result<()> {
+    let mode = mode.into();
+    let Some(master) = block.read_extra()?.read_custom()? else {
+        return Ok(())
+    };
+    let Some(Value::Object(master_map)) = doc.get_mut("master") else {
+        fail!("block document has no `master` section")
+    };
+    for (value_str, dst_str, msg) in [
+        ("recover_create_msg_value", "recover_create_msg_dst", master.read_recover_create_msg()?),
+        ("mint_msg_value", "mint_msg_dst", master.read_mint_msg()?),
+    ] {
+        if let Some(msg) = msg {
+            let msg = msg.read_message()?;
+            if let Some(value) = msg.get_value() {
+                serialize_cc(master_map, value_str, value, mode)?;
+            }
+            if let Some(dst) = msg.dst_ref() {
+                serialize_field(master_map, dst_str, dst.to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Adds `stats` section with aggregates over the transactions of the block: `messages`
+/// and `transactions` counts by `msg_type_name` and `tr_type_name`, `aborted` count,
+/// total `gas_used` and total `fees` split by kind: `storage`, `gas`, `action`, `fwd`
+/// and `total`. Messages passed between accounts of the block are counted once.
+pub fn serialize_block_stats(
+    doc: &mut Map<String, Value>,
+    block: &Block,
+    mode: impl Into<SerializationOptions>
+) -> Result<()> {
+    let mode = mode.into();
+    let mut messages = Map::new();
+    let mut transactions = Map::new();
+    let mut counted = HashSet::new();
+    let mut aborted = 0u32;
+    let mut gas_used = 0u64;
+    let [mut storage, mut gas, mut action, mut fwd, mut total] = <[Grams; 5]>::default();
+    let count = |map: &mut Map<String, Value>, name: &str| {
+        let entry = map.entry(name.to_string()).or_insert(Value::from(0u32));
+        *entry = Value::from(entry.as_u64().unwrap_or_default() + 1);
+    };
+    block.read_extra()?.read_account_blocks()?.iterate_objects(|account_block| {
+        account_block.transactions().iterate_objects(|InRefValue(transaction)| {
+            let mut msg_cells = Vec::new();
+            msg_cells.extend(transaction.in_msg_cell());
+            transaction.out_msgs.iterate_slices(|slice| {
+                msg_cells.extend(slice.reference_opt(0));
+                Ok(true)
+            })?;
+            let opts = transaction.out_msgs.serde_opts();
+            for cell in msg_cells {
+                if !counted.insert(cell.repr_hash()) {
+                    continue
+                }
+                if let Ok(msg) = CommonMessage::construct_from_cell_with_opts(cell, opts)?.get_std() {
+                    let msg_type_name = match msg.header() {
+                        CommonMsgInfo::IntMsgInfo(_) => "internal",
+                        CommonMsgInfo::ExtInMsgInfo(_) => "extIn",
+                        CommonMsgInfo::ExtOutMsgInfo(_) => "extOut",
+                    };
+                    count(&mut messages, msg_type_name);
+                }
+            }
+
+            let descr = transaction.read_description()?;
+            let (_, tr_type_name, is_aborted) = transaction_type(&descr);
+            count(&mut transactions, tr_type_name);
+            if is_aborted == Some(true) {
+                aborted += 1;
+            }
+            let (storage_ph, compute_ph, action_ph) = match &descr {
+                TransactionDescr::Ordinary(tr) => (tr.storage_ph.as_ref(), Some(&tr.compute_ph), tr.action.as_ref()),
+                TransactionDescr::Storage(ph) => (Some(ph), None, None),
+                TransactionDescr::TickTock(tr) => (Some(&tr.storage_ph), Some(&tr.compute_ph), tr.action.as_ref()),
+                TransactionDescr::SplitPrepare(tr) => (tr.storage_ph.as_ref(), Some(&tr.compute_ph), tr.action.as_ref()),
+                TransactionDescr::SplitInstall(_) => (None, None, None),
+                TransactionDescr::MergePrepare(tr) => (Some(&tr.storage_ph), None, None),
+                TransactionDescr::MergeInstall(tr) => (tr.storage_ph.as_ref(), Some(&tr.compute_ph), tr.action.as_ref()),
+            };
+            if let Some(ph) = storage_ph {
+                storage.add(&ph.storage_fees_collected)?;
+            }
+            if let Some(TrComputePhase::Vm(ph)) = compute_ph {
+                gas.add(&ph.gas_fees)?;
+                gas_used += ph.gas_used.as_u64();
+            }
+            if let Some(ph) = action_ph {
+                if let Some(fees) = &ph.total_action_fees {
+                    action.add(fees)?;
+                }
+                if let Some(fees) = &ph.total_fwd_fees {
+                    fwd.add(fees)?;
+                }
+            }
+            total.add(&transaction.total_fees().grams)?;
+            Ok(true)
+        })
+    })?;
+
+    let mut fees = Map::new();
+    serialize_grams(&mut fees, "storage", &storage, mode);
+    serialize_grams(&mut fees, "gas", &gas, mode);
+    serialize_grams(&mut fees, "action", &action, mode);
+    serialize_grams(&mut fees, "fwd", &fwd, mode);
+    serialize_grams(&mut fees, "total", &total, mode);
+    let mut stats = Map::new();
+    serialize_field(&mut stats, "messages", messages);
+    serialize_field(&mut stats, "transactions", transactions);
+    serialize_field(&mut stats, "aborted", aborted);
+    serialize_u64(&mut stats, "gas_used", &gas_used, mode);
+    serialize_field(&mut stats, "fees", fees);
+    serialize_field(doc, "stats", stats);
+    Ok(())
+}
+
+/// Embeds full documents of `recover_create_msg` and `mint_msg` messages as `message` field
+/// of their descriptors in the `master` section of the block document, so minted and
+/// recovered amounts are available without a lookup of the messages.
+pub fn serialize_special_msgs(
+    doc: &mut Map<String, Value>,
+    block: &Block,
+    mode: impl Into<SerializationOptions>
+) -> Result<()> {
+    let mode = mode.into();
+    let Some(master) = block.read_extra()?.read_custom()? else {
+        return Ok(())
+    };
+    let Some(Value::Object(master_map)) = doc.get_mut("master") else {
+        fail!("block document has no `master` section")
+    };
+    for (id_str, msg) in [
+        ("recover_create_msg", master.read_recover_create_msg()?),
+        ("mint_msg", master.read_mint_msg()?),
+    ] {
+        let Some(msg) = msg else {
+            continue
+        };
+        let Some(Value::Object(descr)) = master_map.get_mut(id_str) else {
+            fail!("block document has no `master.{}` descriptor", id_str)
+        };
+        let message = msg.read_message()?;
+        let cell = message.serialize()?;
+        let set = MessageSerializationSet {
+            message,
+            id: cell.repr_hash(),
+            block_id: None,
+            transaction_id: None,
+            transaction_now: None,
+            status: MessageProcessingStatus::Finalized,
+            boc: write_boc(&cell)?,
+            proof: None,
+        };
+        descr.insert("message".to_string(), db_serialize_message_ex("id", &set, mode)?.into());
+    }
+    Ok(())
+}
+
+/// Adds `in_msg_value`, `in_msg_src` and `in_msg_bounce` of the inbound message to the
+/// transaction document, so incoming transfers can be queried without joining messages.
+/// Nothing is added if the transaction has no inbound message or it is pruned.
+pub fn serialize_in_msg_values(
+    doc: &mut Map<String, Value>,
+    transaction: &Transaction,
+    mode: impl Into<SerializationOptions>
+) -> Result<()> {
+    let mode = mode.into();
+    let Some(in_msg) = transaction.in_msg_cell() else {
+        return Ok(())
+    };
+    if in_msg.is_pruned() {
+        return Ok(())
+    }
+    let msg = Message::construct_from_cell(in_msg)?;
+    match msg.header() {
+        CommonMsgInfo::IntMsgInfo(header) => {
+            serialize_grams(doc, "in_msg_value", &header.value.grams, mode);
+            serialize_field(doc, "in_msg_src", header.src.to_string());
+            serialize_field(doc, "in_msg_bounce", header.bounce);
+        }
+        CommonMsgInfo::ExtInMsgInfo(header) => {
+            serialize_field(doc, "in_msg_src", header.src.to_string());
+        }
+        CommonMsgInfo::ExtOutMsgInfo(_) => ()
+    }
+    Ok(())
+}
+
+/// Adds `op` with the first 32 bits of the message body and, for bodies with zero op
+/// followed by UTF-8 text (possibly continued in the chain of first references),
+/// `comment` with the decoded text. Nothing is added if the body is shorter than 32 bits;
+/// `comment` is skipped if the text is not byte aligned or not valid UTF-8.
+pub fn serialize_message_comment(doc: &mut Map<String, Value>, message: &Message) -> Result<()> {
+    let Some(mut body) = message.body() else {
+        return Ok(())
+    };
+    if body.remaining_bits() < 32 {
+        return Ok(())
+    }
+    let op = body.get_next_u32()?;
+    serialize_field(doc, "op", op);
+    if op != 0 {
+        return Ok(())
+    }
+    let mut bytes = Vec::new();
+    loop {
+        if body.remaining_bits() % 8 != 0 {
+            return Ok(())
+        }
+        bytes.extend_from_slice(&body.get_next_bytes(body.remaining_bits() / 8)?);
+        if body.remaining_references() == 0 {
+            break
+        }
+        body = SliceData::load_cell(body.reference(0)?)?;
+    }
+    if let Ok(comment) = String::from_utf8(bytes) {
+        serialize_field(doc, "comment", comment);
+    }
+    Ok(())
+}
+
+/// Adds `bounce_msg_id` with the hash of the bounced message produced by the successful
+/// bounce phase of the transaction, so bounce flows can be traced without scanning
+/// all the out messages.
+pub fn serialize_bounce_msg_id(
+    doc: &mut Map<String, Value>,
+    transaction: &Transaction,
+    mode: impl Into<SerializationOptions>
+) -> Result<()> {
+    let TransactionDescr::Ordinary(descr) = transaction.read_description()? else {
+        return Ok(())
+    };
+    let Some(TrBouncePhase::Ok(_)) = descr.bounce else {
+        return Ok(())
+    };
+    let mut bounce_msg_id = None;
+    let opts = transaction.out_msgs.serde_opts();
+    transaction.out_msgs.iterate_slices(|slice| {
+        if let Some(cell) = slice.reference_opt(0) {
+            let msg = CommonMessage::construct_from_cell_with_opts(cell.clone(), opts)?;
+            if let Ok(msg) = msg.get_std() {
+                if let CommonMsgInfo::IntMsgInfo(header) = msg.header() {
+                    if header.bounced {
+                        bounce_msg_id = Some(cell.repr_hash());
+                    }
+                }
+            }
+        }
+        Ok(true)
+    })?;
+    serialize_id(doc, "bounce_msg_id", bounce_msg_id.as_ref(), mode.into());
+    Ok(())
+}
+
+/// Adds top-level flags of the transaction phases for cheap filtering: `skipped_compute`
+/// if the compute phase was skipped, `aborted_compute` if the VM ran without success and
+/// `failed_action` if the action phase was unsuccessful. Transactions without the compute
+/// phase get no flags.
+pub fn serialize_phase_flags(doc: &mut Map<String, Value>, transaction: &Transaction) -> Result<()> {
+    let (compute_ph, action) = match transaction.read_description()? {
+        TransactionDescr::Ordinary(tr) => (tr.compute_ph, tr.action),
+        TransactionDescr::TickTock(tr) => (tr.compute_ph, tr.action),
+        TransactionDescr::SplitPrepare(tr) => (tr.compute_ph, tr.action),
+        TransactionDescr::MergeInstall(tr) => (tr.compute_ph, tr.action),
+        _ => return Ok(())
+    };
+    let (skipped, aborted) = match &compute_ph {
+        TrComputePhase::Skipped(_) => (true, false),
+        TrComputePhase::Vm(ph) => (false, !ph.success),
+    };
+    serialize_field(doc, "skipped_compute", skipped);
+    serialize_field(doc, "aborted_compute", aborted);
+    serialize_field(doc, "failed_action", action.map_or(false, |ph| !ph.success));
+    Ok(())
+}
+
+/// Adds approximate routing of the message delivered by the in message descriptor:
+/// `ihr` is set if the message came by instant hypercube routing, `hop_count` is the number
+/// of hypercube hops between `cur_addr` and `next_addr` of the envelope, one hop per
+/// hex digit of the destination prefix taken by the route (0 if the message stays in the shard).
+/// Envelopes with explicit intermediate prefixes don't give the hop count.
+pub fn serialize_msg_routing(doc: &mut Map<String, Value>, in_msg: &InMsg) -> Result<()> {
+    let env = match in_msg {
+        InMsg::IHR(_) => {
+            serialize_field(doc, "ihr", true);
+            return Ok(())
+        }
+        InMsg::Immediate(msg) => msg.read_envelope_message()?,
+        InMsg::Final(msg) => msg.read_envelope_message()?,
+        InMsg::Transit(msg) => msg.read_in_message()?,
+        InMsg::DiscardedFinal(msg) => msg.read_envelope_message()?,
+        InMsg::DiscardedTransit(msg) => msg.read_envelope_message()?,
+        _ => return Ok(())
+    };
+    serialize_field(doc, "ihr", false);
+    if let (IntermediateAddress::Regular(cur), IntermediateAddress::Regular(next)) = (env.cur_addr(), env.next_addr()) {
+        serialize_field(doc, "hop_count", hypercube_hops(cur.use_dest_bits(), next.use_dest_bits()));
+    }
+    Ok(())
+}
+
+/// Number of hex digits of the destination prefix touched by the route from
+/// `cur_dest_bits` to `next_dest_bits` taken bits
+fn hypercube_hops(cur_dest_bits: u8, next_dest_bits: u8) -> u32 {
+    if next_dest_bits <= cur_dest_bits {
+        return 0
+    }
+    (next_dest_bits as u32 + 3) / 4 - cur_dest_bits as u32 / 4
+}
+
+/// Adds `master_anchor` section with the masterchain block which commits the shard block:
+/// `mc_block_seqno` and, if the id is known, `mc_block_id` and `mc_block_file_hash`.
+/// Fails if the anchor is older than `master_ref` of the block or the id doesn't match
+/// the seq_no. Masterchain blocks are left as is.
+pub fn serialize_master_anchor(
+    doc: &mut Map<String, Value>,
+    block: &Block,
+    mc_seq_no: Option<u32>,
+    mc_block_id: Option<&BlockIdExt>,
+    mode: impl Into<SerializationOptions>
+) -> Result<()> {
+    let info = block.read_info()?;
+    if info.shard().is_masterchain() {
+        return Ok(())
+    }
+    let mc_seq_no = match (mc_block_id, mc_seq_no) {
+        (Some(id), Some(seq_no)) if id.seq_no() != seq_no => {
+            fail!("masterchain block id {} doesn't match masterchain seq_no {}", id, seq_no)
+        }
+        (Some(id), _) => id.seq_no(),
+        (None, Some(seq_no)) => seq_no,
+        (None, None) => return Ok(())
+    };
+    if let Some(master_ref) = info.read_master_ref()? {
+        if mc_seq_no < master_ref.master.seq_no {
+            fail!(
+                "masterchain block {} can't commit the shard block referring to masterchain block {}",
+                mc_seq_no, master_ref.master.seq_no
+            )
+        }
+    }
+    let mut anchor = Map::new();
+    match mc_block_id {
+        Some(id) => serialize_block_id_ext(&mut anchor, id, true, mode.into()),
+        None => serialize_field(&mut anchor, "mc_block_seqno", mc_seq_no),
+    }
+    serialize_field(doc, "master_anchor", anchor);
+    Ok(())
+}
+
 #[derive(Default)]
 pub struct TransactionSerializationSet {
     pub transaction: Transaction,
@@ -1634,6 +3273,18 @@ pub fn debug_transaction(transaction: Transaction) -> Result<String> {
     Ok(format!("{:#}", serde_json::json!(map)))
 }
 
+/// Serializes the transaction in `Standart` mode.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_transaction_set()?;
+/// let doc = db_serialize_transaction("id", &set)?;
+/// assert_eq!(doc["lt_dec"], "2000001");
+/// assert_eq!(parse_transaction(&doc)?, set.transaction);
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 pub fn db_serialize_transaction<'a>(
     id_str: &'static str,
     set: impl Into<TransactionSerializationSetEx<'a>>
@@ -1641,32 +3292,187 @@ pub fn db_serialize_transaction<'a>(
     db_serialize_transaction_ex(id_str, set, SerializationMode::Standart)
 }
 
+/// Serializes transaction like `db_serialize_transaction_ex` and adds to the compute phase
+/// fields derived from gas prices of the config (p20 or p21 depending on workchain):
+/// `gas_price_used` - price of gas unit in nanotokens, `flat_fee_applied` - whether gas usage
+/// fits into flat gas limit so only flat gas price is charged, and `gas_fees_dec`.
+pub fn db_serialize_transaction_with_config<'a>(
+    id_str: &'static str,
+    set: impl Into<TransactionSerializationSetEx<'a>>,
+    config: &ConfigParams,
+    mode: impl Into<SerializationOptions>
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let set: TransactionSerializationSetEx = set.into();
+    let transaction = set.transaction;
+    let is_masterchain = set.workchain_id == Some(MASTERCHAIN_ID);
+    let mut map = db_serialize_transaction_ex(id_str, set, mode)?;
+    let compute_ph = match transaction.read_description()? {
+        TransactionDescr::Ordinary(tr) => tr.compute_ph,
+        TransactionDescr::TickTock(tr) => tr.compute_ph,
+        TransactionDescr::SplitPrepare(tr) => tr.compute_ph,
+        TransactionDescr::MergeInstall(tr) => tr.compute_ph,
+        _ => return Ok(map)
+    };
+    if let (TrComputePhase::Vm(ph), Some(Value::Object(compute))) = (compute_ph, map.get_mut("compute")) {
+        let gas_prices = config.gas_prices(is_masterchain)?;
+        serialize_field(compute, "gas_fees_dec", ph.gas_fees.as_u128().to_string());
+        serialize_u64(compute, "gas_price_used", &(gas_prices.gas_price >> 16), mode);
+        serialize_field(compute, "flat_fee_applied", ph.gas_used.as_u64() <= gas_prices.flat_gas_limit);
+    }
+    Ok(map)
+}
+
+/// Serializes transaction like `db_serialize_transaction_ex` and embeds full documents
+/// of its messages: `in_message` and `out_messages` in the order of `out_msgs`,
+/// so the single document contains everything needed to display the transaction.
+/// Messages which are not standard are given by `id` and `boc` only.
+pub fn db_serialize_transaction_deep<'a>(
+    id_str: &'static str,
+    set: impl Into<TransactionSerializationSetEx<'a>>,
+    mode: impl Into<SerializationOptions>
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let set: TransactionSerializationSetEx = set.into();
+    let (transaction, block_id) = (set.transaction, set.block_id.cloned());
+    let mut map = db_serialize_transaction_ex(id_str, set, mode)?;
+    if let Some(cell) = transaction.in_msg_cell() {
+        let location = MessageLocation::Inbound(transaction);
+        let msg_set = MessageSerializationSet::with_location(cell, location, block_id.clone())?;
+        map.insert("in_message".to_string(), db_serialize_message_ex("id", &msg_set, mode)?.into());
+    }
+    let mut out_messages = Vec::new();
+    let opts = transaction.out_msgs.serde_opts();
+    transaction.out_msgs.iterate_slices(|slice| {
+        if let Some(cell) = slice.reference_opt(0) {
+            let msg = CommonMessage::construct_from_cell_with_opts(cell.clone(), opts)?;
+            if msg.get_std().is_ok() {
+                let location = MessageLocation::Outbound(transaction);
+                let msg_set = MessageSerializationSet::with_location(cell, location, block_id.clone())?;
+                out_messages.push(Value::from(db_serialize_message_ex("id", &msg_set, mode)?));
+            } else {
+                let mut msg_map = Map::new();
+                serialize_id(&mut msg_map, "id", Some(&cell.repr_hash()), mode);
+                serialize_cell(&mut msg_map, "boc", Some(&cell), false, mode)?;
+                out_messages.push(Value::from(msg_map));
+            }
+        }
+        Ok(true)
+    })?;
+    serialize_field(&mut map, "out_messages", out_messages);
+    Ok(map)
+}
+
+/// `status` and `status_name` of the processing status
+fn serialize_status(map: &mut Map<String, Value>, status: u8, status_name: &str, mode: SerializationOptions) {
+    serialize_field(map, "status", status);
+    if mode.is_q_server() {
+        serialize_field(map, "status_name", status_name);
+    }
+}
+
+/// `tr_type` and `tr_type_name` of the transaction description
+fn serialize_transaction_type(map: &mut Map<String, Value>, descr: &TransactionDescr, mode: SerializationOptions) {
+    let (tr_type, tr_type_name, _) = transaction_type(descr);
+    serialize_field(map, "tr_type", tr_type);
+    if mode.is_q_server() {
+        serialize_field(map, "tr_type_name", tr_type_name);
+    }
+}
+
+/// `account_addr` and `workchain_id` of the transaction account if the workchain is known
+/// from the block or the address is taken from the messages, `account_id` otherwise
+fn serialize_transaction_account(
+    map: &mut Map<String, Value>,
+    transaction: &Transaction,
+    workchain_id: Option<i32>,
+    address_from_message: Option<MsgAddressInt>,
+    mode: SerializationOptions
+) -> Result<()> {
+    if let Some(workchain_id) = workchain_id {
+        let account_addr = construct_address(workchain_id, transaction.account_id().clone())?;
+        serialize_field(map, "account_addr", account_addr.to_string());
+        serialize_field(map, "workchain_id", workchain_id);
+    } else if let Some(address) = address_from_message {
+        serialize_field(map, "account_addr", address.to_string());
+        serialize_field(map, "workchain_id", address.get_workchain_id());
+        serialize_anycast(map, "account_addr", &address, mode);
+    } else {
+        serialize_field(map, "account_id", mode.hash_format.format_hex(&transaction.account_id().as_hex_string()));
+    }
+    Ok(())
+}
+
+/// Account address of the transaction taken from the destination of the inbound message
+/// or the source of the first outbound one
+fn transaction_address_from_messages(transaction: &Transaction) -> Result<Option<MsgAddressInt>> {
+    if !transaction.in_msg.empty() {
+        if let Ok(msg) = transaction.in_msg.read_struct()?.get_std() {
+            if let Some(address) = msg.dst_ref() {
+                return Ok(Some(address.clone()))
+            }
+        }
+    }
+    let mut address = None;
+    let opts = transaction.out_msgs.serde_opts();
+    transaction.out_msgs.iterate_slices(|slice| {
+        if let Some(cell) = slice.reference_opt(0) {
+            let msg = CommonMessage::construct_from_cell_with_opts(cell, opts)?;
+            if let Ok(msg) = msg.get_std() {
+                address = msg.src_ref().cloned();
+            }
+        }
+        Ok(address.is_none())
+    })?;
+    Ok(address)
+}
+
+/// Returns `tr_type`, `tr_type_name` and `aborted` flag of the transaction description
+fn transaction_type(descr: &TransactionDescr) -> (u8, &'static str, Option<bool>) {
+    match descr {
+        TransactionDescr::Ordinary(tr) => (0b0000, "ordinary", Some(tr.aborted)),
+        TransactionDescr::Storage(_) => (0b0001, "storage", None),
+        TransactionDescr::TickTock(tr) => match &tr.tt {
+            TransactionTickTock::Tick => (0b0010, "tick", Some(tr.aborted)),
+            TransactionTickTock::Tock => (0b0011, "tock", Some(tr.aborted)),
+        },
+        TransactionDescr::SplitPrepare(tr) => (0b0100, "splitPrepare", Some(tr.aborted)),
+        TransactionDescr::SplitInstall(_) => (0b0101, "splitInstall", None),
+        TransactionDescr::MergePrepare(tr) => (0b0110, "mergePrepare", Some(tr.aborted)),
+        TransactionDescr::MergeInstall(tr) => (0b0111, "mergeInstall", Some(tr.aborted)),
+    }
+}
+
+/// Serializes the transaction with its description, phases and ids of the messages.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_transaction_set()?;
+/// let doc = db_serialize_transaction_ex("id", &set, SerializationMode::QServer)?;
+/// assert_eq!(doc["lt"], "0x1e8481");
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 pub fn db_serialize_transaction_ex<'a>(
     id_str: &'static str,
     set: impl Into<TransactionSerializationSetEx<'a>>,
-    mode: SerializationMode
+    mode: impl Into<SerializationOptions>
 ) -> Result<Map<String, Value>> {
+    let mode = mode.into();
     let set: TransactionSerializationSetEx = set.into();
     let mut map = Map::new();
     serialize_field(&mut map, "json_version", VERSION);
-    serialize_id(&mut map, id_str, Some(set.id));
-    serialize_id(&mut map, "block_id", set.block_id);
+    serialize_id(&mut map, id_str, Some(set.id), mode);
+    serialize_id(&mut map, "block_id", set.block_id, mode);
     if let Some(proof) = &set.proof {
         serialize_field(&mut map, "proof", base64_encode(proof));
     }
     serialize_field(&mut map, "boc", base64_encode(set.boc));
-    serialize_field(&mut map, "status", set.status as u8);
-    if mode.is_q_server() {
-        serialize_field(&mut map, "status_name", match set.status {
-            TransactionProcessingStatus::Unknown => "unknown",
-            TransactionProcessingStatus::Preliminary => "preliminary",
-            TransactionProcessingStatus::Proposed => "proposed",
-            TransactionProcessingStatus::Finalized => "finalized",
-            TransactionProcessingStatus::Refused => "refused",
-        });
-    }
+    serialize_status(&mut map, set.status as u8, TransactionProcessingStatusJson::from(set.status).name(), mode);
     let mut ext_in_msg_fee = None;
-    let (tr_type, tr_type_name) = match &set.transaction.read_description()? {
+    let description = set.transaction.read_description()?;
+    match &description {
         TransactionDescr::Ordinary(tr) => {
             let mut fees = set.transaction.total_fees().grams;
             if let Some(fee) = serialize_storage_phase(&mut map, tr.storage_ph.as_ref(), mode) {
@@ -1684,11 +3490,9 @@ pub fn db_serialize_transaction_ex<'a>(
             serialize_field(&mut map, "credit_first", tr.credit_first);
             serialize_field(&mut map, "aborted", tr.aborted);
             serialize_field(&mut map, "destroyed", tr.destroyed);
-            (0b0000, "ordinary")
         }
         TransactionDescr::Storage(tr) => {
             serialize_storage_phase(&mut map, Some(tr), mode);
-            (0b0001, "storage")
         }
         TransactionDescr::TickTock(tr) => {
             serialize_storage_phase(&mut map, Some(&tr.storage), mode);
@@ -1696,48 +3500,37 @@ pub fn db_serialize_transaction_ex<'a>(
             serialize_action_phase(&mut map, tr.action.as_ref(), mode);
             serialize_field(&mut map, "aborted", tr.aborted);
             serialize_field(&mut map, "destroyed", tr.destroyed);
-            match &tr.tt {
-                TransactionTickTock::Tick => (0b0010, "tick"),
-                TransactionTickTock::Tock => (0b0011, "tock"),
-            }
         }
         TransactionDescr::SplitPrepare(tr) => {
-            serialize_split_info(&mut map, &tr.split_info);
+            serialize_split_info(&mut map, &tr.split_info, mode);
             serialize_compute_phase(&mut map, Some(&tr.compute_ph), mode);
             serialize_action_phase(&mut map, tr.action.as_ref(), mode);
             serialize_field(&mut map, "aborted", tr.aborted);
             serialize_field(&mut map, "destroyed", tr.destroyed);
-            (0b0100, "splitPrepare")
         }
         TransactionDescr::SplitInstall(tr) => {
-            serialize_split_info(&mut map, &tr.split_info);
-            serialize_id(&mut map, "prepare_transaction", tr.prepare_transaction.hash().ok().as_ref());
+            serialize_split_info(&mut map, &tr.split_info, mode);
+            serialize_id(&mut map, "prepare_transaction", tr.prepare_transaction.hash().ok().as_ref(), mode);
             serialize_field(&mut map, "installed", tr.installed);
-            (0b0101, "splitInstall")
         }
         TransactionDescr::MergePrepare(tr) => {
-            serialize_split_info(&mut map, &tr.split_info);
+            serialize_split_info(&mut map, &tr.split_info, mode);
             serialize_storage_phase(&mut map, Some(&tr.storage_ph), mode);
             serialize_field(&mut map, "aborted", tr.aborted);
-            (0b0110, "mergePrepare")
         }
         TransactionDescr::MergeInstall(tr) => {
-            serialize_split_info(&mut map, &tr.split_info);
-            serialize_id(&mut map, "prepare_transaction", tr.prepare_transaction.hash().ok().as_ref());
+            serialize_split_info(&mut map, &tr.split_info, mode);
+            serialize_id(&mut map, "prepare_transaction", tr.prepare_transaction.hash().ok().as_ref(), mode);
             serialize_credit_phase(&mut map, tr.credit_ph.as_ref(), mode)?;
             serialize_compute_phase(&mut map, Some(&tr.compute_ph), mode);
             serialize_action_phase(&mut map, tr.action.as_ref(), mode);
             serialize_field(&mut map, "aborted", tr.aborted);
             serialize_field(&mut map, "destroyed", tr.destroyed);
-            (0b0111, "mergeInstall")
         }
-    };
-    serialize_field(&mut map, "tr_type", tr_type);
-    if mode.is_q_server() {
-        serialize_field(&mut map, "tr_type_name", tr_type_name);
     }
+    serialize_transaction_type(&mut map, &description, mode);
     serialize_lt(&mut map, "lt", &set.transaction.logical_time(), mode);
-    serialize_id(&mut map, "prev_trans_hash", Some(set.transaction.prev_trans_hash()));
+    serialize_id(&mut map, "prev_trans_hash", Some(set.transaction.prev_trans_hash()), mode);
     serialize_lt(&mut map, "prev_trans_lt", &set.transaction.prev_trans_lt(), mode);
     serialize_field(&mut map, "now", set.transaction.now());
     serialize_field(&mut map, "outmsg_cnt", set.transaction.msg_count());
@@ -1747,7 +3540,7 @@ pub fn db_serialize_transaction_ex<'a>(
     let mut address_from_message = None;
     if !set.transaction.in_msg.empty() {
         let msg = &set.transaction.in_msg;
-        serialize_id(&mut map, "in_msg", Some(&msg.hash()));
+        serialize_id(&mut map, "in_msg", Some(&msg.hash()), mode);
 
         let msg = msg.read_struct()?;
         if let Ok(msg) = msg.get_std() {
@@ -1770,7 +3563,7 @@ pub fn db_serialize_transaction_ex<'a>(
     let opts = set.transaction.out_msgs.serde_opts();
     set.transaction.out_msgs.iterate_slices(|slice| {
         if let Some(cell) = slice.reference_opt(0) {
-            out_ids.push(cell.repr_hash().as_hex_string());
+            out_ids.push(format_uint256(&cell.repr_hash(), mode));
             let msg = CommonMessage::construct_from_cell_with_opts(cell, opts)?;
             if let Ok(msg) = msg.get_std() {
                 if let Some(value) = msg.get_value() {
@@ -1788,22 +3581,59 @@ pub fn db_serialize_transaction_ex<'a>(
         Ok(true)
     })?;
     serialize_field(&mut map, "out_msgs", out_ids);
-    if let Some(workchain_id) = set.workchain_id {
-        let account_addr = construct_address(workchain_id, set.transaction.account_id().clone())?;
-        serialize_field(&mut map, "account_addr", account_addr.to_string());
-        serialize_field(&mut map, "workchain_id", workchain_id);
-    } else if let Some(address) = address_from_message {
-        serialize_field(&mut map, "account_addr", address.to_string());
-        serialize_field(&mut map, "workchain_id", address.get_workchain_id());
-    } else {
-        serialize_field(&mut map, "account_id", set.transaction.account_id().as_hex_string());
-    }
+    serialize_transaction_account(&mut map, set.transaction, set.workchain_id, address_from_message, mode)?;
     serialize_cc(&mut map, "total_fees", set.transaction.total_fees(), mode)?;
     balance_delta.sub(&SignedCurrencyCollection::from_cc(set.transaction.total_fees())?);
     serialize_scc(&mut map, "balance_delta", &balance_delta, mode);
     let state_update = set.transaction.read_state_update()?;
-    serialize_id(&mut map, "old_hash", Some(&state_update.old_hash));
-    serialize_id(&mut map, "new_hash", Some(&state_update.new_hash));
+    serialize_id(&mut map, "old_hash", Some(&state_update.old_hash), mode);
+    serialize_id(&mut map, "new_hash", Some(&state_update.new_hash), mode);
+    Ok(map)
+}
+
+/// Summary of the transaction for list views: `id`, `block_id`, `status`, `account_addr`, `lt`,
+/// `now`, `total_fees`, `aborted`, `tr_type`, `in_msg` and `outmsg_cnt` in the same format as in
+/// the full transaction document. Phases, boc and proof are skipped.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_transaction_set()?;
+/// let doc = db_serialize_transaction_brief("id", &set, SerializationMode::QServer)?;
+/// assert_eq!(doc["tr_type_name"], "ordinary");
+/// assert!(doc.get("boc").is_none());
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_transaction_brief<'a>(
+    id_str: &'static str,
+    set: impl Into<TransactionSerializationSetEx<'a>>,
+    mode: impl Into<SerializationOptions>
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let set: TransactionSerializationSetEx = set.into();
+    let mut map = Map::new();
+    serialize_field(&mut map, "json_version", VERSION);
+    serialize_id(&mut map, id_str, Some(set.id), mode);
+    serialize_id(&mut map, "block_id", set.block_id, mode);
+    serialize_status(&mut map, set.status as u8, TransactionProcessingStatusJson::from(set.status).name(), mode);
+    let description = set.transaction.read_description()?;
+    if let (_, _, Some(aborted)) = transaction_type(&description) {
+        serialize_field(&mut map, "aborted", aborted);
+    }
+    serialize_transaction_type(&mut map, &description, mode);
+    serialize_lt(&mut map, "lt", &set.transaction.logical_time(), mode);
+    serialize_field(&mut map, "now", set.transaction.now());
+    serialize_field(&mut map, "outmsg_cnt", set.transaction.msg_count());
+    if !set.transaction.in_msg.empty() {
+        serialize_id(&mut map, "in_msg", Some(&set.transaction.in_msg.hash()), mode);
+    }
+    let address_from_message = match set.workchain_id {
+        Some(_) => None,
+        None => transaction_address_from_messages(set.transaction)?,
+    };
+    serialize_transaction_account(&mut map, set.transaction, set.workchain_id, address_from_message, mode)?;
+    serialize_cc(&mut map, "total_fees", set.transaction.total_fees(), mode)?;
     Ok(map)
 }
 
@@ -1811,7 +3641,7 @@ fn serialize_account_status(
     map: &mut Map<String, Value>,
     name: &'static str,
     status: &AccountStatus,
-    mode: SerializationMode
+    mode: SerializationOptions
 ) {
     serialize_field(map, name, match status {
         AccountStatus::AccStateUninit   => 0b00,
@@ -1831,6 +3661,39 @@ fn serialize_account_status(
     }
 }
 
+fn serialize_state_init(map: &mut Map<String, Value>, state: &StateInit, mode: SerializationOptions) -> Result<()> {
+    if let Some(split_depth) = state.split_depth() {
+        serialize_field(map, "split_depth", split_depth.as_u32());
+    }
+    if let Some(special) = state.special() {
+        serialize_field(map, "tick", special.tick);
+        serialize_field(map, "tock", special.tock);
+    };
+    serialize_cell(map, "code", state.code(), true, mode)?;
+    serialize_cell(map, "data", state.data(), true, mode)?;
+    serialize_cell(map, "library", state.libraries().root(), true, mode)?;
+    Ok(())
+}
+
+/// Serializes the state init like it is embedded into account and message documents:
+/// `split_depth`, `tick`, `tock`, `code`, `data` and `library` with their hashes.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let account = samples::sample_account();
+/// let doc = db_serialize_state_init(account.state_init().unwrap(), SerializationMode::Standart)?;
+/// assert!(doc["code_hash"].is_string());
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_state_init(state: &StateInit, mode: impl Into<SerializationOptions>) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let mut map = Map::new();
+    serialize_state_init(&mut map, state, mode)?;
+    Ok(map)
+}
+
 #[derive(Default)]
 pub struct AccountSerializationSet {
     pub account: Account,
@@ -1852,6 +3715,17 @@ pub fn debug_account(account: Account) -> Result<String> {
     Ok(format!("{:#}", serde_json::json!(map)))
 }
 
+/// Serializes the account in `Standart` mode.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_account_set()?;
+/// let doc = db_serialize_account("id", &set)?;
+/// assert_eq!(doc["id"], set.account.get_addr().unwrap().to_string());
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 pub fn db_serialize_account(
     id_str: &'static str,
     set: &AccountSerializationSet
@@ -1859,22 +3733,35 @@ pub fn db_serialize_account(
     db_serialize_account_ex(id_str, set, SerializationMode::Standart)
 }
 
+/// Serializes the account with its storage, balance and state.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_account_set()?;
+/// let doc = db_serialize_account_ex("id", &set, SerializationMode::QServer)?;
+/// assert!(doc["balance"].as_str().unwrap().starts_with("0x"));
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 pub fn db_serialize_account_ex(
     id_str: &'static str,
     set: &AccountSerializationSet,
-    mode: SerializationMode
+    mode: impl Into<SerializationOptions>
 ) -> Result<Map<String, Value>> {
+    let mode = mode.into();
     let mut map = Map::new();
     serialize_field(&mut map, "json_version", VERSION);
     if let Some(addr) = set.account.get_addr() {
         serialize_field(&mut map, id_str, addr.to_string());
         serialize_field(&mut map, "workchain_id", addr.get_workchain_id());
+        serialize_anycast(&mut map, id_str, addr, mode);
     }
     serialize_field(&mut map, "boc", base64_encode(&set.boc));
     if let Some(boc1) = set.boc1.as_ref() {
         serialize_field(&mut map, "boc1", base64_encode(boc1));
     }
-    serialize_id(&mut map, "init_code_hash", set.account.init_code_hash());
+    serialize_id(&mut map, "init_code_hash", set.account.init_code_hash(), mode);
     if let Some(storage_stat) = set.account.storage_info() {
         serialize_field(&mut map, "last_paid", storage_stat.last_paid());
         serialize_u64(&mut map, "bits", &storage_stat.used().bits(), mode);
@@ -1889,20 +3776,11 @@ pub fn db_serialize_account_ex(
     match set.account.status() {
         AccountStatus::AccStateActive => {
             if let Some(state) = set.account.state_init() {
-                if let Some(split_depth) = state.split_depth() {
-                    serialize_field(&mut map, "split_depth", split_depth.as_u32());
-                }
-                if let Some(special) = state.special() {
-                    serialize_field(&mut map, "tick", special.tick);
-                    serialize_field(&mut map, "tock", special.tock);
-                };
-                serialize_cell(&mut map, "code", state.code(), true)?;
-                serialize_cell(&mut map, "data", state.data(), true)?;
-                serialize_cell(&mut map, "library", state.libraries().root(), true)?;
+                serialize_state_init(&mut map, state, mode)?;
             }
         }
         AccountStatus::AccStateFrozen => {
-            serialize_id(&mut map, "state_hash", set.account.frozen_hash())
+            serialize_id(&mut map, "state_hash", set.account.frozen_hash(), mode)
         }
         AccountStatus::AccStateUninit => {
 
@@ -1913,7 +3791,59 @@ pub fn db_serialize_account_ex(
         serialize_field(&mut map, "proof", base64_encode(proof));
     }
     serialize_account_status(&mut map, "acc_type", &set.account.status(), mode);
-    serialize_id(&mut map, "prev_code_hash", (&set.prev_code_hash).as_ref());
+    serialize_id(&mut map, "prev_code_hash", (&set.prev_code_hash).as_ref(), mode);
+    Ok(map)
+}
+
+/// Logical time range of a block with its generation time, used to convert logical time
+/// of the block's events to wall-clock time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LtTimeAnchor {
+    pub start_lt: u64,
+    pub end_lt: u64,
+    pub utime: u32,
+}
+
+impl LtTimeAnchor {
+    pub fn from_block_info(block_info: &BlockInfo) -> Self {
+        Self {
+            start_lt: block_info.start_lt(),
+            end_lt: block_info.end_lt(),
+            utime: block_info.gen_utime().as_u32(),
+        }
+    }
+
+    /// Returns time of the block if logical time belongs to it
+    pub fn utime_of(&self, lt: u64) -> Option<u32> {
+        (self.start_lt..=self.end_lt).contains(&lt).then_some(self.utime)
+    }
+}
+
+/// Serializes account like `db_serialize_account_ex` and adds `last_active_at` if its
+/// `last_trans_lt` belongs to the anchor block.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_account_set()?;
+/// let lt = set.account.last_tr_time().unwrap();
+/// let anchor = LtTimeAnchor { start_lt: lt, end_lt: lt + 1, utime: 1_600_000_000 };
+/// let doc = db_serialize_account_with_time_anchor("id", &set, &anchor, SerializationMode::Standart)?;
+/// assert_eq!(doc["last_active_at"], 1_600_000_000);
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_account_with_time_anchor(
+    id_str: &'static str,
+    set: &AccountSerializationSet,
+    anchor: &LtTimeAnchor,
+    mode: impl Into<SerializationOptions>
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let mut map = db_serialize_account_ex(id_str, set, mode)?;
+    if let Some(utime) = set.account.last_tr_time().and_then(|lt| anchor.utime_of(lt)) {
+        serialize_field(&mut map, "last_active_at", utime);
+    }
     Ok(map)
 }
 
@@ -1924,22 +3854,54 @@ pub struct DeletedAccountSerializationSet {
     pub workchain_id: i32
 }
 
+/// Serializes the deleted account in `Standart` mode.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = DeletedAccountSerializationSet {
+///     account_id: samples::SAMPLE_ACCOUNT_ID.into(),
+///     workchain_id: 0,
+///     ..Default::default()
+/// };
+/// let doc = db_serialize_deleted_account("id", &set)?;
+/// assert_eq!(doc["id"], format!("0:{}", "11".repeat(32)));
+/// assert_eq!(doc["acc_type"], 3);
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 pub fn db_serialize_deleted_account(
     id_str: &'static str, set: &DeletedAccountSerializationSet
 ) -> Result<Map<String, Value>> {
     db_serialize_deleted_account_ex(id_str, set, SerializationMode::Standart)
 }
 
+/// Serializes the account removed from the state, `acc_type` is always `NonExist`.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = DeletedAccountSerializationSet {
+///     account_id: samples::SAMPLE_ACCOUNT_ID.into(),
+///     workchain_id: -1,
+///     ..Default::default()
+/// };
+/// let doc = db_serialize_deleted_account_ex("id", &set, SerializationMode::QServer)?;
+/// assert_eq!(doc["workchain_id"], -1);
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 pub fn db_serialize_deleted_account_ex(
-    id_str: &'static str, set: &DeletedAccountSerializationSet, mode: SerializationMode
+    id_str: &'static str, set: &DeletedAccountSerializationSet, mode: impl Into<SerializationOptions>
 ) -> Result<Map<String, Value>> {
+    let mode = mode.into();
     let mut map = Map::new();
     serialize_field(&mut map, "json_version", VERSION);
     let address = construct_address(set.workchain_id, set.account_id.clone())?;
     serialize_field(&mut map, id_str, address.to_string());
     serialize_field(&mut map, "workchain_id", set.workchain_id);
     serialize_account_status(&mut map, "acc_type", &AccountStatus::AccStateNonexist, mode);
-    serialize_id(&mut map, "prev_code_hash", (&set.prev_code_hash).as_ref());
+    serialize_id(&mut map, "prev_code_hash", (&set.prev_code_hash).as_ref(), mode);
 
     Ok(map)
 }
@@ -1956,6 +3918,61 @@ pub struct MessageSerializationSet {
     pub proof: Option<Vec<u8>>,
 }
 
+/// Place of the message in the block used to fill the context of `MessageSerializationSet`
+pub enum MessageLocation<'a> {
+    /// Inbound message of the transaction
+    Inbound(&'a Transaction),
+    /// Outbound message of the transaction
+    Outbound(&'a Transaction),
+    /// Descriptor of the message in `in_msg_descr`
+    InMsgDescr(&'a InMsg),
+    /// Descriptor of the message in `out_msg_descr`
+    OutMsgDescr(&'a OutMsg),
+}
+
+impl MessageSerializationSet {
+    /// Fills the set for the message of the block: `block_id`, `transaction_id` of the
+    /// transaction processing or producing the message and `transaction_now` which is set
+    /// only for inbound external messages. Status of messages of the block is `Finalized`.
+    pub fn from_block(block: &Block, message_cell: Cell, location: MessageLocation) -> Result<Self> {
+        Self::with_location(message_cell, location, Some(block.hash()?))
+    }
+
+    fn with_location(message_cell: Cell, location: MessageLocation, block_id: Option<BlockId>) -> Result<Self> {
+        let message = Message::construct_from_cell(message_cell.clone())?;
+        let (transaction_id, transaction) = match location {
+            MessageLocation::Inbound(transaction) => {
+                (Some(transaction.hash()?), Some(transaction.clone()))
+            }
+            MessageLocation::Outbound(transaction) => (Some(transaction.hash()?), None),
+            MessageLocation::InMsgDescr(in_msg) => {
+                let transaction = match message.is_inbound_external() {
+                    true => in_msg.read_transaction()?,
+                    false => None
+                };
+                (in_msg.transaction_cell().map(|cell| cell.repr_hash()), transaction)
+            }
+            MessageLocation::OutMsgDescr(out_msg) => {
+                (out_msg.transaction_cell().map(|cell| cell.repr_hash()), None)
+            }
+        };
+        let transaction_now = match message.is_inbound_external() {
+            true => transaction.map(|transaction| transaction.now()),
+            false => None
+        };
+        Ok(Self {
+            id: message_cell.repr_hash(),
+            boc: write_boc(&message_cell)?,
+            message,
+            block_id,
+            transaction_id,
+            transaction_now,
+            status: MessageProcessingStatus::Finalized,
+            proof: None,
+        })
+    }
+}
+
 pub fn debug_message(message: Message) -> Result<String> {
     let root_cell = message.serialize()?;
     let set = MessageSerializationSet {
@@ -1972,96 +3989,148 @@ pub fn debug_message(message: Message) -> Result<String> {
     Ok(format!("{:#}", serde_json::json!(map)))
 }
 
+/// Serializes the message in `Standart` mode.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_message_set()?;
+/// let doc = db_serialize_message("id", &set)?;
+/// assert_eq!(doc["value_dec"], "1000000000");
+/// assert_eq!(parse_message(&doc)?, set.message);
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 pub fn db_serialize_message(id_str: &'static str, set: &MessageSerializationSet) -> Result<Map<String, Value>> {
     db_serialize_message_ex(id_str, set, SerializationMode::Standart)
 }
 
-pub fn db_serialize_message_ex(id_str: &'static str, set: &MessageSerializationSet, mode: SerializationMode) -> Result<Map<String, Value>> {
-    let mut map = Map::new();
-    serialize_field(&mut map, "json_version", VERSION);
-    serialize_id(&mut map, id_str, Some(&set.id));
-    // isn't needed there - because message should be fully immutable from source block to destination one
-    //serialize_id(&mut map, "block_id", set.block_id.as_ref());
-    serialize_id(&mut map, "transaction_id", set.transaction_id.as_ref());
-    if let Some(proof) = &set.proof {
-        serialize_field(&mut map, "proof", base64_encode(proof));
-    }
-    serialize_field(&mut map, "boc", base64_encode(&set.boc));
-    serialize_field(&mut map, "status", set.status as u8);
-    if mode.is_q_server() {
-        serialize_field(&mut map, "status_name", match set.status {
-            MessageProcessingStatus::Unknown => "unknown",
-            MessageProcessingStatus::Queued => "queued",
-            MessageProcessingStatus::Processing => "processing",
-            MessageProcessingStatus::Preliminary => "preliminary",
-            MessageProcessingStatus::Proposed => "proposed",
-            MessageProcessingStatus::Finalized => "finalized",
-            MessageProcessingStatus::Refused => "refused",
-            MessageProcessingStatus::Transiting => "transiting",
-        });
-    }
-    if let Some(state) = &set.message.state_init() {
-        if let Some(split_depth) = state.split_depth() {
-            serialize_field(&mut map, "split_depth", split_depth.as_u32());
-        }
-        if let Some(special) = state.special() {
-            serialize_field(&mut map, "tick", special.tick);
-            serialize_field(&mut map, "tock", special.tock);
-        };
-        serialize_cell(&mut map, "code", state.code(), true)?;
-        serialize_cell(&mut map, "data", state.data(), true)?;
-        serialize_cell(&mut map, "library", state.libraries().root(), true)?;
-    }
-
-    serialize_slice(&mut map, "body", set.message.body().as_ref(), true)?;
-    match set.message.header() {
+/// Message header fields: `msg_type`, addresses, value and creation time. The brief header
+/// skips workchain ids, anycasts and fees
+fn serialize_message_header(
+    map: &mut Map<String, Value>,
+    header: &CommonMsgInfo,
+    transaction_now: Option<u32>,
+    brief: bool,
+    mode: SerializationOptions
+) -> Result<()> {
+    match header {
         CommonMsgInfo::IntMsgInfo(ref header) => {
-            serialize_field(&mut map, "msg_type", 0);
+            serialize_field(map, "msg_type", 0);
             if mode.is_q_server() {
-                serialize_field(&mut map, "msg_type_name", "internal");
+                serialize_field(map, "msg_type_name", "internal");
+            }
+            serialize_field(map, "src", header.src.to_string());
+            if let Some(src_addr) = header.src_ref().filter(|_| !brief) {
+                serialize_field(map, "src_workchain_id", src_addr.get_workchain_id());
+                serialize_anycast(map, "src", src_addr, mode);
             }
-            serialize_field(&mut map, "src", header.src.to_string());
-            if let Some(src_addr) = header.src_ref() {
-                serialize_field(&mut map, "src_workchain_id", src_addr.get_workchain_id());
+            serialize_field(map, "dst", header.dst.to_string());
+            if !brief {
+                serialize_field(map, "dst_workchain_id", header.dst.get_workchain_id());
+                serialize_anycast(map, "dst", &header.dst, mode);
+                serialize_field(map, "ihr_disabled", header.ihr_disabled);
+                serialize_grams(map, "ihr_fee", &header.ihr_fee, mode);
+                serialize_grams(map, "fwd_fee", &header.fwd_fee, mode);
             }
-            serialize_field(&mut map, "dst", header.dst.to_string());
-            serialize_field(&mut map, "dst_workchain_id", header.dst.get_workchain_id());
-            serialize_field(&mut map, "ihr_disabled", header.ihr_disabled);
-            serialize_grams(&mut map, "ihr_fee", &header.ihr_fee, mode);
-            serialize_grams(&mut map, "fwd_fee", &header.fwd_fee, mode);
-            serialize_field(&mut map, "bounce", header.bounce);
-            serialize_field(&mut map, "bounced", header.bounced);
-            serialize_cc(&mut map, "value", &header.value, mode)?;
-            serialize_lt(&mut map, "created_lt", &header.created_lt, mode);
-            serialize_field(&mut map, "created_at", header.created_at.as_u32());
+            serialize_field(map, "bounce", header.bounce);
+            serialize_field(map, "bounced", header.bounced);
+            serialize_cc(map, "value", &header.value, mode)?;
+            serialize_lt(map, "created_lt", &header.created_lt, mode);
+            serialize_field(map, "created_at", header.created_at.as_u32());
         }
         CommonMsgInfo::ExtInMsgInfo(ref header) => {
-            serialize_field(&mut map, "msg_type", 1);
+            serialize_field(map, "msg_type", 1);
             if mode.is_q_server() {
-                serialize_field(&mut map, "msg_type_name", "extIn");
+                serialize_field(map, "msg_type_name", "extIn");
             }
-            serialize_field(&mut map, "src", header.src.to_string());
-            serialize_field(&mut map, "dst", header.dst.to_string());
-            serialize_field(&mut map, "dst_workchain_id", header.dst.get_workchain_id());
-            serialize_grams(&mut map, "import_fee", &header.import_fee, mode);
-            if let Some(now) = set.transaction_now {
-                serialize_field(&mut map, "created_at", now);
+            serialize_field(map, "src", header.src.to_string());
+            serialize_field(map, "dst", header.dst.to_string());
+            if !brief {
+                serialize_field(map, "dst_workchain_id", header.dst.get_workchain_id());
+                serialize_anycast(map, "dst", &header.dst, mode);
+                serialize_grams(map, "import_fee", &header.import_fee, mode);
+            }
+            if let Some(now) = transaction_now {
+                serialize_field(map, "created_at", now);
             }
         }
         CommonMsgInfo::ExtOutMsgInfo(ref header) => {
-            serialize_field(&mut map, "msg_type", 2);
+            serialize_field(map, "msg_type", 2);
             if mode.is_q_server() {
-                serialize_field(&mut map, "msg_type_name", "extOut");
+                serialize_field(map, "msg_type_name", "extOut");
             }
-            serialize_field(&mut map, "src", header.src.to_string());
-            if let Some(src_addr) = header.src() {
-                serialize_field(&mut map, "src_workchain_id", src_addr.get_workchain_id());
+            serialize_field(map, "src", header.src.to_string());
+            if let Some(src_addr) = header.src().filter(|_| !brief) {
+                serialize_field(map, "src_workchain_id", src_addr.get_workchain_id());
+                serialize_anycast(map, "src", &src_addr, mode);
             }
-            serialize_field(&mut map, "dst", header.dst.to_string());
-            serialize_lt(&mut map, "created_lt", &header.created_lt, mode);
-            serialize_field(&mut map, "created_at", header.created_at.as_u32());
+            serialize_field(map, "dst", header.dst.to_string());
+            serialize_lt(map, "created_lt", &header.created_lt, mode);
+            serialize_field(map, "created_at", header.created_at.as_u32());
         }
     }
+    Ok(())
+}
+
+/// Serializes the message with its header, state init and body.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_message_set()?;
+/// let doc = db_serialize_message_ex("id", &set, SerializationMode::QServer)?;
+/// assert_eq!(doc["value"], "0x3b9aca00");
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_message_ex(id_str: &'static str, set: &MessageSerializationSet, mode: impl Into<SerializationOptions>) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let mut map = Map::new();
+    serialize_field(&mut map, "json_version", VERSION);
+    serialize_id(&mut map, id_str, Some(&set.id), mode);
+    // isn't needed there - because message should be fully immutable from source block to destination one
+    //serialize_id(&mut map, "block_id", set.block_id.as_ref(), mode);
+    serialize_id(&mut map, "transaction_id", set.transaction_id.as_ref(), mode);
+    if let Some(proof) = &set.proof {
+        serialize_field(&mut map, "proof", base64_encode(proof));
+    }
+    serialize_field(&mut map, "boc", base64_encode(&set.boc));
+    serialize_status(&mut map, set.status as u8, MessageProcessingStatusJson::from(set.status).name(), mode);
+    if let Some(state) = &set.message.state_init() {
+        serialize_state_init(&mut map, state, mode)?;
+    }
+
+    serialize_slice(&mut map, "body", set.message.body().as_ref(), true, mode)?;
+    serialize_message_header(&mut map, set.message.header(), set.transaction_now, false, mode)?;
+    Ok(map)
+}
+
+/// Summary of the message for account history views: `id`, `status`, `msg_type`, `src`, `dst`,
+/// `value`, `created_at`, `created_lt`, `bounce` and `bounced` in the same format as in the full
+/// message document. Boc, body and state init are skipped.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_message_set()?;
+/// let doc = db_serialize_message_brief("id", &set, SerializationMode::QServer)?;
+/// assert_eq!(doc["value"], "0x3b9aca00");
+/// assert!(doc.get("body").is_none());
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_message_brief(
+    id_str: &'static str,
+    set: &MessageSerializationSet,
+    mode: impl Into<SerializationOptions>
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let mut map = Map::new();
+    serialize_field(&mut map, "json_version", VERSION);
+    serialize_id(&mut map, id_str, Some(&set.id), mode);
+    serialize_status(&mut map, set.status as u8, MessageProcessingStatusJson::from(set.status).name(), mode);
+    serialize_message_header(&mut map, set.message.header(), set.transaction_now, true, mode)?;
     Ok(map)
 }
 
@@ -2071,14 +4140,79 @@ pub fn db_serialize_block_signatures(
     signatures_set: &[CryptoSignaturePair]
 ) -> Result<Map<String, Value>> {
 
+    let mode = SerializationOptions::from(SerializationMode::Standart);
     let mut map = Map::new();
     let mut signs = Vec::new();
     serialize_field(&mut map, "json_version", VERSION);
-    serialize_uint256(&mut map, id_str, block_id);
+    serialize_uint256(&mut map, id_str, block_id, mode);
     for s in signatures_set.iter() {
-        signs.push(serialize_crypto_signature(s)?);
+        signs.push(serialize_crypto_signature(s, mode)?);
+    }
+    serialize_field(&mut map, "signatures", signs);
+    Ok(map)
+}
+
+pub struct BlockSignaturesSerializationSet {
+    pub block_id_ext: BlockIdExt,
+    pub gen_utime: u32,
+    pub seq_no: u32,
+    pub shard: ShardIdent,
+    pub validator_list_hash_short: u32,
+    pub catchain_seqno: u32,
+    pub sig_weight: u64,
+    pub signatures: Vec<CryptoSignaturePair>,
+    pub proof_boc: Option<Vec<u8>>,
+}
+
+/// Serializes block signatures with the block context like they are stored
+/// in `blocks_signatures` collection.
+pub fn db_serialize_block_signatures_ex(
+    id_str: &'static str,
+    set: &BlockSignaturesSerializationSet,
+    mode: impl Into<SerializationOptions>,
+) -> Result<Map<String, Value>> {
+    serialize_block_signatures_set(id_str, set, None, mode.into())
+}
+
+/// Same as `db_serialize_block_signatures_ex` with `signers_bitmap` of the validators
+/// of the block, see `signers_bitmap`.
+pub fn db_serialize_block_signatures_with_validators(
+    id_str: &'static str,
+    set: &BlockSignaturesSerializationSet,
+    validators: &ValidatorSet,
+    mode: impl Into<SerializationOptions>,
+) -> Result<Map<String, Value>> {
+    serialize_block_signatures_set(id_str, set, Some(validators), mode.into())
+}
+
+fn serialize_block_signatures_set(
+    id_str: &'static str,
+    set: &BlockSignaturesSerializationSet,
+    validators: Option<&ValidatorSet>,
+    mode: SerializationOptions,
+) -> Result<Map<String, Value>> {
+    let mut map = Map::new();
+    serialize_field(&mut map, "json_version", VERSION);
+    serialize_uint256(&mut map, id_str, set.block_id_ext.root_hash(), mode);
+    serialize_field(&mut map, "gen_utime", set.gen_utime);
+    serialize_field(&mut map, "seq_no", set.seq_no);
+    serialize_field(&mut map, "workchain_id", set.shard.workchain_id());
+    serialize_field(&mut map, "shard", set.shard.shard_prefix_as_str_with_tag());
+    if let Some(proof) = &set.proof_boc {
+        serialize_field(&mut map, "proof", base64_encode(proof));
+    }
+    serialize_field(&mut map, "validator_list_hash_short", set.validator_list_hash_short);
+    serialize_field(&mut map, "catchain_seqno", set.catchain_seqno);
+    serialize_u64(&mut map, "sig_weight", &set.sig_weight, mode);
+    let mut signs = Vec::new();
+    for s in set.signatures.iter() {
+        signs.push(serialize_crypto_signature(s, mode)?);
     }
     serialize_field(&mut map, "signatures", signs);
+    if let Some(validators) = validators {
+        let signers = set.signatures.iter().map(|s| &s.node_id_short);
+        serialize_field(&mut map, "signers_bitmap", signers_bitmap(signers, validators)?);
+    }
     Ok(map)
 }
 
@@ -2092,13 +4226,32 @@ pub fn db_serialize_block_proof(
 pub fn db_serialize_block_proof_ex(
     id_str: &'static str,
     proof: &BlockProof,
-    mode: SerializationMode,
+    mode: impl Into<SerializationOptions>,
 ) -> Result<Map<String, Value>> {
+    serialize_block_proof(id_str, proof, None, mode.into())
+}
+
+/// Same as `db_serialize_block_proof_ex` with `signers_bitmap` of the validators
+/// of the block, see `signers_bitmap`.
+pub fn db_serialize_block_proof_with_validators(
+    id_str: &'static str,
+    proof: &BlockProof,
+    validators: &ValidatorSet,
+    mode: impl Into<SerializationOptions>,
+) -> Result<Map<String, Value>> {
+    serialize_block_proof(id_str, proof, Some(validators), mode.into())
+}
 
+fn serialize_block_proof(
+    id_str: &'static str,
+    proof: &BlockProof,
+    validators: Option<&ValidatorSet>,
+    mode: SerializationOptions,
+) -> Result<Map<String, Value>> {
     let mut map = Map::new();
 
     serialize_field(&mut map, "json_version", VERSION);
-    serialize_uint256(&mut map, id_str, &proof.proof_for.root_hash);
+    serialize_uint256(&mut map, id_str, &proof.proof_for.root_hash, mode);
 
     let merkle_proof = MerkleProof::construct_from_cell(proof.root.clone())?;
     let block_virt_root = merkle_proof.proof.virtualize(1);
@@ -2109,28 +4262,287 @@ pub fn db_serialize_block_proof_ex(
     map.insert("seq_no".to_string(), block_info.seq_no().into());
     map.insert("workchain_id".to_string(), block_info.shard().workchain_id().into());
     map.insert("shard".to_string(), block_info.shard().shard_prefix_as_str_with_tag().into());
-    serialize_cell(&mut map, "proof", Some(&proof.root), false)?;
+    map.insert("proof_for".to_string(), serialize_separated_block_id_ext(&proof.proof_for, mode)?);
+    serialize_block_refs(&mut map, &block_info, mode)?;
+    serialize_cell(&mut map, "proof", Some(&proof.root), false, mode)?;
 
     if let Some(signatures) = proof.signatures.as_ref() {
-        map.insert("validator_list_hash_short".to_string(), signatures.validator_info.validator_list_hash_short.into());
-        map.insert("catchain_seqno".to_string(), signatures.validator_info.catchain_seqno.into());
-        serialize_u64(&mut map, "sig_weight", &signatures.pure_signatures.weight(), mode);
-
-        let mut signs = Vec::new();
-        signatures
-           .pure_signatures
-           .signatures()
-           .iterate_slices(|_key, mut value| -> Result<bool> {
-                signs.push(
-                    serialize_crypto_signature(
-                        &CryptoSignaturePair::construct_from(&mut value)?
-                    )?
-                );
-                Ok(true)
-           }
-       )?;
-       serialize_field(&mut map, "signatures", signs);
+        serialize_block_signatures(&mut map, signatures, validators, mode)?;
+    }
+    Ok(map)
+}
+
+fn serialize_block_signatures(
+    map: &mut Map<String, Value>,
+    signatures: &BlockSignatures,
+    validators: Option<&ValidatorSet>,
+    mode: SerializationOptions,
+) -> Result<()> {
+    map.insert("validator_list_hash_short".to_string(), signatures.validator_info.validator_list_hash_short.into());
+    map.insert("catchain_seqno".to_string(), signatures.validator_info.catchain_seqno.into());
+    serialize_u64(map, "sig_weight", &signatures.pure_signatures.weight(), mode);
+
+    let mut signs = Vec::new();
+    let mut signers = Vec::new();
+    signatures
+        .pure_signatures
+        .signatures()
+        .iterate_slices(|_key, mut value| -> Result<bool> {
+            let pair = CryptoSignaturePair::construct_from(&mut value)?;
+            signs.push(serialize_crypto_signature(&pair, mode)?);
+            signers.push(pair.node_id_short);
+            Ok(true)
+        }
+    )?;
+    serialize_field(map, "signatures", signs);
+    if let Some(validators) = validators {
+        serialize_field(map, "signers_bitmap", signers_bitmap(&signers, validators)?);
+    }
+    Ok(())
+}
+
+/// Hex string where bit `i` (most significant bit of the first byte is `0`) is set
+/// if the validator with index `i` in `validators` is among `signers` (short node ids
+/// of the signatures). Fails if a signer is not in `validators`.
+pub fn signers_bitmap<'a>(
+    signers: impl IntoIterator<Item = &'a UInt256>,
+    validators: &ValidatorSet
+) -> Result<String> {
+    let indices = validators.list().iter()
+        .enumerate()
+        .map(|(index, validator)| (validator.compute_node_id_short(), index))
+        .collect::<HashMap<_, _>>();
+    let mut bitmap = vec![0u8; (validators.list().len() + 7) / 8];
+    for node_id in signers {
+        let index = indices.get(node_id)
+            .ok_or_else(|| error!("block is signed by unknown validator {:x}", node_id))?;
+        bitmap[index / 8] |= 0x80 >> (index % 8);
+    }
+    Ok(hex::encode(bitmap))
+}
+
+/// Serializes top shard block description sent by collators to the masterchain:
+/// id of the described block, validator signatures and the chain of block proofs
+/// with headers of the proved blocks.
+pub fn db_serialize_top_block_descr(
+    descr: &TopBlockDescr,
+    mode: impl Into<SerializationOptions>,
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let mut map = Map::new();
+    serialize_field(&mut map, "json_version", VERSION);
+    serialize_uint256(&mut map, "id", descr.proof_for().root_hash(), mode);
+    map.insert("proof_for".to_string(), serialize_separated_block_id_ext(descr.proof_for(), mode)?);
+    if let Some(signatures) = descr.signatures() {
+        serialize_block_signatures(&mut map, signatures, None, mode)?;
+    }
+    let mut chain = Vec::new();
+    for proof in descr.chain() {
+        let merkle_proof = MerkleProof::construct_from_cell(proof.clone())?;
+        let virt_block = Block::construct_from_cell(merkle_proof.proof.virtualize(1))?;
+        let block_info = virt_block.read_info()?;
+        let mut link = Map::new();
+        serialize_uint256(&mut link, "id", &merkle_proof.hash, mode);
+        link.insert("gen_utime".to_string(), block_info.gen_utime().as_u32().into());
+        link.insert("seq_no".to_string(), block_info.seq_no().into());
+        link.insert("workchain_id".to_string(), block_info.shard().workchain_id().into());
+        link.insert("shard".to_string(), block_info.shard().shard_prefix_as_str_with_tag().into());
+        serialize_lt(&mut link, "start_lt", &block_info.start_lt(), mode);
+        serialize_lt(&mut link, "end_lt", &block_info.end_lt(), mode);
+        serialize_cell(&mut link, "proof", Some(proof), false, mode)?;
+        chain.push(Value::from(link));
+    }
+    map.insert("chain".to_string(), chain.into());
+    Ok(map)
+}
+
+/// Part of the block to prove with `db_serialize_block_parts_proof`, parsed from the path
+/// of the part in JSON: `transactions/<id>`, `messages/<id>` or `config/p<number>`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofSelector {
+    Transaction(UInt256),
+    Message(UInt256),
+    ConfigParam(u32),
+}
+
+impl std::str::FromStr for ProofSelector {
+    type Err = anyhow::Error;
+
+    fn from_str(path: &str) -> Result<Self> {
+        match path.split_once('/') {
+            Some(("transactions", id)) => Ok(Self::Transaction(id.parse()?)),
+            Some(("messages", id)) => Ok(Self::Message(id.parse()?)),
+            Some(("config", param)) => match param.strip_prefix('p').map(str::parse) {
+                Some(Ok(number)) => Ok(Self::ConfigParam(number)),
+                _ => fail!("wrong config param path {}", path)
+            }
+            _ => fail!("unsupported proof path {}, expected transactions/<id>, messages/<id> or config/p<number>", path)
+        }
+    }
+}
+
+/// Finds the cell with the hash in the tree, collects hashes of the cells on the path
+fn find_cell_path(cell: &Cell, hash: &UInt256, path: &mut Vec<UInt256>, visited: &mut HashSet<UInt256>) -> Result<Option<Cell>> {
+    if !visited.insert(cell.repr_hash()) {
+        return Ok(None)
+    }
+    path.push(cell.repr_hash());
+    if &cell.repr_hash() == hash {
+        return Ok(Some(cell.clone()))
+    }
+    for i in 0..cell.references_count() {
+        if let Some(found) = find_cell_path(&cell.reference(i)?, hash, path, visited)? {
+            return Ok(Some(found))
+        }
+    }
+    path.pop();
+    Ok(None)
+}
+
+fn collect_subtree(cell: &Cell, included: &mut HashSet<UInt256>) -> Result<()> {
+    if included.insert(cell.repr_hash()) {
+        for i in 0..cell.references_count() {
+            collect_subtree(&cell.reference(i)?, included)?;
+        }
+    }
+    Ok(())
+}
+
+/// Builds Merkle proof of the block which keeps the block header and the selected
+/// transactions, messages and config params, all other cells are pruned. Returns `id`,
+/// base64 `proof` and the proven parts serialized as usual documents: `transactions`,
+/// `messages` and `config`.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// use ever_block::Serializable;
+///
+/// let root = samples::sample_block()?.serialize()?;
+/// // with no selectors only the block header is kept
+/// let doc = db_serialize_block_parts_proof(&root, &[], SerializationMode::Standart)?;
+/// assert_eq!(doc["id"], root.repr_hash().as_hex_string());
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_block_parts_proof(
+    block_root: &Cell,
+    selectors: &[ProofSelector],
+    mode: impl Into<SerializationOptions>
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let block = Block::construct_from_cell(block_root.clone())?;
+    let block_id = block_root.repr_hash();
+    let workchain_id = block.read_info()?.shard().workchain_id();
+
+    let mut included = HashSet::new();
+    included.insert(block_id.clone());
+    // block info
+    collect_subtree(&block_root.reference(0)?, &mut included)?;
+
+    let mut include_path = |hash: &UInt256| -> Result<Cell> {
+        let mut path = Vec::new();
+        let cell = find_cell_path(block_root, hash, &mut path, &mut HashSet::new())?
+            .ok_or_else(|| error!("cell {} is not found in the block", hash.as_hex_string()))?;
+        included.extend(path);
+        collect_subtree(&cell, &mut included)?;
+        Ok(cell)
+    };
+
+    let mut transactions = Vec::new();
+    let mut messages = Vec::new();
+    let mut config = Map::new();
+    for selector in selectors {
+        match selector {
+            ProofSelector::Transaction(id) => {
+                let cell = include_path(id)?;
+                let set = TransactionSerializationSet {
+                    transaction: Transaction::construct_from_cell(cell.clone())?,
+                    id: id.clone(),
+                    status: TransactionProcessingStatus::Finalized,
+                    block_id: Some(block_id.clone()),
+                    workchain_id,
+                    boc: write_boc(&cell)?,
+                    proof: None,
+                };
+                transactions.push(db_serialize_transaction_ex("id", &set, mode)?);
+            }
+            ProofSelector::Message(id) => {
+                let cell = include_path(id)?;
+                let set = MessageSerializationSet {
+                    message: Message::construct_from_cell(cell.clone())?,
+                    id: id.clone(),
+                    block_id: Some(block_id.clone()),
+                    transaction_id: None,
+                    transaction_now: None,
+                    status: MessageProcessingStatus::Finalized,
+                    boc: write_boc(&cell)?,
+                    proof: None,
+                };
+                messages.push(db_serialize_message_ex("id", &set, mode)?);
+            }
+            ProofSelector::ConfigParam(number) => {
+                let config_params = block.read_extra()?.read_custom()?
+                    .and_then(|extra| extra.config().cloned())
+                    .ok_or_else(|| error!("block has no config, only key blocks contain it"))?;
+                let key = SliceData::load_builder(number.write_to_new_cell()?)?;
+                let param = config_params.config_params.get(key)?
+                    .ok_or_else(|| error!("config param {} is absent", number))?;
+                let cell = include_path(&param.reference(0)?.repr_hash())?;
+                let mut slice = SliceData::load_cell(cell)?;
+                let value = match serialize_known_config_param(*number, &mut slice, mode)? {
+                    Some(value) => value,
+                    None => serialize_unknown_config_param(*number, &mut slice, mode)?,
+                };
+                config.insert(format!("p{}", number), value);
+            }
+        }
     }
+
+    let proof = MerkleProof::create(block_root, |hash| included.contains(hash))?;
+    let mut map = Map::new();
+    serialize_field(&mut map, "json_version", VERSION);
+    serialize_id(&mut map, "id", Some(&block_id), mode);
+    serialize_field(&mut map, "proof", base64_encode(proof.write_to_bytes()?));
+    if !transactions.is_empty() {
+        serialize_field(&mut map, "transactions", transactions);
+    }
+    if !messages.is_empty() {
+        serialize_field(&mut map, "messages", messages);
+    }
+    if !config.is_empty() {
+        serialize_field(&mut map, "config", config);
+    }
+    Ok(map)
+}
+
+pub fn db_serialize_block_header_from_proof(
+    id_str: &'static str,
+    proof: &BlockProof,
+) -> Result<Map<String, Value>> {
+    db_serialize_block_header_from_proof_ex(id_str, proof, SerializationMode::Standart)
+}
+
+/// Serializes block header fields the same way `db_serialize_block` does, but reads them
+/// from the block proof, so the full block is not needed. Signatures are not included.
+pub fn db_serialize_block_header_from_proof_ex(
+    id_str: &'static str,
+    proof: &BlockProof,
+    mode: impl Into<SerializationOptions>,
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    let merkle_proof = MerkleProof::construct_from_cell(proof.root.clone())?;
+    let block_virt_root = merkle_proof.proof.virtualize(1);
+    let virt_block = Block::construct_from_cell(block_virt_root)?;
+    let block_info = virt_block.read_info()?;
+
+    let mut map = Map::new();
+    serialize_field(&mut map, "json_version", VERSION);
+    serialize_uint256(&mut map, id_str, &proof.proof_for.root_hash, mode);
+    serialize_id(&mut map, "file_hash", Some(&proof.proof_for.file_hash), mode);
+    serialize_field(&mut map, "from_proof", true);
+    serialize_field(&mut map, "global_id", virt_block.global_id);
+    serialize_block_info(&mut map, &block_info, mode)?;
     Ok(map)
 }
 
@@ -2144,18 +4556,41 @@ pub struct ShardStateSerializationSet {
 }
 
 
+/// Serializes the shard state in `Standart` mode.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_state_set()?;
+/// let doc = db_serialize_shard_state("id", &set)?;
+/// assert_eq!(doc["id"], "-1:8000000000000000");
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 pub fn db_serialize_shard_state(id_str: &'static str, set: &ShardStateSerializationSet) -> Result<Map<String, Value>> {
     db_serialize_shard_state_ex(id_str, set, SerializationMode::Standart)
 }
 
-pub fn db_serialize_shard_state_ex(id_str: &'static str, set: &ShardStateSerializationSet, mode: SerializationMode) -> Result<Map<String, Value>> {
+/// Serializes the shard state with its accounts, libraries, out message queue and masterchain part if any.
+///
+/// ```
+/// # #[cfg(feature = "samples")] {
+/// use ever_block_json::*;
+/// let set = samples::sample_state_set()?;
+/// let doc = db_serialize_shard_state_ex("id", &set, SerializationMode::QServer)?;
+/// assert_eq!(doc["workchain_id"], -1);
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_shard_state_ex(id_str: &'static str, set: &ShardStateSerializationSet, mode: impl Into<SerializationOptions>) -> Result<Map<String, Value>> {
+    let mode = mode.into();
     let mut map = Map::new();
     serialize_field(&mut map, "json_version", VERSION);
     serialize_field(&mut map, id_str, set.id.as_str());
     let cell = read_single_root_boc(set.boc.as_slice())?;
-    serialize_id(&mut map, "root_hash", Some(&cell.repr_hash()));
-    serialize_file_hash(&mut map, None, &set.boc);
-    serialize_id(&mut map, "block_id", set.block_id.as_ref());
+    serialize_id(&mut map, "root_hash", Some(&cell.repr_hash()), mode);
+    serialize_file_hash(&mut map, None, &set.boc, mode);
+    serialize_id(&mut map, "block_id", set.block_id.as_ref(), mode);
     serialize_field(&mut map, "workchain_id", set.workchain_id);
     serialize_field(&mut map, "boc", base64_encode(&set.boc));
     serialize_field(&mut map, "global_id", set.state.global_id());
@@ -2177,25 +4612,105 @@ pub fn db_serialize_shard_state_ex(id_str: &'static str, set: &ShardStateSeriali
         serialize_mc_state_extra(&mut map, "master", &master, mode)?;
     }
     serialize_shard_accounts(&mut map, "accounts", &set.state.read_accounts()?, mode)?;
-    serialize_libraries(&mut map, "libraries", set.state.libraries())?;
+    serialize_libraries(&mut map, "libraries", set.state.libraries(), mode)?;
     serialize_out_msg_queue_info(&mut map, "out_msg_queue_info", &set.state.read_out_msg_queue_info()?, mode)?;
     Ok(map)
 }
 
-pub fn debug_state(mut state: ShardStateUnsplit) -> Result<String> {
-    state.write_accounts(&Default::default())?;
+/// Serializes the state without its BOC at hand, the document is identified by the shard
+/// (`<workchain_id>:<shard>`) and can be read back with `parse_state`.
+///
+/// ```
+/// use ever_block_json::*;
+/// let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json")?;
+/// let map = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json)?;
+/// let state = parse_state(&map)?;
+/// let doc = db_serialize_shard_state_unsplit("id", &state, SerializationMode::Standart)?;
+/// assert_eq!(parse_state(&doc)?, state);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn db_serialize_shard_state_unsplit(
+    id_str: &'static str,
+    state: &ShardStateUnsplit,
+    mode: impl Into<SerializationOptions>
+) -> Result<Map<String, Value>> {
+    let mode = mode.into();
     let set = ShardStateSerializationSet {
         block_id: None,
         workchain_id: state.shard().workchain_id(),
         id: format!("{}", state.shard()),
         boc: state.write_to_bytes()?,
-        state,
+        state: state.clone(),
     };
-    let map = db_serialize_shard_state_ex("id", &set, SerializationMode::Debug)?;
-    Ok(format!("{:#}", serde_json::json!(map)))
+    db_serialize_shard_state_ex(id_str, &set, mode)
 }
 
-pub fn debug_state_full(state: ShardStateUnsplit) -> Result<String> {
+/// Serializes the zero state for genesis tooling: the same document as
+/// `db_serialize_shard_state_unsplit` (with `root_hash`, `file_hash` and the masterchain part
+/// with the full config, validator info and `global_balance`) plus `workchains` with ids of the
+/// workchains from the config. Fails if the state is not a zero state.
+pub fn db_serialize_zerostate(state: &ShardStateUnsplit, mode: impl Into<SerializationOptions>) -> Result<Map<String, Value>> {
+    let mode = mode.into();
+    if state.seq_no() != 0 {
+        fail!("state with seq_no {} is not a zero state", state.seq_no())
+    }
+    let mut map = db_serialize_shard_state_unsplit("id", state, mode)?;
+    if let Some(master) = state.read_custom()? {
+        let mut workchains = Vec::new();
+        master.config.workchains()?.iterate_with_keys(|workchain_id: i32, _| {
+            workchains.push(workchain_id);
+            Ok(true)
+        })?;
+        serialize_field(&mut map, "workchains", workchains);
+    }
+    Ok(map)
+}
+
+/// Splits serialized shard state into a header document and documents with batches of
+/// `accounts_per_chunk` accounts each. Every document points to the next one by `next` field,
+/// so the whole state can be assembled back with `merge_state_chunks`.
+pub fn split_state_json(
+    id_str: &'static str,
+    mut state: Map<String, Value>,
+    accounts_per_chunk: usize,
+) -> Result<Vec<Map<String, Value>>> {
+    if accounts_per_chunk == 0 {
+        fail!("accounts_per_chunk must be greater than zero")
+    }
+    let id = match state.get(id_str) {
+        Some(Value::String(id)) => id.clone(),
+        _ => fail!("state document has no `{}` field", id_str)
+    };
+    let accounts = match state.remove("accounts") {
+        Some(Value::Array(accounts)) => accounts,
+        Some(_) => fail!("`accounts` field of state document must be an array"),
+        None => Vec::new(),
+    };
+    let chunk_id = |index: usize| format!("{}/{}", id, index);
+
+    let count = (accounts.len() + accounts_per_chunk - 1) / accounts_per_chunk;
+    serialize_field(&mut state, "accounts_chunks", count);
+    if count > 0 {
+        serialize_field(&mut state, "next", chunk_id(1));
+    }
+    let mut result = vec![state];
+    let mut accounts = accounts.into_iter();
+    for index in 1..=count {
+        let mut chunk = Map::new();
+        serialize_field(&mut chunk, id_str, chunk_id(index));
+        serialize_field(&mut chunk, "state_id", id.as_str());
+        serialize_field(&mut chunk, "chunk", index);
+        serialize_field(&mut chunk, "accounts", accounts.by_ref().take(accounts_per_chunk).collect::<Vec<_>>());
+        if index < count {
+            serialize_field(&mut chunk, "next", chunk_id(index + 1));
+        }
+        result.push(chunk);
+    }
+    Ok(result)
+}
+
+pub fn debug_state(mut state: ShardStateUnsplit) -> Result<String> {
+    state.write_accounts(&Default::default())?;
     let set = ShardStateSerializationSet {
         block_id: None,
         workchain_id: state.shard().workchain_id(),
@@ -2207,27 +4722,32 @@ pub fn debug_state_full(state: ShardStateUnsplit) -> Result<String> {
     Ok(format!("{:#}", serde_json::json!(map)))
 }
 
-fn serialize_block_id_ext(map: &mut Map<String, Value>, id: &BlockIdExt, mc: bool) {
+pub fn debug_state_full(state: ShardStateUnsplit) -> Result<String> {
+    let map = db_serialize_shard_state_unsplit("id", &state, SerializationMode::Debug)?;
+    Ok(format!("{:#}", serde_json::json!(map)))
+}
+
+fn serialize_block_id_ext(map: &mut Map<String, Value>, id: &BlockIdExt, mc: bool, mode: SerializationOptions) {
     if mc {
-        serialize_uint256(map, "mc_block_id", id.root_hash());
-        serialize_uint256(map, "mc_block_file_hash", id.file_hash());
+        serialize_uint256(map, "mc_block_id", id.root_hash(), mode);
+        serialize_uint256(map, "mc_block_file_hash", id.file_hash(), mode);
         serialize_field(map, "mc_block_seqno", id.seq_no());
     } else {
-        serialize_uint256(map, "block_id", id.root_hash());
-        serialize_uint256(map, "block_file_hash", id.file_hash());
+        serialize_uint256(map, "block_id", id.root_hash(), mode);
+        serialize_uint256(map, "block_file_hash", id.file_hash(), mode);
         serialize_field(map, "block_seqno", id.seq_no());
         serialize_field(map, "shard", id.shard().shard_prefix_as_str_with_tag());
         serialize_field(map, "wc", id.shard().workchain_id());
     }
 }
 
-fn serialize_separated_block_id_ext(id: &BlockIdExt) -> Result<Value>{
+fn serialize_separated_block_id_ext(id: &BlockIdExt, mode: SerializationOptions) -> Result<Value>{
     let mut map = Map::new();
     serialize_field(&mut map, "wc", id.shard().workchain_id());
     serialize_field(&mut map, "shard", id.shard().shard_prefix_as_str_with_tag());
     serialize_field(&mut map, "seqno", id.seq_no());
-    serialize_uint256(&mut map, "root_hash", id.root_hash());
-    serialize_uint256(&mut map, "file_hash", id.file_hash());
+    serialize_uint256(&mut map, "root_hash", id.root_hash(), mode);
+    serialize_uint256(&mut map, "file_hash", id.file_hash(), mode);
     Ok(map.into())
 }
 
@@ -2235,11 +4755,12 @@ pub fn db_serialize_remp_status(
     status: &RempReceipt,
     signature: &[u8]
 ) -> Result<Map<String, Value>> {
+    let mode = SerializationOptions::from(SerializationMode::Standart);
     let mut map = Map::new();
 
-    serialize_uint256(&mut map, "message_id", status.message_id());
+    serialize_uint256(&mut map, "message_id", status.message_id(), mode);
     serialize_field(&mut map, "timestamp", *status.timestamp());
-    serialize_uint256(&mut map, "source_id", status.source_id());
+    serialize_uint256(&mut map, "source_id", status.source_id(), mode);
     serialize_field(&mut map, "signature", base64_encode(signature));
 
     match status.status() {
@@ -2252,14 +4773,14 @@ pub fn db_serialize_remp_status(
                 RempMessageLevel::TonNode_RempShardchain => "IncludedIntoAcceptedBlock",
             };
             serialize_field(&mut map, "kind", kind);
-            serialize_block_id_ext(&mut map, &acc.block_id, false);
+            serialize_block_id_ext(&mut map, &acc.block_id, false, mode);
             if acc.master_id.seq_no() != 0 {
-                serialize_block_id_ext(&mut map, &acc.master_id, true);
+                serialize_block_id_ext(&mut map, &acc.master_id, true, mode);
             }
         },
         RempMessageStatus::TonNode_RempDuplicate(dup) => {
             serialize_field(&mut map, "kind", "Duplicate");
-            serialize_block_id_ext(&mut map, &dup.block_id, false);
+            serialize_block_id_ext(&mut map, &dup.block_id, false, mode);
         },
         RempMessageStatus::TonNode_RempIgnored(ign) => {
             let kind = match ign.level {
@@ -2270,7 +4791,7 @@ pub fn db_serialize_remp_status(
                 RempMessageLevel::TonNode_RempShardchain => "IgnoredByShardchain",
             };
             serialize_field(&mut map, "kind", kind);
-            serialize_block_id_ext(&mut map, &ign.block_id, false);
+            serialize_block_id_ext(&mut map, &ign.block_id, false, mode);
         },
         RempMessageStatus::TonNode_RempNew => {
             serialize_field(&mut map, "kind", "PutIntoQueue");
@@ -2285,7 +4806,7 @@ pub fn db_serialize_remp_status(
             };
             serialize_field(&mut map, "kind", kind);
             if rj.block_id.seq_no() != 0 {
-                serialize_block_id_ext(&mut map, &rj.block_id, false);
+                serialize_block_id_ext(&mut map, &rj.block_id, false, mode);
             }
             serialize_field(&mut map, "error", rj.error.clone());
         },
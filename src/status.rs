@@ -0,0 +1,133 @@
+/*
+ * Copyright (C) 2019-2023 EverX. All Rights Reserved.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.  You may obtain a copy of the
+ * License at:
+ *
+ * https://www.ever.dev/licenses
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific EVERX DEV software governing permissions and limitations
+ * under the License.
+ */
+
+//! Processing statuses of documents in both forms used in JSON: numeric `status` and
+//! `status_name` emitted in QServer mode.
+
+use ever_block::{
+    fail, BlockProcessingStatus, MessageProcessingStatus, Result, TransactionProcessingStatus,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+macro_rules! status_json {
+    ($name:ident, $status:ident, [$(($variant:ident, $str:literal)),* $(,)?]) => {
+        /// Serialized as the numeric `status`, deserialized from the number or `status_name`
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)*
+        }
+
+        impl $name {
+            pub const ALL: &'static [Self] = &[$(Self::$variant,)*];
+
+            pub fn as_u8(self) -> u8 {
+                $status::from(self) as u8
+            }
+
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $str,)*
+                }
+            }
+
+            pub fn from_u8(value: u8) -> Result<Self> {
+                match Self::ALL.iter().find(|status| status.as_u8() == value) {
+                    Some(status) => Ok(*status),
+                    None => fail!("unknown {} {}", stringify!($status), value)
+                }
+            }
+
+            pub fn from_name(name: &str) -> Result<Self> {
+                match Self::ALL.iter().find(|status| status.name() == name) {
+                    Some(status) => Ok(*status),
+                    None => fail!("unknown {} {}", stringify!($status), name)
+                }
+            }
+
+            /// Parses `status` number or `status_name` string
+            pub fn from_json(value: &Value) -> Result<Self> {
+                match value {
+                    Value::Number(number) => match number.as_u64().map(u8::try_from) {
+                        Some(Ok(value)) => Self::from_u8(value),
+                        _ => fail!("unknown {} {}", stringify!($status), number)
+                    }
+                    Value::String(name) => Self::from_name(name),
+                    _ => fail!("{} must be number or string", stringify!($status))
+                }
+            }
+        }
+
+        impl From<$status> for $name {
+            fn from(status: $status) -> Self {
+                match status {
+                    $($status::$variant => Self::$variant,)*
+                }
+            }
+        }
+
+        impl From<$name> for $status {
+            fn from(status: $name) -> Self {
+                match status {
+                    $($name::$variant => Self::$variant,)*
+                }
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                serializer.serialize_u8(self.as_u8())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+                let value = Value::deserialize(deserializer)?;
+                Self::from_json(&value).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+status_json!(BlockProcessingStatusJson, BlockProcessingStatus, [
+    (Unknown, "unknown"),
+    (Proposed, "proposed"),
+    (Finalized, "finalized"),
+    (Refused, "refused"),
+]);
+
+status_json!(TransactionProcessingStatusJson, TransactionProcessingStatus, [
+    (Unknown, "unknown"),
+    (Preliminary, "preliminary"),
+    (Proposed, "proposed"),
+    (Finalized, "finalized"),
+    (Refused, "refused"),
+]);
+
+status_json!(MessageProcessingStatusJson, MessageProcessingStatus, [
+    (Unknown, "unknown"),
+    (Queued, "queued"),
+    (Processing, "processing"),
+    (Preliminary, "preliminary"),
+    (Proposed, "proposed"),
+    (Finalized, "finalized"),
+    (Refused, "refused"),
+    (Transiting, "transiting"),
+]);
+
+#[cfg(test)]
+#[path = "tests/test_status.rs"]
+mod tests;
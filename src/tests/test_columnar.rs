@@ -0,0 +1,93 @@
+/*
+ * Copyright (C) 2019-2023 EverX. All Rights Reserved.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.  You may obtain a copy of the
+ * License at:
+ *
+ * https://www.ever.dev/licenses
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific EVERX DEV software governing permissions and limitations
+ * under the License.
+ */
+
+use super::*;
+use serde_json::json;
+
+fn doc(value: Value) -> Map<String, Value> {
+    value.as_object().unwrap().clone()
+}
+
+#[test]
+fn test_documents_to_columns() {
+    let docs = [
+        doc(json!({
+            "id": "a",
+            "aborted": false,
+            "lt": 10,
+            "compute": { "gas_used": 5, "success": true },
+            "out_msgs": ["m1", "m2"],
+            "mixed": 1,
+        })),
+        doc(json!({
+            "id": "b",
+            "lt": 11,
+            "compute": { "gas_used": -1 },
+            "balance_delta": 0.5,
+            "mixed": "x",
+            "empty": null,
+        })),
+    ];
+    let batch = documents_to_columns(&docs);
+    assert_eq!(batch.rows, 2);
+    let names = batch.columns.iter().map(|column| column.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, [
+        "id", "aborted", "lt", "compute.gas_used", "compute.success", "out_msgs", "mixed", "balance_delta"
+    ]);
+
+    let column = batch.column("id").unwrap();
+    assert!(!column.nullable);
+    assert_eq!(column.values, ColumnValues::String(vec![Some("a".into()), Some("b".into())]));
+    let column = batch.column("aborted").unwrap();
+    assert!(column.nullable);
+    assert_eq!(column.values, ColumnValues::Bool(vec![Some(false), None]));
+    assert_eq!(batch.column("lt").unwrap().values, ColumnValues::UInt(vec![Some(10), Some(11)]));
+    assert_eq!(batch.column("compute.gas_used").unwrap().values, ColumnValues::Int(vec![Some(5), Some(-1)]));
+    assert_eq!(batch.column("compute.success").unwrap().values, ColumnValues::Bool(vec![Some(true), None]));
+    assert_eq!(
+        batch.column("out_msgs").unwrap().values,
+        ColumnValues::String(vec![Some(r#"["m1","m2"]"#.into()), None])
+    );
+    assert_eq!(
+        batch.column("mixed").unwrap().values,
+        ColumnValues::String(vec![Some("1".into()), Some("x".into())])
+    );
+    assert_eq!(batch.column("balance_delta").unwrap().values, ColumnValues::Float(vec![None, Some(0.5)]));
+    assert!(batch.column("empty").is_none());
+
+    for column in &batch.columns {
+        assert_eq!(column.values.len(), batch.rows, "{}", column.name);
+    }
+    assert_eq!(documents_to_columns(&[]), ColumnBatch::default());
+}
+
+#[test]
+fn test_large_unsigned_columns() {
+    let docs = [
+        doc(json!({ "big": u64::MAX, "signed": u64::MAX, "float": u64::MAX })),
+        doc(json!({ "big": 1, "signed": -1, "float": 0.5 })),
+    ];
+    let batch = documents_to_columns(&docs);
+    assert_eq!(batch.column("big").unwrap().values, ColumnValues::UInt(vec![Some(u64::MAX), Some(1)]));
+    assert_eq!(
+        batch.column("signed").unwrap().values,
+        ColumnValues::String(vec![Some(u64::MAX.to_string()), Some("-1".into())])
+    );
+    assert_eq!(
+        batch.column("float").unwrap().values,
+        ColumnValues::String(vec![Some(u64::MAX.to_string()), Some("0.5".into())])
+    );
+}
@@ -15,7 +15,7 @@
  */
 
 use super::*;
-use crate::{serialize_config, SerializationMode, serialize_config_param};
+use crate::{serialize_config, OtherCurrenciesLayout, SerializationMode, SerializationOptions, serialize_config_param};
 use ever_block::{
     ConfigParam3, ConfigParam32, ConfigParam33, ConfigParam35, ConfigParam36, ConfigParam37,
     ConfigParam39, ConfigParam4, ConfigParam6, ConfigVotingSetup, DelectorParams, Number16,
@@ -34,6 +34,38 @@ fn test_parse_zerostate() {
     assert_json_eq(&json, &ethalon, "zerostate");
 }
 
+#[test]
+fn test_shard_state_json_round_trip() {
+    let ethalon = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json").unwrap();
+    let map = serde_json::from_str::<Map<String, Value>>(&ethalon).unwrap();
+    let state = parse_state(&map).unwrap();
+    for mode in [SerializationMode::Standart, SerializationMode::Debug] {
+        let map = crate::db_serialize_shard_state_unsplit("id", &state, mode).unwrap();
+        assert_eq!(map["id"], "-1:8000000000000000");
+        assert_eq!(parse_state(&map).unwrap(), state);
+    }
+}
+
+#[test]
+fn test_parse_zerostate_chunks() {
+    let ethalon = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json").unwrap();
+    let map = serde_json::from_str::<Map<String, Value>>(&ethalon).unwrap();
+    let mut chunks = crate::split_state_json("id", map.clone(), 3).unwrap();
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[0]["accounts_chunks"], 2);
+    assert_eq!(chunks[2]["accounts"].as_array().unwrap().len(), 1);
+
+    chunks.reverse();
+    assert_eq!(merge_state_chunks("id", &chunks).unwrap(), map);
+
+    let state = parse_state_chunks("id", &chunks).unwrap();
+    let json = crate::debug_state_full(state).unwrap();
+    assert_json_eq(&json, &ethalon, "zerostate");
+
+    chunks.remove(1);
+    check_err(merge_state_chunks("id", &chunks), "state chunk -1:8000000000000000/1 is missing");
+}
+
 fn check_err<T: std::fmt::Debug>(result: Result<T>, text: &str) {
     let len = text.len();
     assert_eq!(&result.expect_err("must generate error").to_string()[0..len], text)
@@ -70,6 +102,101 @@ fn test_parse_errors() {
     check_err(obj.get_num("a3"), "root/obj/a3 must be the integer or a string with the integer");
 }
 
+#[test]
+fn test_parse_error_codes() {
+    let json = serde_json::json!({
+        "obj": { "a1": "qwe" },
+        "hash": "qwe",
+    });
+    let map = PathMap::new(&json.as_object().unwrap());
+    let code = |err: anyhow::Error| DeserializationError::code_of(&err);
+    assert_eq!(code(map.get_obj("unknown").unwrap_err()), Some("E001"));
+    assert_eq!(code(map.get_vec("obj").unwrap_err()), Some("E002"));
+    assert_eq!(code(map.get_bool("hash").unwrap_err()), Some("E002"));
+    assert_eq!(code(map.get_uint256("hash").unwrap_err()), Some("E003"));
+    let obj = map.get_obj("obj").unwrap();
+    let err = obj.get_num("a1").unwrap_err();
+    match err.downcast_ref::<DeserializationError>() {
+        Some(DeserializationError::InvalidValue { path, expected, .. }) => {
+            assert_eq!(path, "root/obj/a1");
+            assert_eq!(*expected, "the integer or a string with the integer");
+        }
+        _ => panic!("unexpected error {}", err)
+    }
+    assert_eq!(code(anyhow::anyhow!("other")), None);
+}
+
+#[test]
+fn test_null_fields() {
+    let json = serde_json::json!({
+        "obj": null,
+        "num": null,
+        "lt": 5,
+    });
+    let map = PathMap::new(&json.as_object().unwrap());
+    let code = |err: anyhow::Error| DeserializationError::code_of(&err);
+    assert_eq!(code(map.get_obj("obj").unwrap_err()), Some("E001"));
+    check_err(map.get_num("num"), "root must have the field `num`");
+    assert_eq!(map.get_num("lt").unwrap(), 5);
+    assert!(check_num_field(&map, "num", 1).is_ok());
+    assert!(check_num_field(&map, "lt", 5).is_ok());
+    assert!(check_num_field(&map, "lt", 6).is_err());
+    assert!(parse_block_ref(&map, "obj").unwrap().is_none());
+}
+
+#[test]
+fn test_get_currency_id() {
+    let json = serde_json::json!({
+        "num": 7,
+        "dec": "4294967295",
+        "hex": "0xff",
+        "big": "4294967296",
+        "neg": -1,
+    });
+    let map = PathMap::new(&json.as_object().unwrap());
+    assert_eq!(map.get_currency_id("num").unwrap(), 7);
+    assert_eq!(map.get_currency_id("dec").unwrap(), u32::MAX);
+    assert_eq!(map.get_currency_id("hex").unwrap(), 255);
+    check_err(map.get_currency_id("big"), "root/big must be the currency id fitting 32-bit dictionary key");
+    check_err(map.get_currency_id("neg"), "root/neg must be the currency id fitting 32-bit dictionary key");
+}
+
+#[test]
+fn test_numeric_strings_strictness() {
+    let json = serde_json::json!({
+        "lt": "10",
+        "end_lt": "0x10",
+        "capabilities": "46",
+        "seq_no": "5",
+        "gen_utime": 7,
+        "vert_seqno_incr": 1,
+        "vert_seqno_incr_dec": "1",
+    });
+    let coercions = CoercionsTracker::default();
+    let map = PathMap::with_coercions(json.as_object().unwrap(), &coercions);
+    for name in ["lt", "end_lt", "capabilities", "seq_no", "gen_utime", "vert_seqno_incr"] {
+        map.get_num(name).unwrap();
+    }
+    assert_eq!(coercions.into_warnings(), vec!["root/seq_no is the number passed as the string"]);
+
+    let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json").unwrap();
+    let mut map = serde_json::from_str::<Map<String, Value>>(&json).unwrap();
+    let (state, warnings) = parse_state_with_strictness(&map, ParserStrictness::Strict).unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+
+    // debug mode writes 64-bit fields as strings without decimal companions
+    let doc = crate::db_serialize_zerostate(&state, crate::SerializationMode::Debug).unwrap();
+    let (_, warnings) = parse_state_with_strictness(&doc, ParserStrictness::Strict).unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+
+    map.insert("global_id".to_string(), "42".into());
+    let (state, warnings) = parse_state_with_strictness(&map, ParserStrictness::Strict).unwrap();
+    assert_eq!(state.global_id(), 42);
+    assert_eq!(warnings, vec!["root/global_id is the number passed as the string"]);
+    let (_, warnings) = parse_state_with_strictness(&map, ParserStrictness::Lenient).unwrap();
+    assert!(warnings.is_empty());
+}
+
 fn get_config_param0() -> ConfigParam0 {
     let mut c = ConfigParam0::new();
     c.config_addr = UInt256::from([1;32]);
@@ -182,6 +309,9 @@ fn get_workchain_desc() -> WorkchainDescr {
     wc.version = 1;
     wc.zerostate_file_hash = UInt256::rand();
     wc.zerostate_root_hash = UInt256::rand();
+    wc.set_max_split(8).unwrap();
+    wc.set_min_split(4).unwrap();
+    wc.set_actual_min_split(2).unwrap();
 
     wc.format = WorkchainFormat::Basic(WorkchainFormat1::with_params(123, 453454));
     wc
@@ -532,6 +662,45 @@ fn test_parse_config_params() {
     }
 }
 
+#[test]
+fn test_storage_prices_indices() {
+    let mut map = ConfigParam18Map::default();
+    map.set(&3, &get_storage_prices()).unwrap();
+    map.set(&7, &get_storage_prices()).unwrap();
+    let mut cp = ConfigParams::new();
+    cp.set_config(ConfigParamEnum::ConfigParam18(ConfigParam18 { map })).unwrap();
+
+    let param = serialize_config_param(&cp, 18).unwrap();
+    let config = serde_json::from_str::<Map<String, Value>>(&param).unwrap();
+    assert_eq!(config["p18"][0]["index"], 3);
+    assert_eq!(config["p18"][1]["index"], 7);
+    let cp_new = parse_config_with_mandatory_params(&config, &[18]).unwrap();
+    assert_eq!(cp.config(18).unwrap(), cp_new.config(18).unwrap());
+
+    // without indices entries are numbered in order
+    let mut config = config;
+    config["p18"].as_array_mut().unwrap().iter_mut()
+        .for_each(|p| { p.as_object_mut().unwrap().remove("index"); });
+    let cp_new = parse_config_with_mandatory_params(&config, &[18]).unwrap();
+    match cp_new.config(18).unwrap() {
+        Some(ConfigParamEnum::ConfigParam18(p18)) => {
+            assert!(p18.map.get(&0).unwrap().is_some());
+            assert!(p18.map.get(&1).unwrap().is_some());
+        }
+        _ => panic!("p18 must be present"),
+    }
+
+    // invalid indices are not replaced with the order number
+    for index in [serde_json::json!(-1), serde_json::json!(4294967296u64), serde_json::json!("qwe")] {
+        let mut config = config.clone();
+        config["p18"][0]["index"] = index.clone();
+        assert!(parse_config_with_mandatory_params(&config, &[18]).is_err(), "{}", index);
+    }
+    let mut config = config;
+    config["p18"][0]["index"] = u32::MAX.into();
+    assert!(parse_config_with_mandatory_params(&config, &[18]).is_err());
+}
+
 #[test]
 fn test_parse_block_proof() {
     let boc = include_bytes!("data/block_proof");
@@ -543,3 +712,605 @@ fn test_parse_block_proof() {
     assert_eq!(ethalon_proof, parsed_proof);
     assert_eq!(boc.as_slice(), &parsed_proof.write_to_bytes().unwrap());
 }
+
+#[test]
+fn test_parse_p34_weights() {
+    let validator = |weight: &str| serde_json::json!({
+        "public_key": "ef0ddf5f98e2ba8f0fc90a056842fe2bda44fd7bdb2a31fade2d0972b68b9413",
+        "weight": weight,
+    });
+
+    let json = serde_json::json!({
+        "p34": {
+            "utime_since": 0,
+            "utime_until": 100,
+            "main": 1,
+            "list": [
+                validator("0x7fffffffffffffff"),
+                validator("0x7fffffffffffffff"),
+                validator("0x7fffffffffffffff"),
+            ]
+        }
+    });
+    let err = parse_config(json.as_object().unwrap()).expect_err("must generate error");
+    assert!(matches!(
+        err.downcast_ref::<DeserializationError>(),
+        Some(DeserializationError::ValidatorsWeightOverflow { .. })
+    ));
+
+    let json = serde_json::json!({
+        "p34": {
+            "utime_since": 0,
+            "utime_until": 100,
+            "main": 1,
+            "total_weight": "0xb",
+            "list": [ validator("0xa") ]
+        }
+    });
+    let err = parse_config(json.as_object().unwrap()).expect_err("must generate error");
+    assert!(matches!(
+        err.downcast_ref::<DeserializationError>(),
+        Some(DeserializationError::TotalWeightMismatch { expected: 11, actual: 10, .. })
+    ));
+
+    let json = serde_json::json!({
+        "p34": {
+            "utime_since": 0,
+            "utime_until": 100,
+            "main": 1,
+            "total_weight": "0xa",
+            "list": [ validator("0xa") ]
+        }
+    });
+    let config = parse_config(json.as_object().unwrap()).unwrap();
+    assert_eq!(config.validator_set().unwrap().total_weight(), 10);
+
+    // present but invalid total weight is not skipped
+    for total_weight in [serde_json::json!("qwe"), serde_json::json!(-10), serde_json::json!(true)] {
+        let mut json = json.clone();
+        json["p34"]["total_weight"] = total_weight.clone();
+        assert!(parse_config(json.as_object().unwrap()).is_err(), "{}", total_weight);
+    }
+
+    // weights above i64::MAX are compared in full
+    let mut json = json;
+    json["p34"]["list"] = serde_json::json!([validator("0x8000000000000000")]);
+    json["p34"]["total_weight"] = "0x8000000000000001".into();
+    let err = parse_config(json.as_object().unwrap()).expect_err("must generate error");
+    assert!(matches!(
+        err.downcast_ref::<DeserializationError>(),
+        Some(DeserializationError::TotalWeightMismatch { expected: 0x8000000000000001, actual: 0x8000000000000000, .. })
+    ));
+}
+
+#[test]
+fn test_validator_set_diff() {
+    let key_block_json = |set: ValidatorSet| {
+        let mut cp = ConfigParams::new();
+        cp.set_config(ConfigParamEnum::ConfigParam34(ConfigParam34 { cur_validators: set })).unwrap();
+        let mut map = Map::new();
+        serialize_config(&mut map, &cp, SerializationMode::Standart).unwrap();
+        serde_json::json!({ "master": map }).as_object().unwrap().clone()
+    };
+
+    let prev = get_validator_set();
+    let mut list = prev.list().to_vec();
+    list.remove(1);
+    list[0].weight = 6;
+    list[0].adnl_addr = Some(UInt256::from([1; 32]));
+    let key = SigPubKey::from_bytes(
+        &hex::decode("ef0ddf5f98e2ba8f0fc90a056842fe2bda44fd7bdb2a31fade2d0972b68b9413").unwrap()
+    ).unwrap();
+    list.push(ValidatorDescr::with_params(key, 5, None, None));
+    let next = ValidatorSet::new(100, 200, 1, list).unwrap();
+
+    let diff = validator_set_diff_from_json(
+        &key_block_json(prev.clone()),
+        &key_block_json(next.clone()),
+        SerializationMode::Standart,
+    ).unwrap();
+    assert_eq!(diff, crate::serialize_validator_set_diff(&prev, &next, SerializationMode::Standart).unwrap());
+
+    let public_keys = |field: &str| diff[field].as_array().unwrap().iter()
+        .map(|v| v["public_key"].as_str().unwrap().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(public_keys("added"), vec![hex::encode(next.list()[1].public_key.as_slice())]);
+    assert_eq!(public_keys("removed"), vec![hex::encode(prev.list()[1].public_key.as_slice())]);
+    assert_eq!(public_keys("weight_changed"), vec![hex::encode(prev.list()[0].public_key.as_slice())]);
+    assert_eq!(public_keys("adnl_changed"), vec![hex::encode(prev.list()[0].public_key.as_slice())]);
+    assert_eq!(diff["adnl_changed"][0]["prev_adnl_addr"], Value::Null);
+    assert_eq!(diff["rotation"], 50.0);
+    assert_eq!(diff["utime_since"], 100);
+}
+
+#[test]
+fn test_parse_p12_defaults() {
+    let json = serde_json::json!({
+        "p12": [{
+            "workchain_id": 0,
+            "enabled_since": 1,
+            "min_split": 2,
+            "max_split": 8,
+            "active": true,
+            "accept_msgs": true,
+            "zerostate_root_hash": "55b13f6d0e1d0c34c9c2160f6f918e92d82bf9ddcf8de2e4c94a3fdf39d15446",
+            "zerostate_file_hash": "ee0bedfe4b32761fb35e9e1d8818ea720cad1a0e7b4d2ed673c488e72e910342",
+            "vm_version": 0,
+            "vm_mode": 0
+        }]
+    });
+    let config = parse_config(json.as_object().unwrap()).unwrap();
+    let descr = config.workchains().unwrap().get(&0).unwrap().unwrap();
+    assert_eq!(descr.actual_min_split(), 2);
+    assert_eq!(descr.flags, 0);
+    assert_eq!(descr.version, 0);
+    assert!(matches!(descr.format, WorkchainFormat::Basic(_)));
+
+    // present but malformed fields are not replaced with defaults
+    for (name, value) in [
+        ("flags", serde_json::json!("qwe")),
+        ("flags", serde_json::json!(65536)),
+        ("version", serde_json::json!(-1)),
+        ("basic", serde_json::json!(1)),
+        ("actual_min_split", serde_json::json!(256)),
+        ("vm_mode", serde_json::json!(-1)),
+        ("enabled_since", serde_json::json!(-1)),
+    ] {
+        let mut json = json.clone();
+        json["p12"][0][name] = value;
+        assert!(parse_config(json.as_object().unwrap()).is_err(), "{}", name);
+    }
+
+    let mut json = json;
+    json["p12"][0]["workchain_id"] = (-2).into();
+    let config = parse_config(json.as_object().unwrap()).unwrap();
+    let doc = crate::db_serialize_config(&config, SerializationMode::Standart).unwrap();
+    assert_eq!(doc["config"]["p12"][0]["workchain_id"], -2);
+    assert_eq!(parse_config(doc["config"].as_object().unwrap()).unwrap(), config);
+
+    // legacy documents have the workchain id as the unsigned dictionary key
+    json["p12"][0]["workchain_id"] = 4294967294u32.into();
+    assert_eq!(parse_config(json.as_object().unwrap()).unwrap(), config);
+    json["p12"][0]["workchain_id"] = 4294967296u64.into();
+    assert!(parse_config(json.as_object().unwrap()).is_err());
+}
+
+#[test]
+fn test_raw_config_param_round_trip() {
+    let config = prepare_config_params();
+    let boc = crate::export_config_param_boc(&config, 12).unwrap().unwrap();
+    assert!(crate::export_config_param_boc(&config, 100).unwrap().is_none());
+
+    let mut imported = ConfigParams::new();
+    let param = serde_json::json!({ "number": 12, "boc": boc });
+    import_config_param(&mut imported, param.as_object().unwrap()).unwrap();
+    assert_eq!(imported.config(12).unwrap(), config.config(12).unwrap());
+
+    let mut builder = BuilderData::new();
+    builder.append_u32(0xDEADBEEF).unwrap();
+    let boc = base64_encode(write_boc(&builder.into_cell().unwrap()).unwrap());
+    let param = serde_json::json!({ "number": 100, "boc": boc });
+    import_config_param(&mut imported, param.as_object().unwrap()).unwrap();
+    assert_eq!(crate::export_config_param_boc(&imported, 100).unwrap(), Some(boc.clone()));
+
+    let mut map = Map::new();
+    serialize_config(&mut map, &imported, SerializationMode::Standart).unwrap();
+    assert_eq!(map["unknown_config"], serde_json::json!([{ "number": 100, "boc": boc }]));
+}
+
+#[test]
+fn test_parse_config_ignored_fields() {
+    let json = serde_json::json!({
+        "p1": "3333333333333333333333333333333333333333333333333333333333333333",
+        "p6": {
+            "mint_new_price": "0x1",
+            "mint_add_price": "0x2",
+            "mint_add_prise": "0x3",
+        },
+        "p1000": 1,
+    });
+    let (config, ignored) = parse_config_with_ignored_fields(json.as_object().unwrap()).unwrap();
+    assert_eq!(config, parse_config(json.as_object().unwrap()).unwrap());
+    assert_eq!(ignored, vec!["root/p6/mint_add_prise", "root/p1000"]);
+}
+
+#[test]
+fn test_verify_account_proof() {
+    let account = ever_block::generate_test_account_by_init_code_hash(false);
+    let account_id = account.get_id().unwrap();
+    let mut accounts = ShardAccounts::default();
+    accounts.set_builder_serialized(
+        account_id,
+        &ShardAccount::with_params(&account, UInt256::ZERO, 0).unwrap().write_to_new_cell().unwrap(),
+        &account.aug().unwrap()
+    ).unwrap();
+    let mut state = ShardStateUnsplit::with_ident(ShardIdent::masterchain());
+    state.write_accounts(&accounts).unwrap();
+    let state_cell = state.serialize().unwrap();
+    let proof = MerkleProof::create(&state_cell, |_| true).unwrap();
+
+    let set = crate::AccountSerializationSet {
+        boc: account.write_to_bytes().unwrap(),
+        account,
+        prev_code_hash: None,
+        boc1: None,
+        proof: Some(proof.write_to_bytes().unwrap()),
+    };
+    let mut json = crate::db_serialize_account("id", &set).unwrap();
+    assert_eq!(verify_account_proof(&json, &state_cell.repr_hash()).unwrap(), AccountProofVerdict::Proven);
+    assert_eq!(
+        verify_account_proof(&json, &UInt256::default()).unwrap(),
+        AccountProofVerdict::StateRootMismatch
+    );
+    json.insert("proof".to_string(), Value::Null);
+    assert_eq!(verify_account_proof(&json, &state_cell.repr_hash()).unwrap(), AccountProofVerdict::NoProof);
+    json.remove("proof");
+    assert_eq!(verify_account_proof(&json, &state_cell.repr_hash()).unwrap(), AccountProofVerdict::NoProof);
+    json.insert("proof".to_string(), "not base64".into());
+    assert!(verify_account_proof(&json, &state_cell.repr_hash()).is_err());
+}
+
+#[test]
+fn test_parse_shard_accounts() {
+    let account = ever_block::generate_test_account_by_init_code_hash(false);
+    let account_id = account.get_id().unwrap();
+    let set = crate::AccountSerializationSet {
+        boc: account.write_to_bytes().unwrap(),
+        account,
+        prev_code_hash: None,
+        boc1: None,
+        proof: None,
+    };
+    let json = crate::db_serialize_account("id", &set).unwrap();
+
+    let accounts = parse_shard_accounts(&[("account.json".to_string(), json.clone())]).unwrap();
+    assert!(accounts.account(&account_id).unwrap().is_some());
+
+    let mut broken = json.clone();
+    broken.insert("boc".to_string(), "not base64".into());
+    let err = parse_shard_accounts(&[
+        ("account.json".to_string(), json),
+        ("broken.json".to_string(), broken.clone()),
+        ("broken2.json".to_string(), broken),
+    ]).expect_err("must generate error").to_string();
+    assert!(err.starts_with("2 account documents can't be parsed"));
+    assert!(err.contains("\nbroken.json: "));
+    assert!(err.contains("\nbroken2.json: "));
+}
+
+#[test]
+fn test_bridge_config_params() {
+    let json = serde_json::json!({
+        "p71": {
+            "bridge_address": "1111111111111111111111111111111111111111111111111111111111111111",
+            "oracle_multisig_address": "2222222222222222222222222222222222222222222222222222222222222222",
+            "oracles": [
+                {
+                    "address": "3333333333333333333333333333333333333333333333333333333333333333",
+                    "public_key": "4444444444444444444444444444444444444444444444444444444444444444"
+                },
+                {
+                    "address": "5555555555555555555555555555555555555555555555555555555555555555",
+                    "public_key": "6666666666666666666666666666666666666666666666666666666666666666"
+                }
+            ],
+            "external_chain_address": "7777777777777777777777777777777777777777777777777777777777777777"
+        }
+    });
+    let config = parse_config_with(json.as_object().unwrap(), &[&crate::BridgeConfigParams]).unwrap();
+    assert!(parse_config(json.as_object().unwrap()).unwrap().config_params.is_empty());
+
+    let mut map = Map::new();
+    crate::serialize_config_with(&mut map, &config, SerializationMode::Standart, &[&crate::BridgeConfigParams]).unwrap();
+    assert_eq!(map["config"], json);
+    assert!(map.get("unknown_config").is_none());
+
+    let mut map = Map::new();
+    serialize_config(&mut map, &config, SerializationMode::Standart).unwrap();
+    assert_eq!(map["unknown_config"][0]["number"], 71);
+}
+
+#[test]
+fn test_cell_pool() {
+    let boc = |value: u32| {
+        let mut builder = BuilderData::new();
+        builder.append_u32(value).unwrap();
+        let cell = builder.into_cell().unwrap();
+        (base64_encode(write_boc(&cell).unwrap()), cell.repr_hash().as_hex_string())
+    };
+    let (a, a_hash) = boc(1);
+    let (b, b_hash) = boc(2);
+    let (c, _) = boc(3);
+    let json = serde_json::json!({
+        "id": "1",
+        "messages": [
+            { "body": a, "code": b },
+            { "body": a, "data": c },
+        ],
+        "code": b,
+        "value": a,
+    }).as_object().unwrap().clone();
+
+    let pooled = crate::pool_repeated_cells(json.clone()).unwrap();
+    assert_eq!(pooled["cell_pool"], serde_json::json!({ a_hash.clone(): a, b_hash.clone(): b }));
+    // the pool is ordered by the first appearance
+    let hashes = pooled["cell_pool"].as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+    assert_eq!(hashes, [a_hash.clone(), b_hash.clone()]);
+    assert_eq!(serde_json::to_string(&crate::pool_repeated_cells(json.clone()).unwrap()).unwrap(), serde_json::to_string(&pooled).unwrap());
+    assert_eq!(pooled["messages"][0]["body"], serde_json::json!({ "pooled": a_hash }));
+    assert_eq!(pooled["code"], serde_json::json!({ "pooled": b_hash }));
+    assert_eq!(pooled["messages"][1]["data"], c);
+    assert_eq!(pooled["value"], a);
+
+    assert_eq!(inline_pooled_cells(pooled.clone()).unwrap(), json);
+    assert_eq!(crate::pool_repeated_cells(pooled["messages"][1].as_object().unwrap().clone()).unwrap().get("cell_pool"), None);
+
+    let mut broken = pooled;
+    broken["cell_pool"].as_object_mut().unwrap().remove(&b_hash);
+    assert!(inline_pooled_cells(broken).is_err());
+
+    // repeated strings which are not BOCs are not pooled
+    let mut json = json;
+    json.insert("data".to_string(), "not a boc".into());
+    json["messages"][1]["data"] = "not a boc".into();
+    let pooled = crate::pool_repeated_cells(json.clone()).unwrap();
+    assert_eq!(pooled["data"], "not a boc");
+    assert_eq!(pooled["cell_pool"].as_object().unwrap().len(), 2);
+    assert_eq!(inline_pooled_cells(pooled).unwrap(), json);
+}
+
+#[test]
+fn test_parse_transaction_and_message() {
+    let boc = std::fs::read("src/tests/data/18AFCDD25BE0989CE516504263EB351818A0FF8F6AB3689501C8E3B767EF413C.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let mut transactions = Vec::new();
+    block.read_extra().unwrap().read_account_blocks().unwrap().iterate_objects(|account_block| {
+        account_block.transaction_iterate_full(|_, cell, _| {
+            transactions.push(cell);
+            Ok(true)
+        })?;
+        Ok(true)
+    }).unwrap();
+    assert!(!transactions.is_empty());
+
+    for cell in transactions {
+        let transaction = Transaction::construct_from_cell(cell.clone()).unwrap();
+        let set = crate::TransactionSerializationSet {
+            transaction: transaction.clone(),
+            id: cell.repr_hash(),
+            status: crate::TransactionProcessingStatus::Finalized,
+            block_id: None,
+            workchain_id: 0,
+            boc: write_boc(&cell).unwrap(),
+            proof: None,
+        };
+        for mode in [SerializationMode::Standart, SerializationMode::QServer] {
+            let doc = crate::db_serialize_transaction_ex("id", &set, mode).unwrap();
+            assert_eq!(parse_transaction(&doc).unwrap(), transaction);
+        }
+
+        if let Some(message) = transaction.read_in_msg().unwrap() {
+            let cell = message.serialize().unwrap();
+            let set = crate::MessageSerializationSet {
+                message: message.clone(),
+                id: cell.repr_hash(),
+                block_id: None,
+                transaction_id: Some(set.id.clone()),
+                transaction_now: Some(transaction.now()),
+                status: crate::MessageProcessingStatus::Finalized,
+                boc: write_boc(&cell).unwrap(),
+                proof: None,
+            };
+            for mode in [SerializationMode::Standart, SerializationMode::QServer] {
+                let mut doc = crate::db_serialize_message_ex("id", &set, mode).unwrap();
+                assert_eq!(parse_message(&doc).unwrap(), message);
+
+                // rebuilt from the fields, the cell layout may differ from the original one
+                doc.remove("boc");
+                doc.remove("id");
+                let rebuilt = parse_message(&doc).unwrap();
+                assert_eq!(rebuilt.header(), message.header());
+                assert_eq!(rebuilt.state_init(), message.state_init());
+                assert_eq!(
+                    rebuilt.body().map(|body| body.into_cell().repr_hash()),
+                    message.body().map(|body| body.into_cell().repr_hash()),
+                );
+                doc.insert("id".to_string(), UInt256::default().as_hex_string().into());
+                assert!(parse_message(&doc).is_err());
+            }
+        }
+
+        let mut doc = crate::db_serialize_transaction("id", &set).unwrap();
+        doc.insert("id".to_string(), "qwe".into());
+        assert_eq!(DeserializationError::code_of(&parse_transaction(&doc).unwrap_err()), Some("E003"));
+        doc.remove("id");
+        assert_eq!(parse_transaction(&doc).unwrap(), transaction);
+        doc.insert("lt".to_string(), (-1).into());
+        doc.remove("lt_dec");
+        assert_eq!(DeserializationError::code_of(&parse_transaction(&doc).unwrap_err()), Some("E003"));
+        doc.insert("lt".to_string(), "0x1".into());
+        assert!(parse_transaction(&doc).is_err());
+        doc.remove("boc");
+        assert_eq!(DeserializationError::code_of(&parse_transaction(&doc).unwrap_err()), Some("E006"));
+    }
+}
+
+#[test]
+fn test_parse_nested_shard_hashes() {
+    let boc = std::fs::read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let set = crate::BlockSerializationSet {
+        id: block.hash().unwrap(),
+        block,
+        status: crate::BlockProcessingStatus::Finalized,
+        boc,
+    };
+    let mut doc = crate::db_serialize_block("id", &set).unwrap();
+    let flat = parse_shard_hashes(&doc["master"]["shard_hashes"]).unwrap();
+    assert!(!flat.is_empty());
+
+    crate::nest_shard_hashes(&mut doc).unwrap();
+    let nested = &doc["master"]["shard_hashes"];
+    assert!(nested["0"].is_array());
+    assert!(nested["0"][0].get("workchain_id").is_none());
+    assert_eq!(parse_shard_hashes(nested).unwrap(), flat);
+
+    let mut shard_hashes = serde_json::json!([{ "workchain_id": 1i64 << 32, "shard": "8000000000000000", "descr": {} }]);
+    assert!(parse_shard_hashes(&shard_hashes).is_err());
+    let mut doc = serde_json::json!({ "master": { "shard_hashes": shard_hashes.take() } });
+    assert!(crate::nest_shard_hashes(doc.as_object_mut().unwrap()).is_err());
+}
+
+#[test]
+fn test_parse_account_without_boc() {
+    let account = ever_block::generate_test_account_by_init_code_hash(false);
+    let set = crate::AccountSerializationSet {
+        boc: account.write_to_bytes().unwrap(),
+        account: account.clone(),
+        prev_code_hash: None,
+        boc1: None,
+        proof: None,
+    };
+    let mut doc = crate::db_serialize_account("id", &set).unwrap();
+    assert_eq!(parse_account(&doc).unwrap(), account);
+
+    doc.insert("boc".to_string(), "not a boc".into());
+    assert!(parse_account(&doc).is_err());
+    doc.insert("boc".to_string(), Value::Null);
+    assert_eq!(parse_account(&doc).unwrap().get_addr(), account.get_addr());
+
+    doc.remove("boc");
+    let parsed = parse_account(&doc).unwrap();
+    assert_eq!(parsed.get_addr(), account.get_addr());
+    assert_eq!(parsed.status(), account.status());
+    assert_eq!(parsed.balance(), account.balance());
+    assert_eq!(parsed.last_tr_time(), account.last_tr_time());
+    assert_eq!(parsed.state_init(), account.state_init());
+    assert_eq!(parsed.init_code_hash(), account.init_code_hash());
+
+    // present but malformed fields are not skipped
+    for (name, value) in [
+        ("split_depth", serde_json::json!("qwe")),
+        ("split_depth", serde_json::json!(32)),
+        ("tick", serde_json::json!(1)),
+        ("due_payment", serde_json::json!("qwe")),
+        ("last_paid", serde_json::json!(-1)),
+        ("init_code_hash", serde_json::json!("qwe")),
+        ("code", serde_json::json!("qwe")),
+    ] {
+        let mut doc = doc.clone();
+        doc.insert(name.to_string(), value);
+        assert!(parse_account(&doc).is_err(), "{}", name);
+    }
+
+    doc.insert("acc_type".to_string(), 3.into());
+    assert!(parse_account(&doc).is_err());
+}
+
+#[test]
+fn test_round_trip_extras() {
+    let mut set = crate::samples::sample_transaction_set().unwrap();
+    set.block_id = Some(UInt256::from([7; 32]));
+    let mut doc = crate::db_serialize_transaction_ex("id", &set, SerializationMode::QServer).unwrap();
+    doc.insert("annotation".to_string(), serde_json::json!({ "source": "indexer" }));
+    doc.get_mut("compute").and_then(Value::as_object_mut).map(|compute| {
+        compute.insert("note".to_string(), "slow".into())
+    });
+
+    let (transaction, extras) = parse_transaction_with_extras(&doc).unwrap();
+    assert_eq!(transaction, set.transaction);
+    assert_eq!(Value::from(extras.clone()), serde_json::json!({
+        "annotation": { "source": "indexer" },
+        "compute": { "note": "slow" },
+    }));
+
+    let mut reserialized = crate::db_serialize_transaction("id", &set).unwrap();
+    attach_extra_fields(&mut reserialized, &extras);
+    assert_eq!(reserialized["annotation"], doc["annotation"]);
+    assert_eq!(reserialized["compute"]["note"], "slow");
+    assert_eq!(reserialized["compute"]["compute_type"], doc["compute"]["compute_type"]);
+
+    let mut message_set = crate::samples::sample_message_set().unwrap();
+    message_set.block_id = Some(UInt256::from([7; 32]));
+    message_set.transaction_id = Some(UInt256::from([8; 32]));
+    for mode in [SerializationMode::Standart, SerializationMode::QServer] {
+        let (_, extras) = parse_message_with_extras(
+            &crate::db_serialize_message_ex("id", &message_set, mode).unwrap()
+        ).unwrap();
+        assert!(extras.is_empty(), "{:?}", extras);
+        let (_, extras) = parse_account_with_extras(
+            &crate::db_serialize_account_ex("id", &crate::samples::sample_account_set().unwrap(), mode).unwrap()
+        ).unwrap();
+        assert!(extras.is_empty(), "{:?}", extras);
+    }
+}
+
+#[test]
+fn test_parse_value_flow() {
+    let boc = std::fs::read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let mut value_flow = block.read_value_flow().unwrap();
+    value_flow.copyleft_rewards.set(&UInt256::from([1; 32]), &Grams::from(1_000_000_000u64)).unwrap();
+    value_flow.copyleft_rewards.set(&UInt256::from([2; 32]), &Grams::from(7u64)).unwrap();
+    let doc = crate::db_serialize_value_flow(&value_flow, SerializationMode::Standart).unwrap();
+    assert_eq!(doc["copyleft_rewards"].as_array().unwrap().len(), 2);
+    assert_eq!(parse_value_flow(doc.as_object().unwrap()).unwrap(), value_flow);
+
+    // extra currencies in the object layout
+    value_flow.minted.other.set(&7, &VarUInteger32::from_two_u128(0, 100).unwrap()).unwrap();
+    let options = SerializationOptions::new(SerializationMode::Standart)
+        .with_other_currencies_layout(OtherCurrenciesLayout::Object);
+    let doc = crate::db_serialize_value_flow(&value_flow, options).unwrap();
+    assert_eq!(doc["minted_other"]["7"], "100");
+    assert_eq!(parse_value_flow(doc.as_object().unwrap()).unwrap(), value_flow);
+}
+
+#[test]
+fn test_diff_states() {
+    let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json").unwrap();
+    let old = serde_json::from_str::<Map<String, Value>>(&json).unwrap();
+    assert!(diff_states(&old, &old).unwrap().is_empty());
+
+    let mut new = old.clone();
+    let config = new["master"]["config"].as_object_mut().unwrap();
+    config.insert("p1".to_string(), Value::from(UInt256::from([7; 32]).as_hex_string()));
+    config.remove("p2");
+    let accounts = new["accounts"].as_array_mut().unwrap();
+    let removed = accounts.remove(0);
+    let mut added = accounts[0].clone();
+    added["id"] = "0:1111111111111111111111111111111111111111111111111111111111111111".into();
+    accounts.push(added);
+    let changed = accounts[0]["id"].as_str().unwrap().to_string();
+    let old_balance = match &accounts[0]["balance"] {
+        Value::String(balance) => balance.parse::<u128>().unwrap(),
+        balance => balance.as_u64().unwrap() as u128,
+    };
+    accounts[0]["balance"] = (old_balance + 5).to_string().into();
+    accounts[0]["balance_other"] = serde_json::json!([{ "currency": 7, "value": "300" }]);
+    let libraries = new["libraries"].as_array_mut().unwrap();
+    libraries[0]["publishers"] = serde_json::json!([]);
+    let removed_library = libraries.remove(1);
+
+    let diff = diff_states(&old, &new).unwrap();
+    let numbers = diff.config_params.iter().map(|param| param.number).collect::<Vec<_>>();
+    assert_eq!(numbers, [1, 2]);
+    assert_eq!(diff.config_params[1].new, None);
+    assert_eq!(diff.added_accounts, ["0:1111111111111111111111111111111111111111111111111111111111111111"]);
+    assert_eq!(diff.removed_accounts, [removed["id"].as_str().unwrap()]);
+    assert_eq!(diff.balance_deltas, [BalanceDelta {
+        account: changed,
+        old: old_balance,
+        new: old_balance + 5,
+        delta: 5,
+        other: vec![CurrencyDelta {
+            currency: 7,
+            old: "0".to_string(),
+            new: "300".to_string(),
+            delta: "300".to_string(),
+        }],
+    }]);
+    assert!(diff.added_libraries.is_empty());
+    assert_eq!(diff.removed_libraries, [removed_library["hash"].as_str().unwrap()]);
+    assert_eq!(diff.changed_libraries, [new["libraries"][0]["hash"].as_str().unwrap()]);
+    assert!(serde_json::to_value(&diff).unwrap()["config_params"].is_array());
+}
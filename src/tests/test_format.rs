@@ -0,0 +1,95 @@
+/*
+ * Copyright (C) 2019-2023 EverX. All Rights Reserved.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.  You may obtain a copy of the
+ * License at:
+ *
+ * https://www.ever.dev/licenses
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific EVERX DEV software governing permissions and limitations
+ * under the License.
+ */
+
+use super::*;
+
+#[test]
+fn test_format_numbers() {
+    assert_eq!(u64_to_string(0), "00");
+    assert_eq!(u64_to_string(0x1f4), "21f4");
+    assert_eq!(u128_to_string(0), "000");
+    assert_eq!(u128_to_string(1_000_000_000), "073b9aca00");
+    assert_eq!(shard_to_string(0x8000_0000_0000_0000), "8000000000000000");
+
+    assert_eq!(format_u64(500, SerializationMode::Standart), "21f4");
+    assert_eq!(format_u64(500, SerializationMode::QServer), "0x1f4");
+    assert_eq!(format_u64(500, SerializationMode::Debug), "500");
+    assert_eq!(format_lt(12_000_345, SerializationMode::Debug), "12_345");
+    assert_eq!(format_lt(12_000_345, SerializationMode::QServer), "0xb71c59");
+    assert_eq!(format_grams(1_000_000_000, SerializationMode::QServer), "0x3b9aca00");
+    assert_eq!(format_grams(1_000_000_000, SerializationMode::Debug), "1000000000");
+}
+
+#[test]
+fn test_format_addresses() {
+    assert_eq!(
+        std_address_to_string(-1, &[0x33; 32]),
+        "-1:3333333333333333333333333333333333333333333333333333333333333333"
+    );
+    assert_eq!(address_prefix_to_string(0, 0x8000_0000_0000_0000), "0:8000000000000000");
+}
+
+#[test]
+fn test_capabilities_names() {
+    assert_eq!(
+        capabilities_names(0x2e),
+        ["CapCreateStatsEnabled", "CapBounceMsgBody", "CapReportVersion", "CapShortDequeue"]
+    );
+    assert_eq!(capabilities_names(0x800 | 0x8000), ["CapSetLibCode"]);
+    assert!(capabilities_names(0).is_empty());
+    for (bit, _) in CAPABILITIES {
+        assert_eq!(bit.count_ones(), 1);
+    }
+}
+
+#[test]
+fn test_capabilities_match_global_capabilities() {
+    use ever_block::GlobalCapabilities;
+    macro_rules! check_capabilities {
+        ($($cap:ident),* $(,)?) => {{
+            // no wildcard arm: a capability added to ever-block breaks the build of the test
+            // until it is listed here and in `CAPABILITIES`
+            fn listed(cap: GlobalCapabilities) -> bool {
+                match cap {
+                    GlobalCapabilities::CapNone => false,
+                    $(GlobalCapabilities::$cap => true,)*
+                }
+            }
+            let expected = [$((GlobalCapabilities::$cap as u64, stringify!($cap))),*];
+            assert_eq!(CAPABILITIES, &expected[..]);
+            $(assert!(listed(GlobalCapabilities::$cap));)*
+        }};
+    }
+    check_capabilities!(
+        CapIhrEnabled, CapCreateStatsEnabled, CapBounceMsgBody, CapReportVersion,
+        CapSplitMergeTransactions, CapShortDequeue, CapMbppEnabled, CapFastStorageStat,
+        CapInitCodeHash, CapOffHypercube, CapMycode, CapSetLibCode, CapFixTupleIndexBug, CapRemp,
+        CapDelections, CapFullBodyInBounced, CapStorageFeeToTvm, CapCopyleft, CapIndexAccounts,
+        CapDiff, CapsTvmBugfixes2022, CapWorkchains, CapStcontNewFormat, CapFastStorageStatBugfix,
+        CapResolveMerkleCell, CapSignatureWithId, CapBounceAfterFailedAction, CapGroth16,
+        CapFeeInGasUnits, CapBigCells, CapSuspendedList, CapFastFinality, CapTvmV19, CapSmft,
+        CapNoSplitOutQueue, CapUndeletableAccounts, CapTvmV20, CapDuePaymentFix, CapCommonMessage,
+    );
+}
+
+#[test]
+fn test_hash_format() {
+    let hash = [0xab; 4];
+    assert_eq!(HashFormat::default().format_hash(&hash), "abababab");
+    assert_eq!(HashFormat { prefix: true, uppercase: false }.format_hash(&hash), "0xabababab");
+    assert_eq!(HashFormat { prefix: true, uppercase: true }.format_hash(&hash), "0xABABABAB");
+    assert_eq!(HashFormat { prefix: false, uppercase: true }.format_hash(&hash), "ABABABAB");
+}
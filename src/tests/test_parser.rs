@@ -21,16 +21,24 @@ use crate::{NoTrace, ParsedBlock};
 use serde_json::Map;
 use std::collections::HashMap;
 use std::{fs::read, path::Path};
-use ever_block::{Block, GetRepresentationHash, InMsg, OutMsg};
+use ever_block::{
+    AccountId, AccountStatus, Block, BlockExtra, BlockInfo, CommonMessage, CurrencyCollection,
+    GetRepresentationHash, HashUpdate, InMsg, InternalMessageHeader, MerkleUpdate, Message, OutMsg,
+    Serializable, ShardAccountBlocks, ShardIdent, Transaction, TransactionDescr,
+    TransactionDescrOrdinary, TrBouncePhase, TrBouncePhaseOk, ValueFlow,
+};
 use ever_block::{read_single_root_boc, UInt256};
 
 #[derive(Default)]
 pub struct ParseOptions {
     mc_seq_no: Option<u32>,
+    mc_block_id: Option<BlockIdExt>,
     file_hash: Option<UInt256>,
     blocks: Option<EntryConfig<JsonFieldsReducer>>,
     transactions: Option<EntryConfig<JsonFieldsReducer>>,
     messages: Option<EntryConfig<JsonFieldsReducer>>,
+    account_transactions: Option<EntryConfig<JsonFieldsReducer>>,
+    account_creations: Option<EntryConfig<JsonFieldsReducer>>,
 }
 
 impl ParseOptions {
@@ -41,6 +49,13 @@ impl ParseOptions {
         }
     }
 
+    fn mc_block_id(self: Self, id: BlockIdExt) -> Self {
+        Self {
+            mc_block_id: Some(id),
+            ..self
+        }
+    }
+
     fn sharding(self: Self, blocks: u32, transactions: u32, messages: u32) -> Self {
         fn config(depth: u32) -> Option<EntryConfig<JsonFieldsReducer>> {
             Some(EntryConfig {
@@ -56,6 +71,26 @@ impl ParseOptions {
         }
     }
 
+    fn account_transactions(self: Self, sharding_depth: u32) -> Self {
+        Self {
+            account_transactions: Some(EntryConfig {
+                reducer: None,
+                sharding_depth: Some(sharding_depth),
+            }),
+            ..self
+        }
+    }
+
+    fn account_creations(self: Self) -> Self {
+        Self {
+            account_creations: Some(EntryConfig {
+                reducer: None,
+                sharding_depth: None,
+            }),
+            ..self
+        }
+    }
+
     fn file_hash(self: Self, file_hash: UInt256) -> Self {
         Self {
             file_hash: Some(file_hash),
@@ -77,6 +112,13 @@ fn parse_block(
 ) -> (Vec<u8>, UInt256, ParsedBlock) {
     let in_path = Path::new("src/tests/data").join(file_rel_path);
     let boc = read(in_path.clone()).expect(&format!("Error reading file {:?}", in_path));
+    parse_block_boc(boc, options)
+}
+
+fn parse_block_boc(
+    boc: Vec<u8>,
+    options: Option<ParseOptions>,
+) -> (Vec<u8>, UInt256, ParsedBlock) {
     let cell = read_single_root_boc(&boc).expect("Error deserializing single root BOC");
 
     let block = Block::construct_from_cell(cell.clone()).unwrap();
@@ -92,9 +134,10 @@ fn parse_block(
             .unwrap_or_else(|| UInt256::calc_file_hash(&boc)),
     );
     let mc_seq_no = options.as_ref().map(|x| x.mc_seq_no).flatten();
-    let (blocks, transactions, messages) = options
-        .map(|x| (x.blocks, x.transactions, x.messages))
-        .unwrap_or((None, None, None));
+    let mc_block_id = options.as_ref().and_then(|x| x.mc_block_id.clone());
+    let (blocks, transactions, messages, account_transactions, account_creations) = options
+        .map(|x| (x.blocks, x.transactions, x.messages, x.account_transactions, x.account_creations))
+        .unwrap_or((None, None, None, None, None));
     fn entry_config(
         opt: Option<EntryConfig<JsonFieldsReducer>>,
     ) -> Option<EntryConfig<JsonFieldsReducer>> {
@@ -109,10 +152,15 @@ fn parse_block(
             blocks: entry_config(blocks),
             transactions: entry_config(transactions),
             messages: entry_config(messages),
+            account_transactions,
+            account_creations,
+            economics: None,
             accounts: None,
             proofs: None,
             max_account_bytes_size: None,
             is_node_se: false,
+            sampling: None,
+            context: None,
         },
         None,
     );
@@ -123,8 +171,10 @@ fn parse_block(
                 block: &block,
                 root: &cell,
                 shard_state: None,
+                prev_shard_state: None,
                 data: &boc,
                 mc_seq_no,
+                mc_block_id: mc_block_id.as_ref(),
                 proof: None,
             },
             false,
@@ -133,6 +183,31 @@ fn parse_block(
     (boc, cell.repr_hash(), parsed)
 }
 
+/// Basechain block with the transactions in its account blocks
+fn synthetic_block(transactions: &[Transaction]) -> Vec<u8> {
+    let mut account_blocks = ShardAccountBlocks::default();
+    for transaction in transactions {
+        account_blocks.add_serialized_transaction(transaction, &transaction.serialize().unwrap()).unwrap();
+    }
+    let mut extra = BlockExtra::default();
+    extra.write_account_blocks(&account_blocks).unwrap();
+    let block = Block::with_params(
+        0, BlockInfo::default(), ValueFlow::default(), MerkleUpdate::default(), extra
+    ).unwrap();
+    block.write_to_bytes().unwrap()
+}
+
+/// Ordinary transaction of the account with the inbound message
+fn synthetic_transaction(account_id: [u8; 32], orig_status: AccountStatus, descr: TransactionDescrOrdinary) -> Transaction {
+    let mut transaction = Transaction::with_address_and_status(AccountId::from(account_id), orig_status);
+    transaction.set_logical_time(2_000_001);
+    transaction.set_end_status(AccountStatus::AccStateActive);
+    transaction.write_in_msg(Some(&CommonMessage::Std(crate::samples::sample_message().unwrap()))).unwrap();
+    transaction.write_state_update(&HashUpdate::default()).unwrap();
+    transaction.write_description(&TransactionDescr::Ordinary(descr)).unwrap();
+    transaction
+}
+
 #[test]
 fn test_transaction_code_hash() {
     println!("MA: {:?}", *MINTER_ADDRESS);
@@ -182,6 +257,11 @@ fn test_parse_block() {
 
     assert_eq!(parsed.messages.len(), 2);
     assert_eq!(parsed.transactions.len(), 2);
+    let gen_utime_ms = block.read_info().unwrap().gen_utime_ms();
+    for tr in &parsed.transactions {
+        let now_ms = tr.body["now_ms"].as_u64().unwrap();
+        assert!(now_ms >= gen_utime_ms && now_ms < gen_utime_ms - gen_utime_ms % 1000 + 1000);
+    }
 
     // serialization changes often so check only id's and records count
     let check_parsed_entry = |entry: ParsedEntry, id: UInt256| {
@@ -691,6 +771,24 @@ fn test_reduce_config() {
     }
 }
 
+#[test]
+fn test_message_routing() {
+    let (_, _, parsed) = parse_block(
+        "3FAFAAB7B5D17E439CD9DDEF7EEC3C3BC9D28E7C181BA374218E13599F8F6657.boc",
+        None,
+    );
+    let internal = parsed.messages.iter()
+        .find(|m| m.id == "e3b9e21c18c77c02a5e21445de1a69cdb01a8e1dd84601b1ae31b8e568b60153")
+        .unwrap();
+    assert_eq!(internal.body["ihr"], false);
+    assert!(internal.body["hop_count"].is_u64());
+    for message in &parsed.messages {
+        if message.body["msg_type"] == 1 {
+            assert!(message.body.get("ihr").is_none());
+        }
+    }
+}
+
 #[test]
 fn test_transaction_id_in_msg() {
     let (_, _, parsed) = parse_block(
@@ -734,3 +832,524 @@ fn test_transaction_id_in_msg() {
         );
     }
 }
+
+#[test]
+fn test_interpolate_now_ms() {
+    assert_eq!(interpolate_now_ms(1_000_500, 100, 200, 100), 1_000_500);
+    assert_eq!(interpolate_now_ms(1_000_500, 100, 200, 150), 1_000_749);
+    assert_eq!(interpolate_now_ms(1_000_500, 100, 200, 200), 1_000_999);
+    assert_eq!(interpolate_now_ms(1_000_500, 100, 100, 100), 1_000_500);
+    assert_eq!(interpolate_now_ms(1_000_000, 100, 200, 300), 1_000_999);
+}
+
+#[test]
+fn test_sampling() {
+    let entry = |id: &str| {
+        let mut body = Map::new();
+        body.insert("id".to_owned(), id.into());
+        crate::ParsedEntry::new(body, None).unwrap()
+    };
+    let mut parsed = ParsedBlock::new();
+    parsed.transactions = vec![
+        entry("00000000000000000000000000000000000000000000000000000000000000a0"),
+        entry("00000000000000000000000000000000000000000000000000000000000000a1"),
+        entry("00000000000000000000000000000000000000000000000000000000000000a2"),
+    ];
+    parsed.accounts = vec![
+        entry("0:00000000000000000000000000000000000000000000000000000000000000b3"),
+        entry("-1:00000000000000000000000000000000000000000000000000000000000000b4"),
+    ];
+    parsed.messages = vec![entry("not a hash")];
+    parsed.sample(2);
+
+    assert_eq!(
+        parsed.counts,
+        Some(crate::ParsedCounts { accounts: 2, transactions: 3, messages: 1 })
+    );
+    let ids = |entries: &Vec<crate::ParsedEntry>| entries.iter().map(|e| e.id.clone()).collect::<Vec<_>>();
+    assert_eq!(
+        ids(&parsed.transactions),
+        vec![
+            "00000000000000000000000000000000000000000000000000000000000000a0",
+            "00000000000000000000000000000000000000000000000000000000000000a2",
+        ]
+    );
+    assert_eq!(ids(&parsed.accounts), vec!["-1:00000000000000000000000000000000000000000000000000000000000000b4"]);
+    assert_eq!(parsed.messages.len() as u64, 1 - crate::block_parser::block::sample_key("not a hash") % 2);
+
+    // the tail of 16 bytes is not on a char boundary
+    let id = "ключ сообщения1";
+    assert!(id.get(id.len() - 16..).is_none());
+    assert_eq!(crate::block_parser::block::sample_key(id), crate::block_parser::block::sample_key(id));
+}
+
+#[test]
+fn test_ingestion_metadata() {
+    let time = std::time::UNIX_EPOCH + std::time::Duration::from_millis(1_700_000_000_123);
+    let context = crate::ParserContext::new(Some("indexer-1".to_owned()))
+        .with_clock(crate::FixedClock(time));
+    let metadata = context.metadata();
+    assert_eq!(metadata["parsed_at"], 1_700_000_000_123u64);
+    assert_eq!(metadata["parser_version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(metadata["host"], "indexer-1");
+    assert!(crate::ParserContext::new(None).metadata().get("host").is_none());
+
+    let mut body = Map::new();
+    body.insert("id".to_owned(), "a0".into());
+    let mut parsed = ParsedBlock::new();
+    parsed.block = Some(crate::ParsedEntry::new(body.clone(), None).unwrap());
+    parsed.messages = vec![crate::ParsedEntry::new(body, None).unwrap()];
+    parsed.insert_metadata(&metadata);
+    assert_eq!(parsed.block.unwrap().body["ingestion"], serde_json::Value::from(metadata.clone()));
+    assert_eq!(parsed.messages[0].body["ingestion"]["parsed_at"], 1_700_000_000_123u64);
+}
+
+#[test]
+fn test_chunks() {
+    let entry = |id: &str, size: usize| {
+        let mut body = Map::new();
+        body.insert("id".to_owned(), id.into());
+        body.insert("boc".to_owned(), "a".repeat(size).into());
+        crate::ParsedEntry::new(body, None).unwrap()
+    };
+    let mut parsed = ParsedBlock::new();
+    parsed.block = Some(entry("b", 10));
+    parsed.transactions = (0..5).map(|i| entry(&format!("t{}", i), 10)).collect();
+    parsed.messages = vec![entry("m0", 10), entry("m1", 1000), entry("m2", 10)];
+
+    let limits = crate::ChunkLimits { max_entries: 2, max_bytes: 100 };
+    let mut chunks = parsed.into_chunks(limits);
+    let mut result = vec![];
+    while let Some(chunk) = chunks.next_chunk().unwrap() {
+        assert!(chunk.entries.len() == 1 || chunk.bytes <= limits.max_bytes);
+        let ids = chunk.entries.iter().map(|e| e.id.clone()).collect::<Vec<_>>();
+        result.push((chunk.kind, ids.join(",")));
+    }
+    assert!(chunks.next_chunk().unwrap().is_none());
+    use crate::ParsedEntryKind::*;
+    assert_eq!(
+        result,
+        vec![
+            (Block, "b".to_owned()),
+            (Transaction, "t0,t1".to_owned()),
+            (Transaction, "t2,t3".to_owned()),
+            (Transaction, "t4".to_owned()),
+            (Message, "m0".to_owned()),
+            (Message, "m1".to_owned()),
+            (Message, "m2".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_chunked() {
+    let boc = read("src/tests/data/3FAFAAB7B5D17E439CD9DDEF7EEC3C3BC9D28E7C181BA374218E13599F8F6657.boc").unwrap();
+    let cell = read_single_root_boc(&boc).unwrap();
+    let block = Block::construct_from_cell(cell.clone()).unwrap();
+    let info = block.read_info().unwrap();
+    let id = BlockIdExt::with_params(info.shard().clone(), info.seq_no(), cell.repr_hash(), UInt256::calc_file_hash(&boc));
+    let mut config = BlockParserConfig::<JsonFieldsReducer>::default();
+    config.blocks = Some(EntryConfig { reducer: None, sharding_depth: None });
+    config.transactions = Some(EntryConfig { reducer: None, sharding_depth: None });
+    config.messages = Some(EntryConfig { reducer: None, sharding_depth: None });
+    let parser = BlockParser::<NoTrace, JsonFieldsReducer>::new(config, None);
+
+    let parsed = parser.parse(ParsingBlock::new(&id, &block, &cell, &boc), false).unwrap();
+    let mut expected = vec![];
+    for (kind, entries) in [
+        (crate::ParsedEntryKind::Block, parsed.block.into_iter().collect::<Vec<_>>()),
+        (crate::ParsedEntryKind::Transaction, parsed.transactions),
+        (crate::ParsedEntryKind::Message, parsed.messages),
+    ] {
+        expected.extend(entries.into_iter().map(|entry| (kind, entry.id, entry.body)));
+    }
+
+    let limits = crate::ChunkLimits { max_entries: 1, max_bytes: usize::MAX };
+    let chunks = parser.parse_chunked(ParsingBlock::new(&id, &block, &cell, &boc), false, limits).unwrap();
+    let mut result = vec![];
+    for chunk in chunks {
+        let chunk = chunk.unwrap();
+        result.extend(chunk.entries.into_iter().map(|entry| (chunk.kind, entry.id, entry.body)));
+    }
+    assert_eq!(result.len(), 5);
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn test_parsed_block_serde() {
+    let (_, _, parsed) = parse_block(
+        "3FAFAAB7B5D17E439CD9DDEF7EEC3C3BC9D28E7C181BA374218E13599F8F6657.boc",
+        None,
+    );
+    let check = |restored: &ParsedBlock| {
+        assert_eq!(restored.block.as_ref().unwrap().body, parsed.block.as_ref().unwrap().body);
+        assert_eq!(restored.transactions.len(), parsed.transactions.len());
+        for (restored, entry) in restored.messages.iter().zip(&parsed.messages) {
+            assert_eq!(restored.id, entry.id);
+            assert_eq!(restored.body, entry.body);
+            assert_eq!(restored.partition, entry.partition);
+        }
+    };
+    let json = serde_json::to_string(&parsed).unwrap();
+    check(&serde_json::from_str::<ParsedBlock>(&json).unwrap());
+    #[cfg(feature = "cbor")]
+    check(&ParsedBlock::from_cbor(&parsed.to_cbor().unwrap()).unwrap());
+}
+
+#[test]
+fn test_account_transactions() {
+    let (_, block_id, parsed) = parse_block(
+        "3FAFAAB7B5D17E439CD9DDEF7EEC3C3BC9D28E7C181BA374218E13599F8F6657.boc",
+        Some(ParseOptions::default().account_transactions(2)),
+    );
+    assert!(!parsed.account_transactions.is_empty());
+    let mut tr_count = 0;
+    for group in &parsed.account_transactions {
+        let account_addr = group.body["account_addr"].as_str().unwrap();
+        assert_eq!(group.id, format!("{}/{}", account_addr, block_id.as_hex_string()));
+        assert_eq!(group.body["block_id"], block_id.as_hex_string());
+        assert!(group.partition.unwrap() < 4);
+        for tr in group.body["transactions"].as_array().unwrap() {
+            let tr_id = tr["transaction_id"].as_str().unwrap();
+            let transaction = parsed.transactions.iter().find(|x| x.id == tr_id).unwrap();
+            assert_eq!(transaction.body["account_addr"], account_addr);
+            tr_count += 1;
+        }
+    }
+    assert_eq!(tr_count, parsed.transactions.len());
+
+    let (_, _, parsed) = parse_block(
+        "3FAFAAB7B5D17E439CD9DDEF7EEC3C3BC9D28E7C181BA374218E13599F8F6657.boc",
+        None,
+    );
+    assert!(parsed.account_transactions.is_empty());
+}
+
+#[test]
+fn test_account_creations() {
+    let created_account = synthetic_transaction(
+        [0x22; 32], AccountStatus::AccStateNonexist, TransactionDescrOrdinary::default()
+    );
+    let active_account = synthetic_transaction(
+        [0x33; 32], AccountStatus::AccStateActive, TransactionDescrOrdinary::default()
+    );
+    let mut observed = 0;
+    for boc in [
+        read("src/tests/data/3FAFAAB7B5D17E439CD9DDEF7EEC3C3BC9D28E7C181BA374218E13599F8F6657.boc").unwrap(),
+        read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap(),
+        synthetic_block(&[created_account, active_account]),
+    ] {
+        let (_, block_id, parsed) = parse_block_boc(boc, Some(ParseOptions::default().account_creations()));
+        observed += parsed.account_creations.len();
+        let created = parsed.transactions.iter()
+            .filter(|tr| tr.body["orig_status"] == 3 && [0, 1].contains(&tr.body["end_status"].as_i64().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(created.len(), parsed.account_creations.len());
+        for (tr, creation) in created.iter().zip(&parsed.account_creations) {
+            assert_eq!(creation.id, tr.body["account_addr"]);
+            assert_eq!(creation.body["transaction_id"], tr.body["id"]);
+            assert_eq!(creation.body["created_in_block"], block_id.as_hex_string());
+            assert_eq!(creation.body["funding_msg_id"], tr.body["in_msg"]);
+        }
+    }
+    assert!(observed >= 1);
+}
+
+#[test]
+fn test_transaction_in_msg_values() {
+    let (_, _, parsed) = parse_block(
+        "89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc", None
+    );
+    let messages = parsed.messages.iter()
+        .map(|msg| (msg.id.as_str(), &msg.body))
+        .collect::<HashMap<_, _>>();
+    let mut checked = 0;
+    for tr in &parsed.transactions {
+        let Some(msg) = tr.body.get("in_msg").and_then(|id| messages.get(id.as_str().unwrap())) else {
+            continue
+        };
+        assert_eq!(tr.body["in_msg_src"], msg["src"]);
+        if msg["msg_type"] == 0 {
+            assert_eq!(tr.body["in_msg_value"], msg["value"]);
+            assert_eq!(tr.body["in_msg_bounce"], msg["bounce"]);
+        } else {
+            assert!(tr.body.get("in_msg_value").is_none());
+        }
+        checked += 1;
+    }
+    assert!(checked > 0);
+}
+
+#[test]
+fn test_transaction_phase_flags() {
+    let (_, _, parsed) = parse_block(
+        "89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc", None
+    );
+    let mut checked = 0;
+    for tr in &parsed.transactions {
+        let Some(compute) = tr.body.get("compute") else {
+            assert!(tr.body.get("skipped_compute").is_none());
+            continue
+        };
+        let skipped = compute["compute_type"] == 0;
+        assert_eq!(tr.body["skipped_compute"], skipped);
+        assert_eq!(tr.body["aborted_compute"], !skipped && compute["success"] == false);
+        let failed_action = tr.body.get("action").map_or(false, |action| action["success"] == false);
+        assert_eq!(tr.body["failed_action"], failed_action);
+        checked += 1;
+    }
+    assert!(checked > 0);
+}
+
+#[test]
+fn test_account_code_changed() {
+    let parser = BlockParser::<NoTrace, JsonFieldsReducer>::new(
+        BlockParserConfig {
+            blocks: None,
+            transactions: None,
+            messages: None,
+            account_transactions: None,
+            account_creations: None,
+            economics: None,
+            accounts: Some(EntryConfig { reducer: None, sharding_depth: None }),
+            proofs: None,
+            max_account_bytes_size: None,
+            is_node_se: false,
+            sampling: None,
+            context: None,
+        },
+        None,
+    );
+    let account = ever_block::generate_test_account_by_init_code_hash(false);
+    let code_hash = account.get_code_hash().unwrap();
+
+    let entry = parser.prepare_account_entry(account.clone(), None, None).unwrap();
+    assert!(entry.body.get("code_changed").is_none());
+
+    let entry = parser.prepare_account_entry(account.clone(), Some(code_hash.clone()), None).unwrap();
+    assert_eq!(entry.body["prev_code_hash"], code_hash.as_hex_string());
+    assert_eq!(entry.body["code_changed"], false);
+
+    let entry = parser.prepare_account_entry(account.clone(), Some(UInt256::default()), None).unwrap();
+    assert_eq!(entry.body["code_changed"], true);
+
+    // deployed in the block: the account did not exist or was not initialized before
+    let entry = crate::block_parser::accounts::ParserAccounts::prepare_account_entry(
+        account, None, true, None, None, None, 0, &parser.config.accounts,
+    ).unwrap();
+    assert!(entry.body.get("prev_code_hash").is_none());
+    assert_eq!(entry.body["code_changed"], true);
+}
+
+#[test]
+fn test_transaction_bounce_msg_id() {
+    let mut header = InternalMessageHeader::with_addresses(
+        crate::samples::sample_address(0).unwrap(),
+        crate::samples::sample_address(-1).unwrap(),
+        CurrencyCollection::with_grams(900_000_000),
+    );
+    header.bounced = true;
+    let mut bounced = synthetic_transaction([0x44; 32], AccountStatus::AccStateActive, TransactionDescrOrdinary {
+        aborted: true,
+        bounce: Some(TrBouncePhase::Ok(TrBouncePhaseOk::default())),
+        ..Default::default()
+    });
+    bounced.add_out_message(&CommonMessage::Std(Message::with_int_header(header))).unwrap();
+    let mut observed = 0;
+    for boc in [
+        read("src/tests/data/3FAFAAB7B5D17E439CD9DDEF7EEC3C3BC9D28E7C181BA374218E13599F8F6657.boc").unwrap(),
+        read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap(),
+        read("src/tests/data/18AFCDD25BE0989CE516504263EB351818A0FF8F6AB3689501C8E3B767EF413C.boc").unwrap(),
+        synthetic_block(&[bounced]),
+    ] {
+        let (_, _, parsed) = parse_block_boc(boc, None);
+        let messages = parsed.messages.iter()
+            .map(|msg| (msg.id.as_str(), &msg.body))
+            .collect::<HashMap<_, _>>();
+        for tr in &parsed.transactions {
+            let bounced = tr.body.get("bounce").map_or(false, |bounce| bounce["bounce_type"] == 2);
+            match tr.body.get("bounce_msg_id") {
+                Some(id) => {
+                    assert!(bounced);
+                    assert!(tr.body["out_msgs"].as_array().unwrap().contains(id));
+                    assert_eq!(messages[id.as_str().unwrap()]["bounced"], true);
+                    observed += 1;
+                }
+                None => assert!(!bounced),
+            }
+        }
+    }
+    assert!(observed >= 1);
+}
+
+#[test]
+fn test_shard_block_master_anchor() {
+    // master_ref of the block is 3128501
+    let (_, _, parsed) = parse_block(
+        "18AFCDD25BE0989CE516504263EB351818A0FF8F6AB3689501C8E3B767EF413C.boc",
+        Some(ParseOptions::default().mc_seq_no(3128502)),
+    );
+    let doc = &parsed.block.as_ref().unwrap().body;
+    assert_eq!(doc["master_anchor"], serde_json::json!({ "mc_block_seqno": 3128502 }));
+    assert!(doc.get("master").is_none());
+
+    let mc_block_id = BlockIdExt::with_params(
+        ShardIdent::masterchain(),
+        3128502,
+        UInt256::from([1; 32]),
+        UInt256::from([2; 32]),
+    );
+    let (_, _, parsed) = parse_block(
+        "18AFCDD25BE0989CE516504263EB351818A0FF8F6AB3689501C8E3B767EF413C.boc",
+        Some(ParseOptions::default().mc_seq_no(3128502).mc_block_id(mc_block_id.clone())),
+    );
+    let anchor = &parsed.block.as_ref().unwrap().body["master_anchor"];
+    assert_eq!(anchor["mc_block_id"], "01".repeat(32));
+    assert_eq!(anchor["mc_block_file_hash"], "02".repeat(32));
+    assert_eq!(anchor["mc_block_seqno"], 3128502);
+
+    let (_, _, parsed) = parse_block(
+        "18AFCDD25BE0989CE516504263EB351818A0FF8F6AB3689501C8E3B767EF413C.boc", None
+    );
+    assert!(parsed.block.as_ref().unwrap().body.get("master_anchor").is_none());
+
+    // anchors contradicting the block are rejected
+    let boc = read("src/tests/data/18AFCDD25BE0989CE516504263EB351818A0FF8F6AB3689501C8E3B767EF413C.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let anchor = |mc_seq_no, mc_block_id| crate::serialize_master_anchor(
+        &mut Map::new(), &block, mc_seq_no, mc_block_id, crate::SerializationMode::Standart
+    );
+    assert!(anchor(Some(3128501), None).is_ok());
+    assert!(anchor(Some(100), None).is_err());
+    assert!(anchor(Some(3128503), Some(&mc_block_id)).is_err());
+    assert!(anchor(None, Some(&mc_block_id)).is_ok());
+}
+
+#[test]
+fn test_block_economics() {
+    let parser = BlockParser::<NoTrace, JsonFieldsReducer>::new(
+        BlockParserConfig {
+            blocks: None,
+            transactions: None,
+            messages: None,
+            account_transactions: None,
+            account_creations: None,
+            economics: Some(EntryConfig { reducer: None, sharding_depth: None }),
+            accounts: None,
+            proofs: None,
+            max_account_bytes_size: None,
+            is_node_se: false,
+            sampling: None,
+            context: None,
+        },
+        None,
+    );
+    let boc = read("src/tests/data/9C9906A80D020952E0192DC60C0B2BF1F55FE9A9E065606E8FE25C08BD1AA6B2.boc").unwrap();
+    let cell = read_single_root_boc(&boc).unwrap();
+    let block = Block::construct_from_cell(cell.clone()).unwrap();
+    let info = block.read_info().unwrap();
+    let id = BlockIdExt::with_params(info.shard().clone(), info.seq_no(), cell.repr_hash(), UInt256::calc_file_hash(&boc));
+    let ethalon = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json").unwrap();
+    let mut state = crate::parse_state(&serde_json::from_str(&ethalon).unwrap()).unwrap();
+    let parse = |shard_state: Option<&ever_block::ShardStateUnsplit>| parser.parse(
+        ParsingBlock {
+            id: &id,
+            block: &block,
+            root: &cell,
+            shard_state,
+            prev_shard_state: None,
+            data: &boc,
+            mc_seq_no: None,
+            mc_block_id: None,
+            proof: None,
+        },
+        false,
+    );
+
+    assert!(parse(None).unwrap().economics.is_none());
+    // the zerostate is not the state after the block
+    assert!(parse(Some(&state)).is_err());
+    state.set_seq_no(info.seq_no());
+    let economics = parse(Some(&state)).unwrap().economics.unwrap();
+    assert_eq!(economics.id, cell.repr_hash().as_hex_string());
+    let master = state.read_custom().unwrap().unwrap();
+    assert_eq!(economics.body["global_balance_dec"], master.global_balance.grams.to_string());
+    let value_flow = block.read_value_flow().unwrap();
+    assert_eq!(economics.body["minted_dec"], value_flow.minted.grams.to_string());
+    assert_eq!(economics.body["fees_collected_dec"], value_flow.fees_collected.grams.to_string());
+}
+
+#[test]
+fn test_resolve_dequeued_messages() {
+    use ever_block::{
+        BlockExtra, BlockInfo, HashmapAugType, MerkleUpdate, MsgEnvelope, OutMsgDescr,
+        OutMsgQueueInfo, Serializable, ShardStateUnsplit, ValueFlow,
+    };
+
+    let message = crate::samples::sample_message().unwrap();
+    let msg_hash = message.serialize().unwrap().repr_hash();
+    let env = MsgEnvelope::with_message_and_fee(&message, 1_000_000.into()).unwrap();
+    let env_hash = env.serialize().unwrap().repr_hash();
+    assert_ne!(msg_hash, env_hash);
+
+    let mut queue_info = OutMsgQueueInfo::default();
+    queue_info.out_queue_mut().insert(0, 0, &env, 2_000_000).unwrap();
+    let mut shard_state = ShardStateUnsplit::with_ident(ShardIdent::full(0));
+    shard_state.write_out_msg_queue_info(&queue_info).unwrap();
+
+    let check = |out_msg: OutMsg, key: &UInt256, item_id: (&str, &str)| {
+        let mut descr = OutMsgDescr::default();
+        descr.set(key, &out_msg, &Default::default()).unwrap();
+        let mut extra = BlockExtra::default();
+        extra.write_out_msg_descr(&descr).unwrap();
+        let block = Block::with_params(
+            0, BlockInfo::default(), ValueFlow::default(), MerkleUpdate::default(), extra,
+        ).unwrap();
+        let root = block.serialize().unwrap();
+        let data = block.write_to_bytes().unwrap();
+        let id = BlockIdExt::with_params(ShardIdent::full(0), 0, root.repr_hash(), UInt256::calc_file_hash(&data));
+        let parsing = ParsingBlock {
+            id: &id,
+            block: &block,
+            root: &root,
+            data: &data,
+            mc_seq_no: None,
+            mc_block_id: None,
+            proof: None,
+            shard_state: None,
+            prev_shard_state: Some(&shard_state),
+        };
+        let mut doc = crate::db_serialize_block("id", crate::BlockSerializationSetFH {
+            block: &block,
+            id: &root.repr_hash(),
+            status: BlockProcessingStatus::Finalized,
+            boc: &data,
+            file_hash: None,
+        }).unwrap();
+        // document items are matched by their ids, not by position
+        doc["out_msg_descr"].as_array_mut().unwrap().insert(0, serde_json::json!({ "msg_type": 0 }));
+        resolve_dequeued_messages(&mut doc, &parsing, crate::SerializationMode::Standart.into()).unwrap();
+
+        let items = doc["out_msg_descr"].as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(items[0].get("dequeued_msg_id").is_none());
+        let item = &items[1];
+        assert_eq!(item.pointer(item_id.0).unwrap(), item_id.1);
+        assert_eq!(item["dequeued_msg_id"], msg_hash.as_hex_string());
+        assert_eq!(item["enqueued_lt"], crate::u64_to_string(2_000_000));
+        assert_eq!(item["enqueued_lt_dec"], "2000000");
+    };
+
+    // `Dequeue` refers to the message, the queue entry is found by its envelope
+    check(
+        OutMsg::dequeue_long(&env, 2_000_001).unwrap(),
+        &msg_hash,
+        ("/out_msg/msg_id", &msg_hash.as_hex_string()),
+    );
+    // `DequeueShort` refers to the envelope only
+    check(
+        OutMsg::dequeue_short(env_hash.clone(), 0, 0, 2_000_001),
+        &msg_hash,
+        ("/msg_env_hash", &env_hash.as_hex_string()),
+    );
+}
@@ -15,7 +15,7 @@
  */
 
 use super::*;
-use crate::block_parser::reducers::JsonFieldsReducer;
+use crate::block_parser::reducers::{JsonFieldsReducer, JsonPointerReducer, JsonSizeReducer};
 use serde_json::json;
 
 #[test]
@@ -215,3 +215,98 @@ fn test_reducer() {
         .clone()
     );
 }
+
+#[test]
+fn test_size_reducer() {
+    let json = json!({
+        "id": "a",
+        "boc": "b".repeat(100),
+        "code": "c".repeat(100),
+        "data": "d".repeat(100),
+    })
+    .as_object()
+    .unwrap()
+    .clone();
+
+    assert_eq!(JsonSizeReducer::new(1000).reduce(json.clone()).unwrap(), json);
+
+    let reduced = JsonSizeReducer::new(260).reduce(json.clone()).unwrap();
+    assert_eq!(reduced["_truncated"], json!(["boc"]));
+    assert!(reduced.contains_key("code"));
+    assert!(serde_json::to_vec(&reduced).unwrap().len() <= 260);
+    assert!(reduced.get("_over_size").is_none());
+
+    let reduced = JsonSizeReducer::new(10).reduce(json.clone()).unwrap();
+    assert_eq!(
+        reduced,
+        json!({
+            "id": "a",
+            "_truncated": ["boc", "code", "data"],
+            "_over_size": true,
+        })
+        .as_object()
+        .unwrap()
+        .clone()
+    );
+
+    let reduced = JsonSizeReducer::with_drop_order(260, &["data", "boc"])
+        .reduce(json.clone())
+        .unwrap();
+    assert_eq!(reduced["_truncated"], json!(["data"]));
+}
+
+#[test]
+fn test_pointer_reducer() {
+    let json = json!({
+        "id": "1",
+        "boc": "te6cc",
+        "block_id": "2",
+        "value": "0x10",
+        "a/b": { "c~d": 1, "e": 2 },
+        "out_msgs": ["x", "y", "z"],
+    })
+    .as_object()
+    .unwrap()
+    .clone();
+
+    assert_eq!(
+        JsonPointerReducer::with_pointers(&[], &["/boc", "/a~1b/c~0d", "/out_msgs/1", "/none/x"])
+            .unwrap()
+            .reduce(json.clone())
+            .unwrap(),
+        json!({
+            "id": "1",
+            "block_id": "2",
+            "value": "0x10",
+            "a/b": { "e": 2 },
+            "out_msgs": ["x", "z"],
+        })
+        .as_object()
+        .unwrap()
+        .clone()
+    );
+
+    assert_eq!(
+        JsonPointerReducer::with_pointers(&["/id", "/block_id", "/value", "/a~1b/e", "/missing"], &["/value"])
+            .unwrap()
+            .reduce(json.clone())
+            .unwrap(),
+        json!({
+            "id": "1",
+            "block_id": "2",
+            "a/b": { "e": 2 },
+        })
+        .as_object()
+        .unwrap()
+        .clone()
+    );
+
+    assert_eq!(
+        JsonPointerReducer::with_pointers(&[""], &[]).unwrap().reduce(json.clone()).unwrap(),
+        json
+    );
+
+    assert!(JsonPointerReducer::with_pointers(&["id"], &[]).is_err());
+    assert!(JsonPointerReducer::with_pointers(&[], &["/a~2"]).is_err());
+    assert!(JsonPointerReducer::with_pointers(&[], &[""]).is_err());
+}
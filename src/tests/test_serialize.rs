@@ -180,7 +180,7 @@ fn test_account_into_json_without_hash_0() {
     println!("\n\n{:#}", serde_json::json!(json));
     assert_eq!(format!("{:#}", serde_json::json!(json)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "983217:0:000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
   "workchain_id": 0,
   "boc": "te6ccgEBFgEA9gAEfdxMGQuAAACBAYICgwOEBIUFhgaHB4gIiQmKCosLjAyNDY4Ojw+RZAe+AdbzRWLeAAAAAAAAAACi6Q7dAB73wAkEAwEBQ6APQXKvQr0nmUedLZlpXZ5OtG4xRMeRXZRVYp/Nw8xC5ZgCAA8/////////9AAPP/8f//////QBDz/////////0BQEPP/////////QGAQ8/P///////9AcBDw/////////0CAAPP/8///////QCA87AEQoCASAOCwIBIA0MAAcHMS3JAAUEBLECASAQDwAFBAPpAAUEAyECASAVEgIBIBQTAAUEAlkAAwORAAVQskA=",
@@ -265,7 +265,7 @@ fn test_account_into_json_with_hash_0() {
     println!("\n\n{:#}", serde_json::json!(json));
     assert_eq!(format!("{:#}", serde_json::json!(json)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "983217:0:000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
   "workchain_id": 0,
   "boc": "te6ccgECFgEAARYABL0biYMhcAAAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8iyBOAA63misW8AAAAAAAAAAFF0h26AD3vnhQLJ5DbtSnn85qIDL3M45rqbL2ylzSoeYveX/SqwkVAkEAwEBQ6APQXKvQr0nmUedLZlpXZ5OtG4xRMeRXZRVYp/Nw8xC5ZgCAA8/////////9AAPP/8f//////QBDz/////////0BQEPP/////////QGAQ8/P///////9AcBDw/////////0CAAPP/8///////QCA87AEQoCASAOCwIBIA0MAAcHMS3JAAUEBLECASAQDwAFBAPpAAUEAyECASAVEgIBIBQTAAUEAlkAAwORAAVQskA=",
@@ -349,7 +349,7 @@ fn test_account_into_json_q() {
     println!("\n\n{:#}", serde_json::json!(json));
     assert_eq!(format!("{:#}", serde_json::json!(json)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "983217:0:000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
   "workchain_id": 0,
   "boc": "te6ccgEBFgEA9gAEfdxMGQuAAACBAYICgwOEBIUFhgaHB4gIiQmKCosLjAyNDY4Ojw+RZAe+AdbzRWLeAAAAAAAAAACi6Q7dAB73wAkEAwEBQ6APQXKvQr0nmUedLZlpXZ5OtG4xRMeRXZRVYp/Nw8xC5ZgCAA8/////////9AAPP/8f//////QBDz/////////0BQEPP/////////QGAQ8/P///////9AcBDw/////////0CAAPP/8///////QCA87AEQoCASAOCwIBIA0MAAcHMS3JAAUEBLECASAQDwAFBAPpAAUEAyECASAVEgIBIBQTAAUEAlkAAwORAAVQskA=",
@@ -422,7 +422,7 @@ fn test_frozen_account_into_json_0() {
     println!("\n\n{:#}", serde_json::json!(json));
     assert_eq!(format!("{:#}", serde_json::json!(json)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "983217:0:000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
   "workchain_id": 0,
   "boc": "te6ccgEBDgEAogABudxMGQuAAACBAYICgwOEBIUFhgaHB4gIiQmKCosLjAyNDY4Ojw+Q5AQ6AdbzRWLeAAAAAAAAAACi6Q7dABdObwUyRFy3kg41ItCU5XanJx0dk6e0bxwNLsS2/h0EUgECA87ACQICASAGAwIBIAUEAAcHMS3JAAUEBLECASAIBwAFBAPpAAUEAyECASANCgIBIAwLAAUEAlkAAwORAAVQskA=",
@@ -500,7 +500,7 @@ fn test_frozen_account_into_json_with_hash_0() {
     println!("\n\n{:#}", serde_json::json!(json));
     assert_eq!(format!("{:#}", serde_json::json!(json)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "983217:0:000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
   "workchain_id": 0,
   "boc": "te6ccgEBDgEAwwAB+xuJgyFwAAAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyHIDHgDreaKxbwAAAAAAAAAAUXSHboALpzeCmSIuW8kHGpFoSnK7U5OOjsnT2jeOBpdiW38Ogik8KBZPIbdqU8/nNRAZe5nHNdTZe2UuaVDzF7y/6VWEioAECA87ACQICASAGAwIBIAUEAAcHMS3JAAUEBLECASAIBwAFBAPpAAUEAyECASANCgIBIAwLAAUEAlkAAwORAAVQskA=",
@@ -579,7 +579,7 @@ fn test_frozen_account_into_json_q() {
     println!("\n\n{:#}", serde_json::json!(json));
     assert_eq!(format!("{:#}", serde_json::json!(json)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "983217:0:000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
   "workchain_id": 0,
   "boc": "te6ccgEBDgEAogABudxMGQuAAACBAYICgwOEBIUFhgaHB4gIiQmKCosLjAyNDY4Ojw+Q5AQ6AdbzRWLeAAAAAAAAAACi6Q7dABdObwUyRFy3kg41ItCU5XanJx0dk6e0bxwNLsS2/h0EUgECA87ACQICASAGAwIBIAUEAAcHMS3JAAUEBLECASAIBwAFBAPpAAUEAyECASANCgIBIAwLAAUEAlkAAwORAAVQskA=",
@@ -652,7 +652,7 @@ fn test_pruned_account_into_json_0() {
     println!("\n\n{:#}", serde_json::json!(json));
     assert_eq!(format!("{:#}", serde_json::json!(json)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "983217:0:000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
   "workchain_id": 0,
   "boc": "te6ccgECEgEAAQMACUYDHqSdbCQpXfDXQJv0UmyHqwKlpkLuM4yUD0ZtscULGIIABQEkfdxMGQuAAACBAYICgwOEBIUFhgaHB4gIiQmKCosLjAyNDY4Ojw+RZAe+AdbzRWLeAAAAAAAAAACi6Q7dAB73wAUEAwIoSAEBQ1njch2YkDA1IY/wfT3zDQzlnSJKvS17C/5lQj+w9n8AAQAPP/8f//////QoSAEBPCgWTyG3alPP5zUQGXuZxzXU2XtlLmlQ8xe8v+lVhIoABAIDzsANBgIBIAoHAgEgCQgABwcxLckABQQEsQIBIAwLAAUEA+kABQQDIQIBIBEOAgEgEA8ABQQCWQADA5EABVCyQA==",
@@ -753,7 +753,7 @@ fn test_message_into_json_0() {
     println!("\n\n{:#}", serde_json::json!(json));
     assert_eq!(format!("{:#}", serde_json::json!(json)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "59bf855c9fbee1152e1e151368f5af5850f22f606c819c43adb2fb319e07a4c8",
   "boc": "te6ccgEBAwEAZgACZpFACBAYICwAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAQ+3tH/////////gIBAUOgD0Fyr0K9J5lHnS2ZaV2eTrRuMUTHkV2UVWKfzcPMQuWIAgAPP/////////Q=",
   "status": 2,
@@ -812,7 +812,7 @@ fn test_message_into_json_q() {
     println!("\n\n{:#}", serde_json::json!(json));
     assert_eq!(format!("{:#}", serde_json::json!(json)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "59bf855c9fbee1152e1e151368f5af5850f22f606c819c43adb2fb319e07a4c8",
   "boc": "te6ccgEBAwEAZgACZpFACBAYICwAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAQ+3tH/////////gIBAUOgD0Fyr0K9J5lHnS2ZaV2eTrRuMUTHkV2UVWKfzcPMQuWIAgAPP/////////Q=",
   "status": 2,
@@ -857,7 +857,7 @@ fn test_transaction_wo_out_msgs_into_json() {
     println!("\n\n{:#}", serde_json::json!(json));
     assert_eq!(format!("{:#}", serde_json::json!(json)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "6abd00aa196e92234902649a4b0955167f65f545c9e5a76046af73b89a026dd3",
   "boc": "te6ccgECDgEAAuIAA7Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3AAAAAAAB4h8AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAHJAUagDAgEAAwACAIJyAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAEBoAQEYwIAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAt78CgkIBQHe////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////BgHe/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+BwDepqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqamAUOgD0Fyr0K9J5lHnS2ZaV2eTrRuMUTHkV2UVWKfzcPMQuWYCQAPP/////////QBDz/////////0CwFFrcm6/FaUEVZY+ivf5GUVGjIDaUr/zQCPNovSzIzIEPtrW1EMAUWtybr8VpQRVlj6K9/kZRUaMgNpSv/NAI82i9LMjMgQ+2tbUQ0ARa3JuvxWlBFXWPor3+RlFRoyA2lK/80AjzaL0syMyBD7a1tR",
   "status": 1,
@@ -919,7 +919,7 @@ fn test_transaction_into_json_0() {
     println!("\n\n{:#}", serde_json::json!(json));
     assert_eq!(format!("{:#}", serde_json::json!(json)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "c47d870b4ce181071c0d69e7ef34dd781562d58a9303a132558bc760b501d9bc",
   "transaction_id": "fcbde06ab4179324274309905a9efdaa11a00782da3a62dbe9978d351e453576",
   "boc": "te6ccgECCgEAAjgABGMCAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAALe/AYFBAEB3v///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////wIB3v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/gMA3qampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampgFDoA9Bcq9CvSeZR50tmWldnk60bjFEx5FdlFVin83DzELlmAUADz/////////0AQ8/////////9AcBRa3JuvxWlBFWWPor3+RlFRoyA2lK/80AjzaL0syMyBD7a1tRCAFFrcm6/FaUEVZY+ivf5GUVGjIDaUr/zQCPNovSzIzIEPtrW1EJAEWtybr8VpQRV1j6K9/kZRUaMgNpSv/NAI82i9LMjMgQ+2tbUQ==",
@@ -970,7 +970,7 @@ r#"{
     println!("\n\n{:#}", serde_json::json!(json));
     assert_eq!(format!("{:#}", serde_json::json!(json)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "fcbde06ab4179324274309905a9efdaa11a00782da3a62dbe9978d351e453576",
   "boc": "te6ccgECFAEAAysAA7Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3AAAAAAAB4h8AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAHJAUagDAgEAAwACAIJyAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAIB4AoEAgHbBgUBAUgIAgEgCQcBASAIAGACAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABASAKBGMCAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAALe/BAPDgsB3v///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////wwB3v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/g0A3qampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampgFDoA9Bcq9CvSeZR50tmWldnk60bjFEx5FdlFVin83DzELlmA8ADz/////////0AQ8/////////9BEBRa3JuvxWlBFWWPor3+RlFRoyA2lK/80AjzaL0syMyBD7a1tREgFFrcm6/FaUEVZY+ivf5GUVGjIDaUr/zQCPNovSzIzIEPtrW1ETAEWtybr8VpQRV1j6K9/kZRUaMgNpSv/NAI82i9LMjMgQ+2tbUQ==",
   "status": 1,
@@ -1039,7 +1039,7 @@ fn test_transaction_into_json_q() {
     println!("\n\n{:#}", serde_json::json!(json));
     assert_eq!(format!("{:#}", serde_json::json!(json)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "735df65db81101d019011e7af787d55fb0cf006e68307991bc4a2840a09b893d",
   "transaction_id": "fcbde06ab4179324274309905a9efdaa11a00782da3a62dbe9978d351e453576",
   "boc": "te6ccgECCgEAAlkABKUIAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAFvfgYFBAEB3v///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////wIB3v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/gMA3qampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampgFDoA9Bcq9CvSeZR50tmWldnk60bjFEx5FdlFVin83DzELlmAUADz/////////0AQ8/////////9AcBRa3JuvxWlBFWWPor3+RlFRoyA2lK/80AjzaL0syMyBD7a1tRCAFFrcm6/FaUEVZY+ivf5GUVGjIDaUr/zQCPNovSzIzIEPtrW1EJAEWtybr8VpQRV1j6K9/kZRUaMgNpSv/NAI82i9LMjMgQ+2tbUQ==",
@@ -1089,7 +1089,7 @@ r#"{
     println!("\n\n{:#}", serde_json::json!(json));
     assert_eq!(format!("{:#}", serde_json::json!(json)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "fcbde06ab4179324274309905a9efdaa11a00782da3a62dbe9978d351e453576",
   "boc": "te6ccgECFAEAAysAA7Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3Nzc3AAAAAAAB4h8AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAHJAUagDAgEAAwACAIJyAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAIB4AoEAgHbBgUBAUgIAgEgCQcBASAIAGACAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABASAKBGMCAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAALe/BAPDgsB3v///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////wwB3v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/g0A3qampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampqampgFDoA9Bcq9CvSeZR50tmWldnk60bjFEx5FdlFVin83DzELlmA8ADz/////////0AQ8/////////9BEBRa3JuvxWlBFWWPor3+RlFRoyA2lK/80AjzaL0syMyBD7a1tREgFFrcm6/FaUEVZY+ivf5GUVGjIDaUr/zQCPNovSzIzIEPtrW1ETAEWtybr8VpQRV1j6K9/kZRUaMgNpSv/NAI82i9LMjMgQ+2tbUQ==",
   "status": 1,
@@ -1235,6 +1235,233 @@ fn test_block_into_json_q() {
     )
 }
 
+#[test]
+fn test_special_msg_values() {
+    let boc = read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let set = BlockSerializationSet {
+        block: block.clone(),
+        id: block.hash().unwrap(),
+        status: BlockProcessingStatus::Finalized,
+        boc,
+    };
+    let mut doc = db_serialize_block("id", &set).unwrap();
+    serialize_special_msg_values(&mut doc, &block, SerializationMode::Standart).unwrap();
+
+    let master = block.read_extra().unwrap().read_custom().unwrap().unwrap();
+    let msg = master.read_recover_create_msg().unwrap().unwrap().read_message().unwrap();
+    let value = msg.get_value().unwrap().grams.to_string();
+    assert_eq!(doc["master"]["recover_create_msg_value_dec"], value);
+    assert_eq!(doc["master"]["recover_create_msg_dst"], msg.dst_ref().unwrap().to_string());
+    assert!(doc["master"]["recover_create_msg_dst"].as_str().unwrap().starts_with("-1:"));
+    assert!(doc["master"].get("mint_msg_value").is_none());
+
+    let boc = read("src/tests/data/18AFCDD25BE0989CE516504263EB351818A0FF8F6AB3689501C8E3B767EF413C.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let mut doc = Map::new();
+    serialize_special_msg_values(&mut doc, &block, SerializationMode::Standart).unwrap();
+    assert!(doc.is_empty());
+}
+
+#[test]
+fn test_special_msgs() {
+    let boc = read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let set = BlockSerializationSet {
+        block: block.clone(),
+        id: block.hash().unwrap(),
+        status: BlockProcessingStatus::Finalized,
+        boc,
+    };
+    let mut doc = db_serialize_block("id", &set).unwrap();
+    serialize_special_msgs(&mut doc, &block, SerializationMode::Standart).unwrap();
+
+    let master = block.read_extra().unwrap().read_custom().unwrap().unwrap();
+    let msg = master.read_recover_create_msg().unwrap().unwrap().read_message().unwrap();
+    let embedded = &doc["master"]["recover_create_msg"]["message"];
+    assert_eq!(embedded["id"], doc["master"]["recover_create_msg"]["msg_id"]);
+    assert_eq!(embedded["value_dec"], msg.get_value().unwrap().grams.to_string());
+    assert_eq!(embedded["dst"], msg.dst_ref().unwrap().to_string());
+    assert_eq!(crate::parse_message(embedded.as_object().unwrap()).unwrap(), msg);
+    assert!(doc["master"].get("mint_msg").is_none());
+
+    let boc = read("src/tests/data/18AFCDD25BE0989CE516504263EB351818A0FF8F6AB3689501C8E3B767EF413C.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let mut doc = Map::new();
+    serialize_special_msgs(&mut doc, &block, SerializationMode::Standart).unwrap();
+    assert!(doc.is_empty());
+}
+
+#[test]
+fn test_annotate_deprecated_fields() {
+    let boc = read("src/tests/data/18AFCDD25BE0989CE516504263EB351818A0FF8F6AB3689501C8E3B767EF413C.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let set = BlockSerializationSet {
+        id: block.hash().unwrap(),
+        block,
+        status: BlockProcessingStatus::Finalized,
+        boc,
+    };
+    let doc = db_serialize_block("id", &set).unwrap();
+    assert!(doc.get("_deprecated").is_none());
+
+    let mut annotated = doc.clone();
+    annotate_deprecated_fields(&mut annotated, "block");
+    assert_eq!(annotated, doc);
+
+    let fields = [DeprecatedField {
+        kind: "block",
+        field: "prev_seq_no",
+        removed_in: VERSION + 1,
+        replacement: "prev_ref.seq_no",
+    }];
+    let mut annotated = doc.clone();
+    annotate_fields(&fields, &mut annotated, "block");
+    assert_eq!(annotated["_deprecated"], serde_json::json!([{
+        "field": "prev_seq_no",
+        "removed_in": VERSION + 1,
+        "replacement": "prev_ref.seq_no",
+    }]));
+
+    let mut annotated = doc.clone();
+    annotate_fields(&fields, &mut annotated, "transaction");
+    assert_eq!(annotated, doc);
+}
+
+#[test]
+fn test_block_boc_index() {
+    let boc = read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let id = block.hash().unwrap();
+    let set = BlockSerializationSet {
+        block,
+        id: id.clone(),
+        status: BlockProcessingStatus::Finalized,
+        boc: boc.clone(),
+    };
+    let (doc, index) = db_serialize_block_with_boc_index("id", &set, SerializationMode::Standart).unwrap();
+    assert_eq!(doc, db_serialize_block("id", &set).unwrap());
+    assert_eq!(index[0], BocIndexEntry { path: "/boc".to_string(), hash: id, size: boc.len() });
+    assert_eq!(index, collect_boc_index(&doc).unwrap());
+
+    let doc = serde_json::json!({
+        "messages": [{ "body": doc["boc"] }],
+        "a/b": { "code": doc["boc"] },
+    });
+    let paths = collect_boc_index(doc.as_object().unwrap()).unwrap()
+        .into_iter().map(|entry| entry.path).collect::<Vec<_>>();
+    assert_eq!(paths, ["/messages/0/body", "/a~1b/code"]);
+}
+
+#[test]
+fn test_hash_format() {
+    let boc = read("src/tests/data/18AFCDD25BE0989CE516504263EB351818A0FF8F6AB3689501C8E3B767EF413C.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let set = BlockSerializationSet {
+        id: block.hash().unwrap(),
+        block,
+        status: BlockProcessingStatus::Finalized,
+        boc,
+    };
+    let format = HashFormat { prefix: true, uppercase: true };
+    let options = SerializationOptions::new(SerializationMode::Standart).with_hash_format(format);
+    let doc = db_serialize_block_ex("id", &set, options).unwrap();
+    assert_eq!(doc["id"], format!("0x{}", set.id.as_hex_string().to_uppercase()));
+    fn check_no_plain_hashes(value: &Value) {
+        match value {
+            Value::String(string) => assert!(
+                string.len() != 64 || !string.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)),
+                "hash {} is not formatted", string
+            ),
+            Value::Array(array) => array.iter().for_each(check_no_plain_hashes),
+            Value::Object(map) => map.iter()
+                .filter(|(name, _)| !name.ends_with("public_key") && !name.starts_with("signature_"))
+                .for_each(|(_, value)| check_no_plain_hashes(value)),
+            _ => ()
+        }
+    }
+    check_no_plain_hashes(&doc.clone().into());
+    assert_eq!(
+        crate::parse_block_prev_refs(&doc).unwrap(),
+        crate::parse_block_prev_refs(&db_serialize_block("id", &set).unwrap()).unwrap()
+    );
+
+    // default format
+    let doc = db_serialize_block("id", &set).unwrap();
+    assert_eq!(doc["id"], set.id.as_hex_string());
+}
+
+#[test]
+fn test_block_parts_proof() {
+    let boc = read("src/tests/data/18AFCDD25BE0989CE516504263EB351818A0FF8F6AB3689501C8E3B767EF413C.boc").unwrap();
+    let root = read_single_root_boc(&boc).unwrap();
+    let set = BlockSerializationSet {
+        block: Block::construct_from_cell(root.clone()).unwrap(),
+        id: root.repr_hash(),
+        status: BlockProcessingStatus::Finalized,
+        boc,
+    };
+    let doc = db_serialize_block("id", &set).unwrap();
+    let tr_id = doc["account_blocks"][0]["transactions"][0]["transaction_id"].as_str().unwrap();
+
+    let selector = format!("transactions/{}", tr_id).parse::<ProofSelector>().unwrap();
+    let parts = db_serialize_block_parts_proof(&root, &[selector], SerializationMode::Standart).unwrap();
+    assert_eq!(parts["id"], root.repr_hash().as_hex_string());
+    assert_eq!(parts["transactions"][0]["id"], tr_id);
+    assert!(parts.get("messages").is_none());
+
+    let proof = base64_decode(parts["proof"].as_str().unwrap()).unwrap();
+    let proof = MerkleProof::construct_from_bytes(&proof).unwrap();
+    assert_eq!(proof.hash, root.repr_hash());
+
+    // the selected transaction is kept unpruned in the proof
+    fn find_cell(cell: &Cell, hash: &UInt256) -> Option<Cell> {
+        if &cell.repr_hash() == hash {
+            return Some(cell.clone())
+        }
+        (0..cell.references_count())
+            .filter_map(|i| cell.reference(i).ok())
+            .find_map(|child| find_cell(&child, hash))
+    }
+    let tr_hash = tr_id.parse::<UInt256>().unwrap();
+    let tr_cell = find_cell(&proof.proof.virtualize(1), &tr_hash).unwrap();
+    assert_eq!(tr_cell.cell_type(), CellType::Ordinary);
+    let transaction = Transaction::construct_from_cell(tr_cell).unwrap();
+    assert_eq!(transaction.serialize().unwrap().repr_hash(), tr_hash);
+
+    assert!("accounts/1".parse::<ProofSelector>().is_err());
+    let selector = ProofSelector::Transaction(UInt256::default());
+    assert!(db_serialize_block_parts_proof(&root, &[selector], SerializationMode::Standart).is_err());
+}
+
+#[test]
+fn test_db_serialize_config() {
+    let boc = read("src/tests/data/9C9906A80D020952E0192DC60C0B2BF1F55FE9A9E065606E8FE25C08BD1AA6B2.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let set = BlockSerializationSet {
+        id: block.hash().unwrap(),
+        block: block.clone(),
+        status: BlockProcessingStatus::Finalized,
+        boc,
+    };
+    let doc = db_serialize_block_ex("id", &set, SerializationMode::QServer).unwrap();
+    let config = block.read_extra().unwrap().read_custom().unwrap().unwrap().config().cloned().unwrap();
+    let map = db_serialize_config(&config, SerializationMode::QServer).unwrap();
+    assert_eq!(map["config_addr"], doc["master"]["config_addr"]);
+    assert_eq!(map["config"], doc["master"]["config"]);
+    assert_eq!(map.get("unknown_config"), doc["master"].get("unknown_config"));
+}
+
+#[test]
+fn test_db_serialize_mc_state_extra() {
+    let ethalon = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json").unwrap();
+    let map = serde_json::from_str::<Map<String, Value>>(&ethalon).unwrap();
+    let state = crate::parse_state(&map).unwrap();
+    let master = state.read_custom().unwrap().unwrap();
+    let master_map = db_serialize_mc_state_extra(&master, SerializationMode::Debug).unwrap();
+    assert_eq!(Value::from(master_map), map["master"]);
+}
+
 #[test]
 fn test_key_block_into_json() {
     test_json_block(
@@ -1435,7 +1662,7 @@ fn test_db_serialize_block_signatures() {
     assert_eq!(
         doc,
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "_id": "0101010101010101010101010101010101010101010101010101010101010101",
   "signatures": [
     {
@@ -1455,7 +1682,7 @@ r#"{
 #[test]
 fn test_serialize_shard_descr() {
   let sd = ShardDescr::default();
-  let doc = serialize_shard_descr(&sd, SerializationMode::Standart).unwrap();
+  let doc = serialize_shard_descr(&sd, SerializationMode::Standart.into()).unwrap();
   print!("{}", serde_json::to_string_pretty(&doc).unwrap());
   assert_eq!(doc,
     serde_json::from_str::<serde_json::Value>(r#"
@@ -1505,6 +1732,128 @@ fn test_db_serialize_block_proof() {
     assert_json_eq_file(&json, "proof");
 }
 
+#[test]
+fn test_db_serialize_block_header_from_proof() {
+    let boc = read("src/tests/data/block_proof").expect(&format!("Error reading proof file"));
+    let cell = read_single_root_boc(&boc).expect("Error deserializing single root BOC");
+    let proof = BlockProof::construct_from_cell(cell).unwrap();
+
+    let header = db_serialize_block_header_from_proof("id", &proof).unwrap();
+    let proof_json = db_serialize_block_proof("id", &proof).unwrap();
+
+    assert_eq!(header["from_proof"], true);
+    assert!(!header.contains_key("signatures"));
+    assert!(header.contains_key("prev_ref"));
+    for field in ["id", "gen_utime", "seq_no", "workchain_id", "shard"] {
+        assert_eq!(header[field], proof_json[field], "{}", field);
+    }
+}
+
+#[test]
+fn test_db_serialize_block_signatures_ex() {
+    let boc = read("src/tests/data/block_proof").expect(&format!("Error reading proof file"));
+    let cell = read_single_root_boc(&boc).expect("Error deserializing single root BOC");
+    let proof = BlockProof::construct_from_cell(cell).unwrap();
+    let signatures = proof.signatures.as_ref().unwrap();
+    let mut pairs = Vec::new();
+    signatures.pure_signatures.signatures().iterate_slices(|_, mut value| {
+        pairs.push(CryptoSignaturePair::construct_from(&mut value)?);
+        Ok(true)
+    }).unwrap();
+
+    let merkle_proof = MerkleProof::construct_from_cell(proof.root.clone()).unwrap();
+    let block_info = Block::construct_from_cell(merkle_proof.proof.virtualize(1)).unwrap().read_info().unwrap();
+
+    let set = BlockSignaturesSerializationSet {
+        block_id_ext: proof.proof_for.clone(),
+        gen_utime: block_info.gen_utime().as_u32(),
+        seq_no: proof.proof_for.seq_no(),
+        shard: proof.proof_for.shard().clone(),
+        validator_list_hash_short: signatures.validator_info.validator_list_hash_short,
+        catchain_seqno: signatures.validator_info.catchain_seqno,
+        sig_weight: signatures.pure_signatures.weight(),
+        signatures: pairs,
+        proof_boc: Some(write_boc(&proof.root).unwrap()),
+    };
+    let doc = db_serialize_block_signatures_ex("_id", &set, SerializationMode::Standart).unwrap();
+    // the proof document has also `proof_for` and refs of the block
+    let ethalon = std::fs::read_to_string("src/tests/data/proof-ethalon.json").unwrap();
+    let mut expected = serde_json::from_str::<Map<String, Value>>(&ethalon).unwrap();
+    expected.remove("proof_for");
+    expected.remove("prev_ref");
+    assert_eq!(Value::from(doc), Value::from(expected));
+}
+
+#[test]
+fn test_signers_bitmap() {
+    let json = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json").unwrap();
+    let map = serde_json::from_str::<Map<String, Value>>(&json).unwrap();
+    let config = crate::parse_config(map["master"]["config"].as_object().unwrap()).unwrap();
+    let validators = config.validator_set().unwrap();
+    assert_eq!(validators.list().len(), 7);
+
+    assert_eq!(signers_bitmap([], &validators).unwrap(), "00");
+
+    let mut set = BlockSignaturesSerializationSet {
+        block_id_ext: BlockIdExt::default(),
+        gen_utime: 0,
+        seq_no: 0,
+        shard: ShardIdent::masterchain(),
+        validator_list_hash_short: 0,
+        catchain_seqno: 0,
+        sig_weight: 0,
+        signatures: [0, 3, 6].iter().map(|index| CryptoSignaturePair::with_params(
+            validators.list()[*index].compute_node_id_short(),
+            CryptoSignature::with_r_s(&[1; 32], &[2; 32]),
+        )).collect(),
+        proof_boc: None,
+    };
+    let doc = db_serialize_block_signatures_with_validators(
+        "id", &set, &validators, SerializationMode::Standart
+    ).unwrap();
+    assert_eq!(doc["signers_bitmap"], "92");
+    // hash format of the document doesn't matter
+    let options = SerializationOptions::new(SerializationMode::Standart)
+        .with_hash_format(HashFormat { prefix: true, uppercase: true });
+    let doc = db_serialize_block_signatures_with_validators("id", &set, &validators, options).unwrap();
+    assert_eq!(doc["signers_bitmap"], "92");
+    let doc = db_serialize_block_signatures_ex("id", &set, SerializationMode::Standart).unwrap();
+    assert!(doc.get("signers_bitmap").is_none());
+
+    set.signatures.push(CryptoSignaturePair::with_params(
+        UInt256::default(), CryptoSignature::with_r_s(&[1; 32], &[2; 32])
+    ));
+    assert!(db_serialize_block_signatures_with_validators(
+        "id", &set, &validators, SerializationMode::Standart
+    ).is_err());
+}
+
+#[test]
+fn test_db_serialize_top_block_descr() {
+    let boc = read("src/tests/data/block_proof").expect(&format!("Error reading proof file"));
+    let cell = read_single_root_boc(&boc).expect("Error deserializing single root BOC");
+    let proof = BlockProof::construct_from_cell(cell).unwrap();
+
+    let mut descr = TopBlockDescr::with_id_and_signatures(
+        proof.proof_for.clone(),
+        proof.signatures.clone().unwrap()
+    );
+    descr.append_proof(proof.root.clone());
+
+    let doc = db_serialize_top_block_descr(&descr, SerializationMode::Standart).unwrap();
+    let proof_json = db_serialize_block_proof("id", &proof).unwrap();
+    assert_eq!(doc["id"], proof_json["id"]);
+    assert_eq!(doc["proof_for"]["seqno"], proof_json["seq_no"]);
+    for field in ["validator_list_hash_short", "catchain_seqno", "sig_weight", "signatures"] {
+        assert_eq!(doc[field], proof_json[field], "{}", field);
+    }
+    let chain = doc["chain"].as_array().unwrap();
+    assert_eq!(chain.len(), 1);
+    for field in ["id", "gen_utime", "seq_no", "workchain_id", "shard", "proof"] {
+        assert_eq!(chain[0][field], proof_json[field], "{}", field);
+    }
+}
+
 fn prepare_shard_state_json(name: &str, workchain_id: i32, mode: SerializationMode) -> String {
     let boc = read(format!("src/tests/data/states/{}", name))
         .expect(&format!("Error reading file {:?}", name));
@@ -1616,6 +1965,44 @@ fn check_transaction_field(
   assert_eq!(serde_json::json!(serialized)[field_name], q_value.into());
 }
 
+#[test]
+fn test_compute_phase_gas_prices() {
+  let boc = std::fs::read("src/tests/data/transactions/ext_in&int_out.boc").unwrap();
+  let cell = read_single_root_boc(&boc).expect("Error deserializing single root BOC");
+  let set = TransactionSerializationSet {
+    block_id: None,
+    id: cell.repr_hash(),
+    transaction: Transaction::construct_from_cell(cell).unwrap(),
+    boc,
+    proof: None,
+    status: TransactionProcessingStatus::Finalized,
+    workchain_id: 0,
+  };
+  let mut config = ConfigParams::new();
+  config.set_config(ConfigParamEnum::ConfigParam21(GasLimitsPrices {
+    gas_price: 1000 << 16,
+    gas_limit: 1_000_000,
+    special_gas_limit: 1_000_000,
+    gas_credit: 10_000,
+    block_gas_limit: 10_000_000,
+    freeze_due_limit: 100_000_000,
+    delete_due_limit: 1_000_000_000,
+    flat_gas_limit: 100,
+    flat_gas_price: 100_000,
+    max_gas_threshold: 0,
+  })).unwrap();
+
+  let plain = db_serialize_transaction_ex("id", &set, SerializationMode::QServer).unwrap();
+  let map = db_serialize_transaction_with_config("id", &set, &config, SerializationMode::QServer).unwrap();
+  let compute = map["compute"].as_object().unwrap();
+  assert_eq!(compute["gas_price_used"], "0x3e8");
+  let gas_used = compute["gas_used"].as_u64().unwrap();
+  assert_eq!(compute["flat_fee_applied"], gas_used <= 100);
+  let gas_fees = u128::from_str_radix(compute["gas_fees"].as_str().unwrap().trim_start_matches("0x"), 16).unwrap();
+  assert_eq!(compute["gas_fees_dec"], gas_fees.to_string());
+  assert_eq!(map["lt"], plain["lt"]);
+}
+
 #[test]
 fn test_balance_delta() {
   check_transaction_field("aborted_bounced.boc", "balance_delta", "000", "0x0");
@@ -1634,6 +2021,14 @@ fn test_ext_in_msg_fee() {
   check_transaction_field("int_in.boc", "ext_in_msg_fee", Value::Null, Value::Null);
 }
 
+#[test]
+fn test_action_phase_totals() {
+  check_transaction_field("aborted_bounced.boc", "msgs_created", Value::Null, Value::Null);
+  check_transaction_field("ext_in&int_out.boc", "msgs_created", 1, 1);
+  check_transaction_field("ext_in&int_out.boc", "skipped_actions", 0, 0);
+  check_transaction_field("ext_in&int_out.boc", "action_success", true, true);
+}
+
 #[test]
 fn test_serialize_deleted_account_s() {
   let account = generate_test_account_by_init_code_hash(true);
@@ -1647,7 +2042,7 @@ fn test_serialize_deleted_account_s() {
   assert_eq!(
     format!("{:#}", serde_json::json!(doc)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "0:0000000000000000000000000000000000000000000000000000000000000000",
   "workchain_id": 0,
   "acc_type": 3,
@@ -1668,7 +2063,7 @@ fn test_serialize_deleted_account_q() {
   assert_eq!(
     format!("{:#}", serde_json::json!(doc)),
 r#"{
-  "json_version": 8,
+  "json_version": 21,
   "id": "0:0000000000000000000000000000000000000000000000000000000000000000",
   "workchain_id": 0,
   "acc_type": 3,
@@ -1677,6 +2072,107 @@ r#"{
 }"#)
 }
 
+#[test]
+fn test_serialize_account_last_active_at() {
+    let account = generate_test_account_by_init_code_hash(false);
+    let set = AccountSerializationSet {
+        account,
+        ..Default::default()
+    };
+
+    let anchor = LtTimeAnchor { start_lt: 0, end_lt: 10, utime: 1576526553 };
+    let doc = db_serialize_account_with_time_anchor(
+        "id", &set, &anchor, SerializationMode::Standart
+    ).unwrap();
+    assert_eq!(doc["last_trans_lt"], "00");
+    assert_eq!(doc["last_active_at"], 1576526553);
+
+    let anchor = LtTimeAnchor { start_lt: 1, end_lt: 10, utime: 1576526553 };
+    let doc = db_serialize_account_with_time_anchor(
+        "id", &set, &anchor, SerializationMode::Standart
+    ).unwrap();
+    assert!(!doc.contains_key("last_active_at"));
+    assert_eq!(doc, db_serialize_account("id", &set).unwrap());
+}
+
+#[test]
+fn test_serialize_config_proposals() {
+    fn proposal_status(param: i32, value: Option<Cell>, voters: &[u16]) -> BuilderData {
+        let mut proposal = BuilderData::new();
+        proposal.append_u8(0xf3).unwrap();
+        proposal.append_i32(param).unwrap();
+        match value {
+            Some(value) => {
+                proposal.append_bit_one().unwrap();
+                proposal.checked_append_reference(value).unwrap();
+            }
+            None => { proposal.append_bit_zero().unwrap(); }
+        }
+        proposal.append_bit_zero().unwrap();
+
+        let mut voters_dict = HashmapE::with_bit_len(16);
+        for voter in voters {
+            let key = SliceData::load_builder(voter.write_to_new_cell().unwrap()).unwrap();
+            voters_dict.set_builder(key, &BuilderData::new()).unwrap();
+        }
+        let mut status = BuilderData::new();
+        status.append_u8(0xce).unwrap();
+        status.append_u32(1576526600).unwrap();
+        status.checked_append_reference(proposal.into_cell().unwrap()).unwrap();
+        status.append_bit_one().unwrap();
+        voters_dict.write_to(&mut status).unwrap();
+        status.append_i64(-5).unwrap();
+        UInt256::with_array([7; 32]).write_to(&mut status).unwrap();
+        status.append_u8(3).unwrap();
+        status.append_u8(1).unwrap();
+        status.append_u8(0).unwrap();
+        status
+    }
+
+    let elector = UInt256::with_array([0x33; 32]);
+    let mut votes = HashmapE::with_bit_len(256);
+    votes.set_builder(
+        SliceData::load_builder(UInt256::with_array([1; 32]).write_to_new_cell().unwrap()).unwrap(),
+        &proposal_status(1, Some(elector.write_to_new_cell().unwrap().into_cell().unwrap()), &[0, 5]),
+    ).unwrap();
+    votes.set_builder(
+        SliceData::load_builder(UInt256::with_array([2; 32]).write_to_new_cell().unwrap()).unwrap(),
+        &proposal_status(100, None, &[]),
+    ).unwrap();
+
+    let mut data = BuilderData::new();
+    data.checked_append_reference(Cell::default()).unwrap();
+    data.append_u32(12).unwrap();
+    UInt256::with_array([4; 32]).write_to(&mut data).unwrap();
+    votes.write_to(&mut data).unwrap();
+    let data = data.into_cell().unwrap();
+
+    let map = serialize_config_proposals(&data, SerializationMode::Standart).unwrap();
+    assert_eq!(map["seqno"], 12);
+    assert_eq!(map["public_key"], UInt256::with_array([4; 32]).as_hex_string());
+    let proposals = map["proposals"].as_array().unwrap();
+    assert_eq!(proposals.len(), 2);
+
+    let p1 = &proposals[0];
+    assert_eq!(p1["hash"], UInt256::with_array([1; 32]).as_hex_string());
+    assert_eq!(p1["param"], 1);
+    assert_eq!(p1["value"], elector.as_hex_string());
+    assert!(p1["value_boc"].is_string());
+    assert_eq!(p1["expires"], 1576526600);
+    assert_eq!(p1["is_critical"], true);
+    assert_eq!(p1["voters"], serde_json::json!([0, 5]));
+    assert_eq!(p1["remaining_weight"], -5);
+    assert_eq!(p1["rounds_remaining"], 3);
+    assert_eq!(p1["wins"], 1);
+    assert_eq!(p1["losses"], 0);
+
+    let p100 = &proposals[1];
+    assert_eq!(p100["param"], 100);
+    assert!(p100.get("value").is_none());
+    assert!(p100.get("value_boc").is_none());
+    assert_eq!(p100["voters"], serde_json::json!([]));
+}
+
 #[test]
 fn test_block_order() {
     let block = std::fs::read(
@@ -1849,7 +2345,7 @@ fn test_se_deserialise_mesh_config() {
 
     let json = serde_json::to_string_pretty(
         &serde_json::json!({
-            "p58": serialize_mesh_config(&mesh_config).unwrap()
+            "p58": serialize_mesh_config(&mesh_config, SerializationMode::Standart.into()).unwrap()
         })
     ).unwrap();
     println!("{}", json);
@@ -1859,3 +2355,998 @@ fn test_se_deserialise_mesh_config() {
 
     assert_eq!(mesh_config, config_params.mesh_config().unwrap().unwrap());
 }
+
+#[test]
+fn test_vertical_block_refs() {
+    let blk_ref = |seq_no: u32, byte: u8| ExtBlkRef {
+        end_lt: 1_000_000 + seq_no as u64,
+        seq_no,
+        root_hash: UInt256::from([byte; 32]),
+        file_hash: UInt256::from([byte + 1; 32]),
+    };
+    let prev_ref = BlkPrevInfo::Blocks {
+        prev1: ChildCell::with_struct(&blk_ref(10, 1)).unwrap(),
+        prev2: ChildCell::with_struct(&blk_ref(11, 3)).unwrap(),
+    };
+    let prev_vert_ref = BlkPrevInfo::Block { prev: blk_ref(9, 5) };
+
+    let mut block_info = BlockInfo::new();
+    block_info.set_prev_stuff(true, &prev_ref).unwrap();
+    block_info.set_vertical_stuff(1, 3, Some(prev_vert_ref.clone())).unwrap();
+
+    for mode in [SerializationMode::Standart, SerializationMode::QServer] {
+        let mut map = Map::new();
+        serialize_block_info(&mut map, &block_info, mode).unwrap();
+        assert_eq!(map["prev_vert_ref"]["vert_seq_no"], 2);
+        assert!(map["prev_ref"].get("vert_seq_no").is_none());
+        let (parsed_prev_ref, parsed_prev_vert_ref) = crate::parse_block_prev_refs(&map).unwrap();
+        assert_eq!(parsed_prev_ref, prev_ref);
+        assert_eq!(parsed_prev_vert_ref, Some(prev_vert_ref.clone()));
+    }
+}
+
+#[test]
+fn test_merge_block_parents() {
+    let blk_ref = |seq_no: u32, byte: u8| ExtBlkRef {
+        end_lt: 1_000_000 + seq_no as u64,
+        seq_no,
+        root_hash: UInt256::from([byte; 32]),
+        file_hash: UInt256::from([byte + 1; 32]),
+    };
+    let shard = ShardIdent::with_tagged_prefix(0, 0x6000_0000_0000_0000).unwrap();
+    let (left, right) = shard.split().unwrap();
+
+    let mut block_info = BlockInfo::new();
+    block_info.set_shard(shard.clone());
+    block_info.set_prev_stuff(true, &BlkPrevInfo::Blocks {
+        prev1: ChildCell::with_struct(&blk_ref(10, 1)).unwrap(),
+        prev2: ChildCell::with_struct(&blk_ref(12, 3)).unwrap(),
+    }).unwrap();
+
+    let mut map = Map::new();
+    serialize_block_info(&mut map, &block_info, SerializationMode::QServer).unwrap();
+    let parents = map["parents"].as_array().unwrap();
+    assert_eq!(parents.len(), 2);
+    assert_eq!(parents[0]["seq_no"], 10);
+    assert_eq!(parents[0]["end_lt"], map["prev_ref"]["end_lt"]);
+    assert_eq!(parents[0]["shard"], left.shard_prefix_as_str_with_tag());
+    assert_eq!(parents[1]["file_hash"], map["prev_alt_ref"]["file_hash"]);
+    assert_eq!(parents[1]["shard"], right.shard_prefix_as_str_with_tag());
+
+    let expected = vec![
+        BlockIdExt::with_params(left, 10, UInt256::from([1; 32]), UInt256::from([2; 32])),
+        BlockIdExt::with_params(right, 12, UInt256::from([3; 32]), UInt256::from([4; 32])),
+    ];
+    assert_eq!(crate::parse_block_parents(&map).unwrap(), expected);
+    map.remove("parents");
+    assert_eq!(crate::parse_block_parents(&map).unwrap(), expected);
+
+    map.remove("prev_alt_ref");
+    assert!(crate::parse_block_prev_refs(&map).is_err());
+
+    let mut block_info = BlockInfo::new();
+    block_info.set_shard(shard.clone());
+    block_info.set_prev_stuff(false, &BlkPrevInfo::Block { prev: blk_ref(10, 1) }).unwrap();
+    let mut map = Map::new();
+    serialize_block_info(&mut map, &block_info, SerializationMode::Standart).unwrap();
+    assert!(map.get("parents").is_none());
+    assert_eq!(
+        crate::parse_block_parents(&map).unwrap(),
+        vec![BlockIdExt::with_params(shard, 10, UInt256::from([1; 32]), UInt256::from([2; 32]))]
+    );
+}
+
+/// Flips random bits of the valid BOC, deterministic for the seed
+fn mutate_boc(boc: &[u8], seed: u64) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(0x9E3779B97F4A7C15) | 1;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let mut boc = boc.to_vec();
+    for _ in 0..1 + next() % 4 {
+        let index = (next() % boc.len() as u64) as usize;
+        boc[index] ^= 1 << (next() % 8);
+    }
+    boc
+}
+
+/// Runs the check on the valid BOC and its mutations. The check returns errors for the data
+/// it can't deserialize or serialize and panics if the produced document is wrong
+fn check_mutations(boc: &[u8], check: impl Fn(&[u8]) -> Result<()>) {
+    check(boc).unwrap();
+    for seed in 0..64 {
+        let boc = mutate_boc(boc, seed);
+        if let Err(err) = check(&boc) {
+            log::debug!("mutation with seed {} is rejected: {}", seed, err);
+        }
+    }
+}
+
+#[test]
+fn test_serialize_mutated_bocs() {
+    let boc = read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    check_mutations(&boc, |boc| {
+        let cell = read_single_root_boc(boc)?;
+        let block = Block::construct_from_cell(cell.clone())?;
+        let id = cell.repr_hash();
+        let set = BlockSerializationSetFH {
+            block: &block,
+            id: &id,
+            status: BlockProcessingStatus::Finalized,
+            boc,
+            file_hash: None,
+        };
+        let map = db_serialize_block("id", set)?;
+        let info = block.read_info()?;
+        assert_eq!(map["boc"], base64_encode(boc));
+        assert_eq!(map["seq_no"], info.seq_no());
+        assert_eq!(map["workchain_id"], info.shard().workchain_id());
+        assert_eq!(crate::parse_block_prev_refs(&map).unwrap().0, info.read_prev_ref()?);
+        Ok(())
+    });
+
+    let boc = read("src/tests/data/transactions/ext_in&int_out.boc").unwrap();
+    check_mutations(&boc, |boc| {
+        let cell = read_single_root_boc(boc)?;
+        let transaction = Transaction::construct_from_cell(cell.clone())?;
+        let id = cell.repr_hash();
+        let set = TransactionSerializationSetEx {
+            transaction: &transaction,
+            id: &id,
+            status: TransactionProcessingStatus::Finalized,
+            block_id: None,
+            workchain_id: None,
+            boc,
+            proof: None,
+        };
+        let map = db_serialize_transaction("id", set)?;
+        assert_eq!(map["outmsg_cnt"], transaction.msg_count());
+        assert_eq!(crate::parse_transaction(&map).unwrap(), transaction);
+        Ok(())
+    });
+
+    let boc = generate_test_account_by_init_code_hash(false).write_to_bytes().unwrap();
+    check_mutations(&boc, |boc| {
+        let account = Account::construct_from_bytes(boc)?;
+        let address = account.get_addr().map(|addr| addr.to_string());
+        let set = AccountSerializationSet {
+            account,
+            prev_code_hash: None,
+            boc: boc.to_vec(),
+            boc1: None,
+            proof: None,
+        };
+        let map = db_serialize_account("id", &set)?;
+        assert_eq!(map.get("id").and_then(|id| id.as_str()), address.as_deref());
+        assert_eq!(crate::parse_account(&map).unwrap(), set.account);
+        Ok(())
+    });
+}
+
+#[test]
+fn test_field_filter() {
+    let mut doc = serde_json::json!({
+        "id": "1",
+        "boc": "te6",
+        "boc_hash": "2",
+        "code_hash": "3",
+        "account": { "code": "te6", "data": "te6", "balance": "4" },
+        "messages": [{ "proof": "te6", "value": "5" }],
+    }).as_object().unwrap().clone();
+    let mut keep_hashes = doc.clone();
+    let mut drop_hashes = doc.clone();
+
+    FieldFilter::compact().apply(&mut doc);
+    assert_eq!(serde_json::Value::from(doc), serde_json::json!({
+        "id": "1",
+        "boc_hash": "2",
+        "code_hash": "3",
+        "account": { "balance": "4" },
+        "messages": [{ "value": "5" }],
+    }));
+
+    FieldFilter { drop_hashes: true, ..FieldFilter::new(&["boc"]) }.apply(&mut drop_hashes);
+    assert_eq!(serde_json::Value::from(drop_hashes), serde_json::json!({
+        "id": "1",
+        "code_hash": "3",
+        "account": { "code": "te6", "data": "te6", "balance": "4" },
+        "messages": [{ "proof": "te6", "value": "5" }],
+    }));
+
+    FieldFilter::new(&["boc", "code"]).apply(&mut keep_hashes);
+    assert_eq!(serde_json::Value::from(keep_hashes), serde_json::json!({
+        "id": "1",
+        "boc_hash": "2",
+        "code_hash": "3",
+        "account": { "data": "te6", "balance": "4" },
+        "messages": [{ "proof": "te6", "value": "5" }],
+    }));
+}
+
+#[test]
+fn test_db_serialize_out_msg_queue_info() {
+    let name = "state_4723_0_c800000000000000_81832210A895E93967B7D2A0638159FC5FD88C1DB402545AAAABA509BE93017F";
+    let boc = read(format!("src/tests/data/states/{}", name)).unwrap();
+    let state = ShardStateUnsplit::construct_from_bytes(&boc).unwrap();
+    let ethalon = std::fs::read_to_string(format!("src/tests/data/states/{}-ethalon.json", name)).unwrap();
+    let ethalon = serde_json::from_str::<Value>(&ethalon).unwrap();
+
+    let info = db_serialize_out_msg_queue_info(
+        &state.read_out_msg_queue_info().unwrap(),
+        SerializationMode::Standart
+    ).unwrap();
+    assert_eq!(Value::from(info), ethalon["out_msg_queue_info"]);
+}
+
+#[test]
+fn test_db_serialize_shard_hashes() {
+    let boc = read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let set = BlockSerializationSet {
+        id: block.hash().unwrap(),
+        block: block.clone(),
+        status: BlockProcessingStatus::Finalized,
+        boc,
+    };
+    let doc = db_serialize_block("id", &set).unwrap();
+    let master = block.read_extra().unwrap().read_custom().unwrap().unwrap();
+    let map = db_serialize_shard_hashes(master.hashes(), SerializationMode::Standart).unwrap();
+    for field in ["shard_hashes", "min_shard_gen_utime", "max_shard_gen_utime"] {
+        assert_eq!(map[field], doc["master"][field], "{}", field);
+    }
+}
+
+#[test]
+fn test_shard_descr_gen_utime_ms() {
+    let mut descr = ShardDescr {
+        gen_utime: 1_700_000_000,
+        ..Default::default()
+    };
+    let doc = serialize_shard_descr(&descr, SerializationMode::Standart.into()).unwrap();
+    assert!(doc.get("gen_utime_ms").is_none());
+
+    descr.gen_utime_ms_part = 250;
+    let doc = serialize_shard_descr(&descr, SerializationMode::Standart.into()).unwrap();
+    assert_eq!(doc["gen_utime"], 1_700_000_000u32);
+    assert_eq!(doc["gen_utime_ms"], 1_700_000_000_250u64);
+}
+
+#[test]
+fn test_db_serialize_shard_hashes_with_outcome() {
+    let boc = read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let mc_seq_no = block.read_info().unwrap().seq_no();
+    let master = block.read_extra().unwrap().read_custom().unwrap().unwrap();
+    let plain = db_serialize_shard_hashes(master.hashes(), SerializationMode::Standart).unwrap();
+
+    let map = db_serialize_shard_hashes_with_outcome(
+        master.hashes(), None, mc_seq_no, SerializationMode::Standart
+    ).unwrap();
+    let shards = map["shard_hashes"].as_array().unwrap();
+    assert_eq!(shards.len(), plain["shard_hashes"].as_array().unwrap().len());
+    for shard in shards {
+        let descr = &shard["descr"];
+        assert!(descr.get("seq_no_advanced").is_none());
+        assert_eq!(
+            descr["mc_blocks_since_update"].as_u64().unwrap(),
+            (mc_seq_no - descr["reg_mc_seqno"].as_u64().unwrap() as u32) as u64
+        );
+    }
+
+    let map = db_serialize_shard_hashes_with_outcome(
+        master.hashes(), Some(&ShardHashes::default()), mc_seq_no, SerializationMode::Standart
+    ).unwrap();
+    for shard in map["shard_hashes"].as_array().unwrap() {
+        assert_eq!(shard["descr"]["seq_no_advanced"], true);
+    }
+
+    let map = db_serialize_shard_hashes_with_outcome(
+        master.hashes(), Some(master.hashes()), mc_seq_no, SerializationMode::Standart
+    ).unwrap();
+    for shard in map["shard_hashes"].as_array().unwrap() {
+        assert_eq!(shard["descr"]["seq_no_advanced"], false);
+    }
+}
+
+#[test]
+fn test_db_serialize_value_flow() {
+    let boc = read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let set = BlockSerializationSet {
+        id: block.hash().unwrap(),
+        block: block.clone(),
+        status: BlockProcessingStatus::Finalized,
+        boc,
+    };
+    for mode in [SerializationMode::Standart, SerializationMode::QServer] {
+        let doc = db_serialize_block_ex("id", &set, mode).unwrap();
+        let value_flow = db_serialize_value_flow(&block.read_value_flow().unwrap(), mode).unwrap();
+        assert_eq!(value_flow, doc["value_flow"]);
+    }
+}
+
+#[test]
+fn test_db_serialize_block_info() {
+    let boc = read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let set = BlockSerializationSet {
+        id: block.hash().unwrap(),
+        block: block.clone(),
+        status: BlockProcessingStatus::Finalized,
+        boc,
+    };
+    let doc = db_serialize_block("id", &set).unwrap();
+    let header = db_serialize_block_info("id", &block, SerializationMode::Standart).unwrap();
+    for (field, value) in &header {
+        assert_eq!(value, &doc[field], "{}", field);
+    }
+    for field in ["value_flow", "in_msg_descr", "account_blocks", "master", "boc"] {
+        assert!(header.get(field).is_none(), "{}", field);
+    }
+}
+
+#[test]
+fn test_serialize_currency_id() {
+    let key = SliceData::load_builder(0xfffffffeu32.write_to_new_cell().unwrap()).unwrap();
+    assert_eq!(serialize_currency_id(key).unwrap(), serde_json::json!(0xfffffffeu32));
+    let key = SliceData::load_builder(0x1_0000_0001u64.write_to_new_cell().unwrap()).unwrap();
+    assert_eq!(serialize_currency_id(key).unwrap(), serde_json::json!("4294967297"));
+
+    let mut ecc = ExtraCurrencyCollection::default();
+    ecc.set(&u32::MAX, &VarUInteger32::from_two_u128(0, 100).unwrap()).unwrap();
+    let other = serialize_ecc(&ecc, SerializationMode::QServer.into()).unwrap();
+    assert_eq!(other[0]["currency"], u32::MAX);
+}
+
+#[test]
+fn test_db_serialize_validator_set() {
+    let boc = read("src/tests/data/9C9906A80D020952E0192DC60C0B2BF1F55FE9A9E065606E8FE25C08BD1AA6B2.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let config = block.read_extra().unwrap().read_custom().unwrap().unwrap().config().cloned().unwrap();
+    let map = db_serialize_config(&config, SerializationMode::QServer).unwrap();
+    let set = db_serialize_validator_set(&config.validator_set().unwrap(), SerializationMode::QServer).unwrap();
+    assert_eq!(Value::from(set), map["config"]["p34"]);
+}
+
+#[test]
+fn test_db_serialize_libraries() {
+    let lib = SliceData::new(vec![0xAB, 0xCD, 0x80]).into_cell();
+    let mut descr = LibDescr::new(lib.clone());
+    descr.publishers_mut().set(&UInt256::with_array([1; 32]), &()).unwrap();
+    let mut state = ShardStateUnsplit::with_ident(ShardIdent::masterchain());
+    state.libraries_mut().set(&lib.repr_hash(), &descr).unwrap();
+
+    let libraries = db_serialize_libraries(state.libraries(), SerializationMode::Standart).unwrap();
+    assert_eq!(libraries[0]["hash"], lib.repr_hash().as_hex_string());
+    assert_eq!(libraries[0]["publishers"], serde_json::json!([UInt256::with_array([1; 32]).as_hex_string()]));
+    let boc = base64_decode(libraries[0]["lib"].as_str().unwrap()).unwrap();
+    assert_eq!(read_single_root_boc(boc).unwrap(), lib);
+
+    let doc = db_serialize_shard_state_unsplit("id", &state, SerializationMode::Standart).unwrap();
+    assert_eq!(libraries, doc["libraries"]);
+}
+
+#[test]
+fn test_db_serialize_shard_account() {
+    let account = crate::samples::sample_account();
+    let hash = UInt256::with_array([7; 32]);
+    let shard_account = ShardAccount::with_params(&account, hash.clone(), 0x1234).unwrap();
+    let doc = db_serialize_shard_account("id", &shard_account, SerializationMode::Standart).unwrap();
+    assert_eq!(doc["last_trans_hash"], hash.as_hex_string());
+    assert_eq!(doc["last_trans_lt"], "31234");
+    assert_eq!(doc["last_trans_lt_dec"], "4660");
+
+    let set = AccountSerializationSet {
+        boc: account.write_to_bytes().unwrap(),
+        account,
+        prev_code_hash: None,
+        boc1: None,
+        proof: None,
+    };
+    let account_doc = db_serialize_account("id", &set).unwrap();
+    for (field, value) in &account_doc {
+        if !field.starts_with("last_trans_lt") {
+            assert_eq!(value, &doc[field], "{}", field);
+        }
+    }
+}
+
+#[test]
+fn test_db_serialize_msg_descr() {
+    let boc = read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let set = BlockSerializationSet {
+        id: block.hash().unwrap(),
+        block: block.clone(),
+        status: BlockProcessingStatus::Finalized,
+        boc,
+    };
+    let mode = SerializationMode::QServer;
+    let doc = db_serialize_block_ex("id", &set, mode).unwrap();
+    let extra = block.read_extra().unwrap();
+    let mut in_msgs = Vec::new();
+    extra.read_in_msg_descr().unwrap().iterate_objects(|msg| {
+        in_msgs.push(db_serialize_in_msg(&msg, mode)?);
+        Ok(true)
+    }).unwrap();
+    assert_eq!(Value::from(in_msgs), doc["in_msg_descr"]);
+    let mut out_msgs = Vec::new();
+    extra.read_out_msg_descr().unwrap().iterate_objects(|msg| {
+        if let OutMsg::New(new) = &msg {
+            assert_eq!(
+                Value::from(db_serialize_envelope_msg(&new.read_out_message()?, mode)),
+                db_serialize_out_msg(&msg, mode)?["out_msg"]
+            );
+        }
+        out_msgs.push(db_serialize_out_msg(&msg, mode)?);
+        Ok(true)
+    }).unwrap();
+    assert_eq!(Value::from(out_msgs), doc["out_msg_descr"]);
+}
+
+#[test]
+fn test_db_serialize_account_block() {
+    let boc = read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let set = BlockSerializationSet {
+        id: block.hash().unwrap(),
+        block: block.clone(),
+        status: BlockProcessingStatus::Finalized,
+        boc,
+    };
+    let doc = db_serialize_block_ex("id", &set, SerializationMode::QServer).unwrap();
+    let workchain_id = block.read_info().unwrap().shard().workchain_id();
+    let mut account_blocks = Vec::new();
+    block.read_extra().unwrap().read_account_blocks().unwrap().iterate_objects(|account_block| {
+        account_blocks.push(db_serialize_account_block(&account_block, workchain_id, SerializationMode::QServer)?);
+        Ok(true)
+    }).unwrap();
+    assert!(!account_blocks.is_empty());
+    assert_eq!(serde_json::json!(account_blocks), doc["account_blocks"]);
+}
+
+#[test]
+fn test_db_serialize_state_init() {
+    let account = generate_test_account_by_init_code_hash(false);
+    let set = AccountSerializationSet {
+        boc: account.write_to_bytes().unwrap(),
+        account: account.clone(),
+        prev_code_hash: None,
+        boc1: None,
+        proof: None,
+    };
+    let doc = db_serialize_account("id", &set).unwrap();
+    let state = db_serialize_state_init(account.state_init().unwrap(), SerializationMode::Standart).unwrap();
+    assert!(state.contains_key("code"));
+    for (field, value) in &state {
+        assert_eq!(value, &doc[field], "{}", field);
+    }
+}
+
+#[test]
+fn test_db_serialize_block_brief() {
+    let boc = read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let set = BlockSerializationSet {
+        id: block.hash().unwrap(),
+        block,
+        status: BlockProcessingStatus::Finalized,
+        boc,
+    };
+    for mode in [SerializationMode::Standart, SerializationMode::QServer] {
+        let doc = db_serialize_block_ex("id", &set, mode).unwrap();
+        let brief = db_serialize_block_brief("id", &set, mode).unwrap();
+        for (field, value) in &brief {
+            assert_eq!(value, &doc[field], "{}", field);
+        }
+        assert_eq!(brief.contains_key("status_name"), matches!(mode, SerializationMode::QServer));
+        for field in ["status", "prev_ref"] {
+            assert!(brief.contains_key(field), "{}", field);
+        }
+        for field in ["boc", "in_msg_descr", "out_msg_descr", "account_blocks", "master"] {
+            assert!(brief.get(field).is_none(), "{}", field);
+        }
+    }
+}
+
+#[test]
+fn test_db_serialize_block_with_state_update() {
+    let boc = read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let set = BlockSerializationSet {
+        id: block.hash().unwrap(),
+        block,
+        status: BlockProcessingStatus::Finalized,
+        boc,
+    };
+    let doc = db_serialize_block_ex("id", &set, SerializationMode::Standart).unwrap();
+    let full = db_serialize_block_with_state_update("id", &set, SerializationMode::Standart).unwrap();
+    for (field, value) in &doc {
+        assert_eq!(value, &full[field], "{}", field);
+    }
+
+    let cell = read_single_root_boc(base64_decode(full["state_update_boc"].as_str().unwrap()).unwrap()).unwrap();
+    let state_update = MerkleUpdate::construct_from_cell(cell).unwrap();
+    assert_eq!(state_update, set.block.read_state_update().unwrap());
+    assert_eq!(full["old_hash"], state_update.old_hash.as_hex_string());
+    assert_eq!(full["new_hash"], state_update.new_hash.as_hex_string());
+}
+
+#[test]
+fn test_db_serialize_transaction_brief() {
+    let transaction = generate_tranzaction(AccountId::from([55; 32]));
+    let set = TransactionSerializationSet {
+        id: transaction.hash().unwrap(),
+        boc: transaction.write_to_bytes().unwrap(),
+        transaction,
+        status: TransactionProcessingStatus::Finalized,
+        block_id: Some(UInt256::with_array([3; 32])),
+        workchain_id: 0,
+        proof: None,
+    };
+    for mode in [SerializationMode::Standart, SerializationMode::QServer] {
+        let doc = db_serialize_transaction_ex("id", &set, mode).unwrap();
+        let brief = db_serialize_transaction_brief("id", &set, mode).unwrap();
+        for (field, value) in &brief {
+            assert_eq!(value, &doc[field], "{}", field);
+        }
+        for field in ["status_name", "tr_type_name"] {
+            assert_eq!(brief.contains_key(field), matches!(mode, SerializationMode::QServer), "{}", field);
+        }
+        for field in ["account_addr", "lt", "now", "total_fees", "aborted", "tr_type", "in_msg", "outmsg_cnt"] {
+            assert!(brief.contains_key(field), "{}", field);
+        }
+        for field in ["boc", "compute", "out_msgs", "balance_delta"] {
+            assert!(brief.get(field).is_none(), "{}", field);
+        }
+    }
+}
+
+#[test]
+fn test_message_set_from_block() {
+    let boc = read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let block_id = block.hash().unwrap();
+    block.read_extra().unwrap().read_in_msg_descr().unwrap().iterate_objects(|in_msg| {
+        let set = MessageSerializationSet::from_block(
+            &block, in_msg.message_cell()?, MessageLocation::InMsgDescr(&in_msg)
+        )?;
+        assert_eq!(set.block_id, Some(block_id.clone()));
+        assert_eq!(set.transaction_id, in_msg.transaction_cell().map(|cell| cell.repr_hash()));
+        assert_eq!(set.transaction_now, None);
+        assert_eq!(set.id, set.message.hash()?);
+        Ok(true)
+    }).unwrap();
+
+    let header = ExternalInboundMessageHeader {
+        dst: crate::samples::sample_address(0).unwrap(),
+        ..Default::default()
+    };
+    let message = Message::with_ext_in_header(header);
+    let mut transaction = Transaction::with_address_and_status(
+        AccountId::from([55; 32]),
+        AccountStatus::AccStateActive,
+    );
+    transaction.set_now(42);
+    transaction.write_in_msg(Some(&CommonMessage::Std(message.clone()))).unwrap();
+    let block = crate::samples::sample_block().unwrap();
+    let set = MessageSerializationSet::from_block(
+        &block, message.serialize().unwrap(), MessageLocation::Inbound(&transaction)
+    ).unwrap();
+    assert_eq!(set.transaction_id, Some(transaction.hash().unwrap()));
+    assert_eq!(set.transaction_now, Some(42));
+    let set = MessageSerializationSet::from_block(
+        &block, message.serialize().unwrap(), MessageLocation::Outbound(&transaction)
+    ).unwrap();
+    assert_eq!(set.transaction_id, Some(transaction.hash().unwrap()));
+    assert_eq!(set.transaction_now, None);
+}
+
+#[test]
+fn test_db_serialize_message_brief() {
+    let transaction = generate_tranzaction(AccountId::from([55; 32]));
+    let message = transaction.get_out_msg(0).unwrap().unwrap().get_std().unwrap().clone();
+    let set = MessageSerializationSet {
+        id: message.hash().unwrap(),
+        boc: message.write_to_bytes().unwrap(),
+        message,
+        block_id: None,
+        transaction_id: transaction.hash().ok(),
+        transaction_now: Some(transaction.now()),
+        status: MessageProcessingStatus::Finalized,
+        proof: None,
+    };
+    for mode in [SerializationMode::Standart, SerializationMode::QServer] {
+        let doc = db_serialize_message_ex("id", &set, mode).unwrap();
+        let brief = db_serialize_message_brief("id", &set, mode).unwrap();
+        for (field, value) in &brief {
+            assert_eq!(value, &doc[field], "{}", field);
+        }
+        for field in ["status_name", "msg_type_name"] {
+            assert_eq!(brief.contains_key(field), matches!(mode, SerializationMode::QServer), "{}", field);
+        }
+        for field in ["boc", "body", "code", "data", "library"] {
+            assert!(brief.get(field).is_none(), "{}", field);
+        }
+    }
+}
+
+#[test]
+fn test_db_serialize_zerostate() {
+    let ethalon = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json").unwrap();
+    let map = serde_json::from_str::<Map<String, Value>>(&ethalon).unwrap();
+    let state = crate::parse_state(&map).unwrap();
+    let doc = db_serialize_zerostate(&state, SerializationMode::Debug).unwrap();
+    let cell = state.serialize().unwrap();
+    assert_eq!(doc["root_hash"], cell.repr_hash().as_hex_string());
+    let boc = write_boc(&cell).unwrap();
+    assert_eq!(doc["file_hash"], UInt256::calc_file_hash(&boc).as_hex_string());
+    assert_eq!(doc["workchains"], serde_json::json!([0, 777]));
+    assert_eq!(doc["master"], map["master"]);
+    assert_eq!(crate::parse_state(&doc).unwrap(), state);
+
+    let mut state = state;
+    state.set_seq_no(1);
+    assert!(db_serialize_zerostate(&state, SerializationMode::Debug).is_err());
+}
+
+#[test]
+fn test_absent_fields_policy() {
+    let set = crate::samples::sample_message_set().unwrap();
+    let plain = db_serialize_message("id", &set).unwrap();
+
+    let mut doc = plain.clone();
+    apply_absent_fields_policy(&mut doc, "message", AbsentFieldsPolicy::NullAbsent);
+    let (_, fields) = OPTIONAL_FIELDS.iter().find(|(kind, _)| *kind == "message").unwrap();
+    for field in fields.iter() {
+        assert!(doc.contains_key(*field), "{}", field);
+    }
+    for (name, value) in &plain {
+        assert_eq!(&doc[name], value);
+    }
+    assert_eq!(crate::parse_message(&doc).unwrap(), set.message);
+
+    apply_absent_fields_policy(&mut doc, "message", AbsentFieldsPolicy::OmitAbsent);
+    assert_eq!(doc, plain);
+
+    // nested sections and embedded documents
+    let set = crate::samples::sample_transaction_set().unwrap();
+    let plain = db_serialize_transaction_deep("id", &set, SerializationMode::QServer).unwrap();
+    assert!(plain["compute"].get("exit_arg").is_none());
+    let mut doc = plain.clone();
+    apply_absent_fields_policy(&mut doc, "transaction", AbsentFieldsPolicy::NullAbsent);
+    assert_eq!(doc["compute"]["exit_arg"], Value::Null);
+    assert_eq!(doc["block_id"], Value::Null);
+    assert_eq!(doc["in_message"]["src_anycast"], Value::Null);
+    assert_eq!(crate::parse_transaction(&doc).unwrap(), set.transaction);
+    apply_absent_fields_policy(&mut doc, "transaction", AbsentFieldsPolicy::OmitAbsent);
+    assert_eq!(doc, plain);
+
+    let set = crate::samples::sample_state_set().unwrap();
+    let plain = db_serialize_shard_state("id", &set).unwrap();
+    let mut doc = plain.clone();
+    apply_absent_fields_policy(&mut doc, "shard_state", AbsentFieldsPolicy::NullAbsent);
+    assert_eq!(doc["block_id"], Value::Null);
+    apply_absent_fields_policy(&mut doc, "shard_state", AbsentFieldsPolicy::OmitAbsent);
+    assert_eq!(doc, plain);
+}
+
+#[test]
+fn test_db_serialize_transaction_deep() {
+    let set = crate::samples::sample_transaction_set().unwrap();
+    let plain = db_serialize_transaction_ex("id", &set, SerializationMode::QServer).unwrap();
+    let mut doc = db_serialize_transaction_deep("id", &set, SerializationMode::QServer).unwrap();
+
+    let in_message = doc.remove("in_message").unwrap();
+    assert_eq!(in_message["id"], plain["in_msg"]);
+    assert_eq!(in_message["transaction_id"], plain["id"]);
+    assert_eq!(crate::parse_message(in_message.as_object().unwrap()).unwrap(), crate::samples::sample_message().unwrap());
+    assert_eq!(doc.remove("out_messages").unwrap(), Value::Array(vec![]));
+    assert_eq!(doc, plain);
+
+    let transaction = generate_tranzaction(AccountId::from(crate::samples::SAMPLE_ACCOUNT_ID));
+    let cell = transaction.serialize().unwrap();
+    let set = TransactionSerializationSet {
+        id: cell.repr_hash(),
+        boc: write_boc(&cell).unwrap(),
+        transaction,
+        block_id: Some(UInt256::from([3; 32])),
+        ..Default::default()
+    };
+    let plain = db_serialize_transaction_ex("id", &set, SerializationMode::QServer).unwrap();
+    let doc = db_serialize_transaction_deep("id", &set, SerializationMode::QServer).unwrap();
+    let out_messages = doc["out_messages"].as_array().unwrap();
+    assert_eq!(out_messages.len(), 3);
+    for (message, id) in out_messages.iter().zip(plain["out_msgs"].as_array().unwrap()) {
+        assert_eq!(&message["id"], id);
+        assert_eq!(message["transaction_id"], plain["id"]);
+        assert_eq!(message["block_id"], plain["block_id"]);
+        assert!(message.get("boc").is_some());
+    }
+}
+
+#[test]
+fn test_anycast_addresses() {
+    let rewrite_pfx = SliceData::from_string("a_").unwrap();
+    let anycast = AnycastInfo::with_rewrite_pfx(rewrite_pfx.clone()).unwrap();
+    let dst = MsgAddressInt::with_standart(
+        Some(anycast), 0, AccountId::from(crate::samples::SAMPLE_ACCOUNT_ID)
+    ).unwrap();
+    let mut set = crate::samples::sample_message_set().unwrap();
+    set.message.set_dst(dst.clone());
+    let cell = set.message.serialize().unwrap();
+    set.id = cell.repr_hash();
+    set.boc = write_boc(&cell).unwrap();
+    let doc = db_serialize_message("id", &set).unwrap();
+    assert!(doc.get("src_anycast").is_none());
+    let dst_anycast = &doc["dst_anycast"];
+    assert_eq!(dst_anycast["depth"], rewrite_pfx.remaining_bits());
+    assert_eq!(SliceData::from_string(dst_anycast["rewrite_pfx"].as_str().unwrap()).unwrap(), rewrite_pfx);
+
+    // messages are checked against anycasts of the document and rebuilt with them
+    assert_eq!(crate::parse_message(&doc).unwrap().dst_ref(), Some(&dst));
+    let mut without_boc = doc.clone();
+    without_boc.remove("boc");
+    assert_eq!(crate::parse_message(&without_boc).unwrap().dst_ref(), Some(&dst));
+    let mut wrong = doc.clone();
+    wrong["dst_anycast"]["rewrite_pfx"] = "e_".into();
+    assert!(crate::parse_message(&wrong).is_err());
+    let mut wrong = doc.clone();
+    wrong.insert("src_anycast".to_string(), dst_anycast.clone());
+    assert!(crate::parse_message(&wrong).is_err());
+
+    let account_set = crate::samples::sample_account_set().unwrap();
+    let mut doc = db_serialize_account("id", &account_set).unwrap();
+    assert!(doc.get("id_anycast").is_none());
+    doc.remove("boc");
+    doc.insert("id".to_string(), crate::samples::sample_address(0).unwrap().to_string().into());
+    doc.insert("id_anycast".to_string(), dst_anycast.clone());
+    let account = crate::parse_account(&doc).unwrap();
+    assert_eq!(account.get_addr(), Some(&dst));
+
+    doc["id_anycast"]["depth"] = 5.into();
+    assert!(crate::parse_account(&doc).is_err());
+}
+
+#[test]
+fn test_message_comment() {
+    let mut set = crate::samples::sample_message_set().unwrap();
+    let mut doc = db_serialize_message("id", &set).unwrap();
+    serialize_message_comment(&mut doc, &set.message).unwrap();
+    assert!(doc.get("op").is_none());
+
+    let mut tail = BuilderData::new();
+    tail.append_raw("ks 🙏".as_bytes(), 56).unwrap();
+    let mut body = BuilderData::new();
+    body.append_u32(0).unwrap();
+    body.append_raw(b"than", 32).unwrap();
+    body.checked_append_reference(tail.into_cell().unwrap()).unwrap();
+    set.message.set_body(SliceData::load_builder(body).unwrap());
+    let mut doc = Map::new();
+    serialize_message_comment(&mut doc, &set.message).unwrap();
+    assert_eq!(doc["op"], 0);
+    assert_eq!(doc["comment"], "thanks 🙏");
+
+    let mut body = BuilderData::new();
+    body.append_u32(0x0f8a7ea5).unwrap();
+    body.append_raw(b"data", 32).unwrap();
+    set.message.set_body(SliceData::load_builder(body).unwrap());
+    let mut doc = Map::new();
+    serialize_message_comment(&mut doc, &set.message).unwrap();
+    assert_eq!(doc["op"], 0x0f8a7ea5);
+    assert!(doc.get("comment").is_none());
+}
+
+#[test]
+fn test_block_stats() {
+    let boc = read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let mut transactions = 0;
+    let mut total_fees = 0;
+    block.read_extra().unwrap().read_account_blocks().unwrap().iterate_objects(|account_block| {
+        account_block.transactions().iterate_objects(|InRefValue(transaction)| {
+            transactions += 1;
+            total_fees += transaction.total_fees().grams.as_u128();
+            Ok(true)
+        })
+    }).unwrap();
+
+    let mut doc = Map::new();
+    serialize_block_stats(&mut doc, &block, SerializationMode::Standart).unwrap();
+    let stats = &doc["stats"];
+    let counted = stats["transactions"].as_object().unwrap().values()
+        .map(|count| count.as_u64().unwrap())
+        .sum::<u64>();
+    assert_eq!(counted, transactions);
+    assert!(stats["messages"].as_object().unwrap().keys().all(|name| ["internal", "extIn", "extOut"].contains(&name.as_str())));
+    assert_eq!(stats["fees"]["total_dec"], total_fees.to_string());
+
+    let mut doc = Map::new();
+    serialize_block_stats(&mut doc, &crate::samples::sample_block().unwrap(), SerializationMode::QServer).unwrap();
+    assert_eq!(doc["stats"]["transactions"], serde_json::json!({}));
+    assert_eq!(doc["stats"]["fees"]["total"], "0x0");
+}
+
+#[test]
+fn test_shard_descr_collators() {
+    let mut descr = ShardDescr::default();
+    let doc = serialize_shard_descr(&descr, SerializationMode::Standart).unwrap();
+    assert!(doc.get("collators").is_none());
+    assert!(doc.get("proof_chain").is_none());
+
+    let range = |collator, start, finish| CollatorRange { collator, start, finish };
+    descr.collators = Some(ShardCollators {
+        prev: range(1, 100, 110),
+        prev2: None,
+        current: range(2, 111, 120),
+        next: range(3, 121, 130),
+        next2: Some(range(4, 121, 130)),
+        updated_at: 1_700_000_000,
+    });
+    let doc = serialize_shard_descr(&descr, SerializationMode::Standart).unwrap();
+    assert_eq!(doc["collators"], serde_json::json!({
+        "prev": { "collator": 1, "start": 100, "finish": 110 },
+        "current": { "collator": 2, "start": 111, "finish": 120 },
+        "next": { "collator": 3, "start": 121, "finish": 130 },
+        "next2": { "collator": 4, "start": 121, "finish": 130 },
+        "updated_at": 1_700_000_000,
+    }));
+}
+
+#[test]
+fn test_db_serialize_block_deep() {
+    let boc = read("src/tests/data/89ED400A43E76664437EFC9C79B84AC387493A9EE5E789338FF71C25F54218BE.boc").unwrap();
+    let block = Block::construct_from_bytes(&boc).unwrap();
+    let set = BlockSerializationSet {
+        block: block.clone(),
+        id: block.hash().unwrap(),
+        status: BlockProcessingStatus::Finalized,
+        boc,
+    };
+    let plain = db_serialize_block("id", &set).unwrap();
+    let doc = db_serialize_block_deep("id", &set, false, SerializationMode::Standart).unwrap();
+    let deep = db_serialize_block_deep("id", &set, true, SerializationMode::Standart).unwrap();
+    let groups = doc["account_blocks"].as_array().unwrap();
+    assert_eq!(groups.len(), plain["account_blocks"].as_array().unwrap().len());
+    let mut count = 0;
+    for (group, deep_group) in groups.iter().zip(deep["account_blocks"].as_array().unwrap()) {
+        for (item, deep_item) in group["transactions"].as_array().unwrap().iter()
+            .zip(deep_group["transactions"].as_array().unwrap())
+        {
+            let transaction = &item["transaction"];
+            assert_eq!(transaction["id"], item["transaction_id"]);
+            assert_eq!(transaction["block_id"], plain["id"]);
+            assert_eq!(transaction["account_addr"], group["account_addr"]);
+            assert!(transaction.get("out_messages").is_none());
+            assert!(deep_item["transaction"]["out_messages"].is_array());
+            count += 1;
+        }
+    }
+    assert_eq!(count, plain["tr_count"]);
+}
+
+#[test]
+fn test_other_currencies_layout() {
+    let set = crate::samples::sample_account_set().unwrap();
+    let balance = set.account.balance().unwrap();
+    let plain = db_serialize_account("id", &set).unwrap();
+    let items = plain["balance_other"].as_array().unwrap();
+    assert!(!items.is_empty());
+
+    for mode in [SerializationMode::Standart, SerializationMode::QServer] {
+        let options = SerializationOptions::new(mode)
+            .with_other_currencies_layout(OtherCurrenciesLayout::Object);
+        let mut doc = db_serialize_account_ex("id", &set, options).unwrap();
+        let other = doc["balance_other"].as_object().unwrap();
+        assert_eq!(other.len(), items.len());
+        for item in items {
+            let currency = match &item["currency"] {
+                Value::String(currency) => currency.clone(),
+                currency => currency.as_u64().unwrap().to_string(),
+            };
+            let value = &other[&currency];
+            match mode {
+                SerializationMode::Standart => assert_eq!(value, &item["value_dec"]),
+                _ => assert!(value.as_str().unwrap().starts_with("0x")),
+            }
+        }
+        if mode.is_standart() {
+            doc.remove("boc");
+            assert_eq!(crate::parse_account(&doc).unwrap().balance(), Some(balance));
+        }
+    }
+
+    // default layout
+    assert_eq!(db_serialize_account("id", &set).unwrap(), plain);
+}
+
+#[test]
+fn test_msg_routing() {
+    let message = crate::samples::sample_message().unwrap();
+    let msg_cell = message.serialize().unwrap();
+    let tr_cell = Transaction::default().serialize().unwrap();
+    let routed = |cur: u8, next: u8| {
+        let env = MsgEnvelope::with_routing(
+            msg_cell.clone(),
+            Grams::from(1_000_000u64),
+            IntermediateAddress::use_dest_bits(cur).unwrap(),
+            IntermediateAddress::use_dest_bits(next).unwrap(),
+        );
+        let in_msg = InMsg::final_msg(env.serialize().unwrap(), tr_cell.clone(), Grams::zero());
+        let mut doc = Map::new();
+        serialize_msg_routing(&mut doc, &in_msg).unwrap();
+        doc
+    };
+
+    let doc = routed(0, 96);
+    assert_eq!(doc["ihr"], false);
+    assert_eq!(doc["hop_count"], 24);
+    assert_eq!(routed(4, 12)["hop_count"], 2);
+    assert_eq!(routed(3, 9)["hop_count"], 3);
+    assert_eq!(routed(96, 96)["hop_count"], 0);
+    assert_eq!(routed(8, 0)["hop_count"], 0);
+
+    let mut doc = Map::new();
+    let ihr = InMsg::ihr(msg_cell.clone(), tr_cell.clone(), Grams::zero(), Cell::default());
+    serialize_msg_routing(&mut doc, &ihr).unwrap();
+    assert_eq!(doc["ihr"], true);
+    assert!(doc.get("hop_count").is_none());
+}
+
+/// Checks that the fields written by `format_u64` are listed in `U64_STRING_FIELDS`. They are
+/// found by `0x` values in `q_server`, the standart document tells them from grams by the
+/// length prefix.
+fn check_u64_string_fields(q_server: &Value, standart: &Value, path: &str) {
+    match (q_server, standart) {
+        (Value::Object(q_server), Value::Object(standart)) => for (name, value) in q_server {
+            let path = format!("{}/{}", path, name);
+            let Some(standart) = standart.get(name) else {
+                continue
+            };
+            match (value.as_str().and_then(|value| value.strip_prefix("0x")), standart.as_str()) {
+                (Some(hex), Some(string)) => {
+                    let is_u64 = u64::from_str_radix(hex, 16).map_or(false, |value| u64_to_string(value) == string);
+                    if is_u64 && name != "lt" && !name.ends_with("_lt") {
+                        assert!(U64_STRING_FIELDS.contains(&name.as_str()), "{} is not listed in U64_STRING_FIELDS", path);
+                    }
+                }
+                _ => check_u64_string_fields(value, standart, &path)
+            }
+        }
+        (Value::Array(q_server), Value::Array(standart)) => {
+            for (i, (value, standart)) in q_server.iter().zip(standart).enumerate() {
+                check_u64_string_fields(value, standart, &format!("{}/{}", path, i));
+            }
+        }
+        _ => ()
+    }
+}
+
+#[test]
+fn test_u64_string_fields() {
+    let ethalon = std::fs::read_to_string("src/tests/data/zerostate-ethalon.json").unwrap();
+    let zerostate = crate::parse_state(&serde_json::from_str(&ethalon).unwrap()).unwrap();
+    let block_set = crate::samples::sample_block_set().unwrap();
+    let transaction_set = crate::samples::sample_transaction_set().unwrap();
+    let message_set = crate::samples::sample_message_set().unwrap();
+    let account_set = crate::samples::sample_account_set().unwrap();
+    let state_set = crate::samples::sample_state_set().unwrap();
+    let serialize = |mode: SerializationMode| -> Vec<Map<String, Value>> {
+        vec![
+            db_serialize_block_ex("id", &block_set, mode).unwrap(),
+            db_serialize_transaction_ex("id", &transaction_set, mode).unwrap(),
+            db_serialize_message_ex("id", &message_set, mode).unwrap(),
+            db_serialize_account_ex("id", &account_set, mode).unwrap(),
+            db_serialize_shard_state_ex("id", &state_set, mode).unwrap(),
+            db_serialize_zerostate(&zerostate, mode).unwrap(),
+        ]
+    };
+    let q_server = serialize(SerializationMode::QServer);
+    let standart = serialize(SerializationMode::Standart);
+    for (q_server, standart) in q_server.into_iter().zip(standart) {
+        check_u64_string_fields(&q_server.into(), &standart.into(), "root");
+    }
+}
@@ -0,0 +1,40 @@
+/*
+ * Copyright (C) 2019-2023 EverX. All Rights Reserved.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.  You may obtain a copy of the
+ * License at:
+ *
+ * https://www.ever.dev/licenses
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific EVERX DEV software governing permissions and limitations
+ * under the License.
+ */
+
+use super::*;
+
+#[test]
+fn test_status_json() {
+    for status in MessageProcessingStatusJson::ALL {
+        let ever_status = MessageProcessingStatus::from(*status);
+        assert_eq!(status.as_u8(), ever_status as u8);
+        assert_eq!(MessageProcessingStatusJson::from(ever_status), *status);
+        assert_eq!(MessageProcessingStatusJson::from_u8(status.as_u8()).unwrap(), *status);
+        assert_eq!(MessageProcessingStatusJson::from_name(status.name()).unwrap(), *status);
+    }
+
+    let status = TransactionProcessingStatusJson::Finalized;
+    assert_eq!(serde_json::to_value(status).unwrap(), TransactionProcessingStatus::Finalized as u8);
+    assert_eq!(serde_json::from_value::<TransactionProcessingStatusJson>(
+        serde_json::json!("finalized")).unwrap(), status);
+    assert_eq!(serde_json::from_value::<TransactionProcessingStatusJson>(
+        serde_json::json!(status.as_u8())).unwrap(), status);
+
+    assert!(BlockProcessingStatusJson::from_json(&serde_json::json!(100)).is_err());
+    assert!(BlockProcessingStatusJson::from_json(&serde_json::json!(1000)).is_err());
+    assert!(BlockProcessingStatusJson::from_json(&serde_json::json!("done")).is_err());
+    assert!(BlockProcessingStatusJson::from_json(&serde_json::json!(true)).is_err());
+}